@@ -0,0 +1,424 @@
+use crate::archive_guard::{MAX_COMPRESSION_RATIO, MAX_TOTAL_BYTES, RATIO_CHECK_MIN_BYTES};
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use zip::ZipArchive;
+
+/// Archive formats packs can be shared in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    SevenZip,
+    TarGz,
+    TarXz,
+}
+
+impl ArchiveFormat {
+    /// Detects the format from the first bytes of a file, whatever its extension
+    pub fn detect(path: &Path) -> Result<Self, String> {
+        let mut file = fs::File::open(path)
+            .map_err(|e| format!("Failed to open archive: {}", e))?;
+        let mut magic = [0u8; 6];
+        let read = file.read(&mut magic)
+            .map_err(|e| format!("Failed to read archive: {}", e))?;
+        let magic = &magic[..read];
+
+        if magic.starts_with(b"PK\x03\x04") || magic.starts_with(b"PK\x05\x06") {
+            Ok(Self::Zip)
+        } else if magic.starts_with(b"7z\xBC\xAF\x27\x1C") {
+            Ok(Self::SevenZip)
+        } else if magic.starts_with(&[0x1F, 0x8B]) {
+            Ok(Self::TarGz)
+        } else if magic.starts_with(b"\xFD7zXZ\x00") {
+            Ok(Self::TarXz)
+        } else {
+            Err("Unsupported archive: expected .zip, .7z, .tar.gz or .tar.xz".to_string())
+        }
+    }
+
+    /// File extension for the format, without the leading dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::SevenZip => "7z",
+            Self::TarGz => "tar.gz",
+            Self::TarXz => "tar.xz",
+        }
+    }
+}
+
+/// One entry of an archive, as recorded in its headers
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    /// Name as stored, with '/' separators
+    pub name: String,
+    /// Name as a relative path; None if it is absolute or uses ".."
+    pub path: Option<PathBuf>,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+    pub size: u64,
+    /// Per-entry compressed size; None for tarballs and 7z, which compress across entries
+    pub compressed_size: Option<u64>,
+    pub unix_mode: Option<u32>,
+}
+
+/// Turns a stored name into a relative path, rejecting absolute and ".." names
+fn enclosed_path(name: &str) -> Option<PathBuf> {
+    if name.contains('\0') || name.starts_with('/') {
+        return None;
+    }
+    // ':' starts a drive (C:) or an alternate data stream (x.dll:evil) on Windows,
+    // so it is rejected in every component, as names::check_component does
+    if name.contains(':') {
+        return None;
+    }
+
+    let mut path = PathBuf::new();
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    Some(path)
+}
+
+fn entry(name: String, is_dir: bool, is_symlink: bool, size: u64, compressed_size: Option<u64>, unix_mode: Option<u32>) -> ArchiveEntry {
+    let mut name = name.replace('\\', "/");
+    let is_dir = is_dir || name.ends_with('/');
+    // Folders always end with '/', as zip stores them
+    if is_dir && !name.ends_with('/') {
+        name.push('/');
+    }
+
+    ArchiveEntry {
+        path: enclosed_path(&name),
+        is_dir,
+        name,
+        is_symlink,
+        size,
+        compressed_size,
+        unix_mode,
+    }
+}
+
+fn zip_entry(file: &zip::read::ZipFile) -> ArchiveEntry {
+    entry(
+        file.name().to_string(),
+        file.is_dir(),
+        file.is_symlink(),
+        file.size(),
+        Some(file.compressed_size()),
+        file.unix_mode(),
+    )
+}
+
+fn tar_entry<R: Read>(file: &tar::Entry<R>) -> ArchiveEntry {
+    let header = file.header();
+    let kind = header.entry_type();
+    entry(
+        String::from_utf8_lossy(&file.path_bytes()).to_string(),
+        kind.is_dir(),
+        kind.is_symlink() || kind.is_hard_link(),
+        file.size(),
+        None,
+        header.mode().ok(),
+    )
+}
+
+fn sevenz_entry(file: &sevenz_rust::SevenZArchiveEntry) -> ArchiveEntry {
+    // High 16 bits hold the unix mode when this flag is set
+    const UNIX_EXTENSION: u32 = 0x8000;
+    const REPARSE_POINT: u32 = 0x400;
+
+    let attributes = if file.has_windows_attributes { file.windows_attributes } else { 0 };
+    let unix_mode = (attributes & UNIX_EXTENSION != 0).then_some(attributes >> 16);
+    let is_symlink = attributes & REPARSE_POINT != 0
+        || unix_mode.is_some_and(|mode| mode & 0o170000 == 0o120000);
+
+    entry(file.name().to_string(), file.is_directory(), is_symlink, file.size(), None, unix_mode)
+}
+
+/// A listing kept for reuse: path, size, mtime, format and entries
+type Listing = (PathBuf, u64, SystemTime, ArchiveFormat, Vec<ArchiveEntry>);
+
+/// Recently listed archives, so the layout, preflight, conflict and plan steps
+/// of one install don't each decompress a tarball again
+static LISTINGS: Mutex<Vec<Listing>> = Mutex::new(Vec::new());
+
+/// How many listings are kept
+const MAX_CACHED_LISTINGS: usize = 4;
+
+/// Fails once a tarball's listing declares more than the guard would allow
+/// Tarballs have no index, so listing decompresses the stream; stopping here
+/// keeps a tar bomb from being inflated in full before archive_guard sees it.
+fn check_listed_size(total_bytes: u64, packed_size: u64) -> Result<(), String> {
+    if total_bytes > MAX_TOTAL_BYTES {
+        return Err(format!(
+            "Archive expands to more than the {} MB limit",
+            MAX_TOTAL_BYTES / (1024 * 1024)
+        ));
+    }
+    if total_bytes >= RATIO_CHECK_MIN_BYTES && total_bytes / packed_size.max(1) > MAX_COMPRESSION_RATIO {
+        return Err(format!(
+            "Archive expands more than {}x, the compression ratio limit",
+            MAX_COMPRESSION_RATIO
+        ));
+    }
+    Ok(())
+}
+
+/// A pack archive in any supported format
+/// Entries are listed once on open, from headers where the format has them;
+/// contents are streamed in archive order.
+pub struct Archive {
+    path: PathBuf,
+    format: ArchiveFormat,
+    entries: Vec<ArchiveEntry>,
+}
+
+impl Archive {
+    /// Opens an archive and reads its entry list
+    /// The listing is reused while the file's size and mtime are unchanged.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let metadata = fs::metadata(path)
+            .map_err(|e| format!("Failed to open archive: {}", e))?;
+        let size = metadata.len();
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+
+        let cached = LISTINGS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|(p, s, m, _, _)| p == path && *s == size && *m == modified)
+            .map(|(_, _, _, format, entries)| (*format, entries.clone()));
+        if let Some((format, entries)) = cached {
+            return Ok(Self {
+                path: path.to_path_buf(),
+                format,
+                entries,
+            });
+        }
+
+        let format = ArchiveFormat::detect(path)?;
+        let mut archive = Self {
+            path: path.to_path_buf(),
+            format,
+            entries: Vec::new(),
+        };
+        archive.entries = archive.list(size)?;
+
+        let mut listings = LISTINGS.lock().unwrap_or_else(|e| e.into_inner());
+        listings.retain(|(p, _, _, _, _)| p != path);
+        if listings.len() >= MAX_CACHED_LISTINGS {
+            listings.remove(0);
+        }
+        listings.push((path.to_path_buf(), size, modified, format, archive.entries.clone()));
+
+        Ok(archive)
+    }
+
+    /// Reads the entry list without extracting anything
+    fn list(&self, packed_size: u64) -> Result<Vec<ArchiveEntry>, String> {
+        match self.format {
+            // 7z keeps every entry in its header block
+            ArchiveFormat::SevenZip => {
+                let reader = sevenz_rust::SevenZReader::open(&self.path, sevenz_rust::Password::empty())
+                    .map_err(|e| format!("Failed to read 7z archive: {}", e))?;
+                Ok(reader
+                    .archive()
+                    .files
+                    .iter()
+                    .filter(|file| !file.name().is_empty())
+                    .map(sevenz_entry)
+                    .collect())
+            }
+            // Zip reads its central directory; entries are not inflated unless read
+            ArchiveFormat::Zip => {
+                let mut entries = Vec::new();
+                self.for_each_entry(|entry, _| {
+                    entries.push(entry.clone());
+                    Ok(())
+                })?;
+                Ok(entries)
+            }
+            ArchiveFormat::TarGz | ArchiveFormat::TarXz => {
+                let mut entries = Vec::new();
+                let mut total_bytes: u64 = 0;
+                self.for_each_entry(|entry, _| {
+                    total_bytes = total_bytes.saturating_add(entry.size);
+                    check_listed_size(total_bytes, packed_size)?;
+                    entries.push(entry.clone());
+                    Ok(())
+                })?;
+                Ok(entries)
+            }
+        }
+    }
+
+    pub fn format(&self) -> ArchiveFormat {
+        self.format
+    }
+
+    pub fn entries(&self) -> &[ArchiveEntry] {
+        &self.entries
+    }
+
+    /// Size of the archive file itself
+    pub fn packed_size(&self) -> u64 {
+        fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0)
+    }
+
+    fn open_file(&self) -> Result<fs::File, String> {
+        fs::File::open(&self.path).map_err(|e| format!("Failed to open archive: {}", e))
+    }
+
+    /// Streams every entry in archive order, with a reader over its contents
+    /// Readers of directories and symlinks should not be read.
+    pub fn for_each_entry(
+        &self,
+        mut visit: impl FnMut(&ArchiveEntry, &mut dyn Read) -> Result<(), String>,
+    ) -> Result<(), String> {
+        match self.format {
+            ArchiveFormat::Zip => {
+                let mut archive = ZipArchive::new(self.open_file()?)
+                    .map_err(|e| format!("Failed to read zip archive: {}", e))?;
+
+                for i in 0..archive.len() {
+                    let mut file = archive.by_index(i)
+                        .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+                    let entry = zip_entry(&file);
+                    visit(&entry, &mut file)?;
+                }
+                Ok(())
+            }
+            ArchiveFormat::TarGz => {
+                let decoder = flate2::read::GzDecoder::new(BufReader::new(self.open_file()?));
+                for_each_tar_entry(decoder, visit)
+            }
+            ArchiveFormat::TarXz => {
+                let decoder = xz2::read::XzDecoder::new(BufReader::new(self.open_file()?));
+                for_each_tar_entry(decoder, visit)
+            }
+            ArchiveFormat::SevenZip => {
+                let mut reader = sevenz_rust::SevenZReader::open(&self.path, sevenz_rust::Password::empty())
+                    .map_err(|e| format!("Failed to read 7z archive: {}", e))?;
+
+                // The 7z reader only takes its own error type, so ours is carried out here
+                let mut failure = None;
+                reader
+                    .for_each_entries(|file, contents| {
+                        // Some writers store the folder they compressed as an unnamed entry
+                        if file.name().is_empty() {
+                            return Ok(true);
+                        }
+                        let entry = sevenz_entry(file);
+                        match visit(&entry, contents) {
+                            Ok(()) => Ok(true),
+                            Err(e) => {
+                                failure = Some(e);
+                                Ok(false)
+                            }
+                        }
+                    })
+                    .map_err(|e| format!("Failed to read 7z archive: {}", e))?;
+
+                failure.map_or(Ok(()), Err)
+            }
+        }
+    }
+}
+
+fn for_each_tar_entry(
+    reader: impl Read,
+    mut visit: impl FnMut(&ArchiveEntry, &mut dyn Read) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut archive = tar::Archive::new(reader);
+    let files = archive.entries()
+        .map_err(|e| format!("Failed to read tar archive: {}", e))?;
+
+    for file in files {
+        let mut file = file.map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        let entry = tar_entry(&file);
+        visit(&entry, &mut file)?;
+    }
+
+    Ok(())
+}
+
+/// Copies an entry's contents, failing if they don't match the size it declares
+/// Never writes more than declared, whatever the stream yields.
+pub fn copy_entry(entry: &ArchiveEntry, contents: &mut dyn Read, out: &mut impl io::Write) -> Result<u64, String> {
+    let written = io::copy(&mut contents.take(entry.size + 1), out)
+        .map_err(|e| format!("Failed to extract {}: {}", entry.name, e))?;
+
+    if written != entry.size {
+        return Err(format!(
+            "Extracted size of {} does not match the archive ({} of {} bytes)",
+            entry.name, written, entry.size
+        ));
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::io::Write;
+
+    /// Writes a .tar.gz holding one file of `size` zero bytes
+    fn write_tar_gz(path: &Path, name: &str, size: usize) {
+        let file = fs::File::create(path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::best());
+        let mut builder = tar::Builder::new(encoder);
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(size as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, io::repeat(0).take(size as u64)).unwrap();
+        builder.into_inner().unwrap().finish().unwrap().flush().unwrap();
+    }
+
+    #[test]
+    fn lists_tarball_entries_and_reuses_the_listing() {
+        let dir = TempDir::new("archive_listing");
+        let path = dir.join("pack.tar.gz");
+        write_tar_gz(&path, "CustomStadium/a.bundle", 1000);
+
+        let archive = Archive::open(&path).unwrap();
+        assert_eq!(archive.format(), ArchiveFormat::TarGz);
+        assert_eq!(archive.entries().len(), 1);
+        assert_eq!(archive.entries()[0].name, "CustomStadium/a.bundle");
+        assert_eq!(archive.entries()[0].size, 1000);
+
+        let cached = LISTINGS.lock().unwrap().iter().any(|(p, _, _, _, _)| p == &path);
+        assert!(cached);
+        assert_eq!(Archive::open(&path).unwrap().entries().len(), 1);
+    }
+
+    #[test]
+    fn stops_listing_a_tar_bomb() {
+        let dir = TempDir::new("archive_bomb");
+        let path = dir.join("bomb.tar.gz");
+        write_tar_gz(&path, "zeros.bin", 8 * 1024 * 1024);
+
+        let error = Archive::open(&path).err().unwrap();
+        assert!(error.contains("compression ratio"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_unsafe_names() {
+        assert_eq!(enclosed_path("a/./b"), Some(PathBuf::from("a/b")));
+        assert_eq!(enclosed_path("../evil"), None);
+        assert_eq!(enclosed_path("/etc/passwd"), None);
+        assert_eq!(enclosed_path("C:/Windows/x"), None);
+        assert_eq!(enclosed_path("BepInEx/plugins/x.dll:evil"), None);
+    }
+}
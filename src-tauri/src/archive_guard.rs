@@ -0,0 +1,213 @@
+use crate::archive::Archive;
+use crate::layout::entry_relative_path;
+use crate::models::{ArchiveViolation, ArchiveViolationKind};
+use std::collections::HashMap;
+use std::path::Component;
+
+/// Largest total uncompressed size accepted from one archive
+pub const MAX_TOTAL_BYTES: u64 = 16 * 1024 * 1024 * 1024;
+
+/// Largest uncompressed:compressed ratio for a single entry, or for the whole
+/// archive when the format doesn't record per-entry compressed sizes
+pub const MAX_COMPRESSION_RATIO: u64 = 200;
+
+/// Entries below this size may compress as well as they like (text, configs)
+pub const RATIO_CHECK_MIN_BYTES: u64 = 1024 * 1024;
+
+fn violation(kind: ArchiveViolationKind, entry: &str, message: String) -> ArchiveViolation {
+    ArchiveViolation {
+        kind,
+        entry: entry.to_string(),
+        message,
+    }
+}
+
+/// Checks every entry of an archive before anything is extracted
+/// Rejects symlinks, zip bombs, duplicate or case-colliding paths and names
+/// that would land outside the target once the prefix is stripped.
+pub fn check_archive(archive: &Archive, strip_prefix: &str) -> Vec<ArchiveViolation> {
+    let mut violations = Vec::new();
+    let mut total_bytes: u64 = 0;
+    // Lowercased path -> name as it appears in the archive
+    let mut seen: HashMap<String, String> = HashMap::new();
+
+    for entry in archive.entries() {
+        let name = &entry.name;
+
+        if entry.is_symlink {
+            violations.push(violation(
+                ArchiveViolationKind::Symlink,
+                name,
+                format!("{} is a symbolic link", name),
+            ));
+            continue;
+        }
+
+        // Names that are absolute, use "..", contain ':' or escape once the prefix is stripped
+        let relative = entry_relative_path(entry, strip_prefix);
+        let escapes = entry.path.is_none()
+            || relative
+                .as_ref()
+                .is_some_and(|r| r.components().any(|c| !matches!(c, Component::Normal(_))));
+        if escapes {
+            violations.push(violation(
+                ArchiveViolationKind::OutsideTarget,
+                name,
+                format!("{} would be written outside the install folder", name),
+            ));
+            continue;
+        }
+
+        if entry.is_dir {
+            continue;
+        }
+
+        let size = entry.size;
+        total_bytes = total_bytes.saturating_add(size);
+
+        if let Some(compressed) = entry.compressed_size {
+            if size >= RATIO_CHECK_MIN_BYTES && size / compressed.max(1) > MAX_COMPRESSION_RATIO {
+                violations.push(violation(
+                    ArchiveViolationKind::CompressionRatio,
+                    name,
+                    format!("{} expands {}x, more than the {}x limit", name, size / compressed.max(1), MAX_COMPRESSION_RATIO),
+                ));
+            }
+        }
+
+        let Some(relative) = relative else {
+            continue;
+        };
+        let path = relative.to_string_lossy().replace('\\', "/");
+        let key = path.to_lowercase();
+
+        match seen.get(&key) {
+            Some(previous) if *previous == path => violations.push(violation(
+                ArchiveViolationKind::Duplicate,
+                name,
+                format!("{} appears more than once", path),
+            )),
+            Some(previous) => violations.push(violation(
+                ArchiveViolationKind::CaseCollision,
+                name,
+                format!("{} and {} differ only by case", previous, path),
+            )),
+            None => {
+                seen.insert(key, path);
+            }
+        }
+    }
+
+    // Tarballs and 7z compress across entries, so only the whole archive can be measured
+    let per_entry = archive.entries().iter().all(|e| e.is_dir || e.compressed_size.is_some());
+    let packed = archive.packed_size().max(1);
+    if !per_entry && total_bytes >= RATIO_CHECK_MIN_BYTES && total_bytes / packed > MAX_COMPRESSION_RATIO {
+        violations.push(violation(
+            ArchiveViolationKind::CompressionRatio,
+            "",
+            format!("Archive expands {}x, more than the {}x limit", total_bytes / packed, MAX_COMPRESSION_RATIO),
+        ));
+    }
+
+    if total_bytes > MAX_TOTAL_BYTES {
+        violations.push(violation(
+            ArchiveViolationKind::TotalSize,
+            "",
+            format!(
+                "Archive expands to {} MB, more than the {} MB limit",
+                total_bytes / (1024 * 1024),
+                MAX_TOTAL_BYTES / (1024 * 1024)
+            ),
+        ));
+    }
+
+    violations
+}
+
+/// Turns violations into a single error message
+pub fn ensure_safe(violations: &[ArchiveViolation]) -> Result<(), String> {
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    let messages: Vec<&str> = violations.iter().map(|v| v.message.as_str()).collect();
+    Err(format!("Archive rejected:\n{}", messages.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::fs;
+    use std::io::Write;
+    use std::path::Path;
+    use zip::write::SimpleFileOptions;
+
+    /// Writes a zip holding each (name, contents) pair, deflated
+    fn write_zip(path: &Path, files: &[(&str, Vec<u8>)]) {
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, contents) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn check(name: &str, files: &[(&str, Vec<u8>)], strip_prefix: &str) -> Vec<ArchiveViolation> {
+        let dir = TempDir::new(&format!("archive_guard_{}", name));
+        let path = dir.join("pack.zip");
+        write_zip(&path, files);
+
+        check_archive(&Archive::open(&path).unwrap(), strip_prefix)
+    }
+
+    fn kinds(violations: &[ArchiveViolation]) -> Vec<String> {
+        violations.iter().map(|v| format!("{:?}", v.kind)).collect()
+    }
+
+    #[test]
+    fn accepts_a_plain_pack() {
+        let violations = check(
+            "plain",
+            &[("Pack/BepInEx/core/BepInEx.dll", b"dll".to_vec()), ("Pack/winhttp.dll", b"dll".to_vec())],
+            "Pack/",
+        );
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    #[test]
+    fn rejects_names_escaping_the_target() {
+        let violations = check("traversal", &[("../evil.dll", b"x".to_vec()), ("ok.txt", b"x".to_vec())], "");
+        assert_eq!(kinds(&violations), ["OutsideTarget"]);
+        assert_eq!(violations[0].entry, "../evil.dll");
+    }
+
+    #[test]
+    fn rejects_entries_compressing_too_well() {
+        let zeros = vec![0; 4 * RATIO_CHECK_MIN_BYTES as usize];
+        let violations = check("bomb", &[("zeros.bin", zeros), ("small.cfg", vec![0; 1000])], "");
+        assert_eq!(kinds(&violations), ["CompressionRatio"]);
+        assert_eq!(violations[0].entry, "zeros.bin");
+    }
+
+    #[test]
+    fn rejects_paths_differing_only_by_case() {
+        let violations = check(
+            "case",
+            &[("BepInEx/config/a.cfg", b"x".to_vec()), ("BepInEx/Config/A.cfg", b"y".to_vec())],
+            "",
+        );
+        assert_eq!(kinds(&violations), ["CaseCollision"]);
+        assert!(violations[0].message.contains("differ only by case"), "{}", violations[0].message);
+    }
+
+    #[test]
+    fn joins_violations_into_one_error() {
+        assert_eq!(ensure_safe(&[]), Ok(()));
+
+        let violations = check("error", &[("../a", b"x".to_vec()), ("../b", b"x".to_vec())], "");
+        let error = ensure_safe(&violations).unwrap_err();
+        assert_eq!(error.lines().count(), 3, "{}", error);
+    }
+}
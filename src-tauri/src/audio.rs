@@ -1,10 +1,13 @@
-use crate::models::{AudioFolderStatus, AudioMapping, Fm26Installation};
+use crate::models::{AudioFolderStatus, AudioMapping, NameError, NameField};
+use crate::names::{self, NameChecks};
+use crate::registry;
 use std::fs;
 use std::path::Path;
 
 /// Reads audio mappings from AudioMappings.txt
 #[tauri::command]
-pub fn read_audio_mappings(install: Fm26Installation) -> Result<Vec<AudioMapping>, String> {
+pub fn read_audio_mappings(app_handle: tauri::AppHandle, install_id: String) -> Result<Vec<AudioMapping>, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let mappings_path = Path::new(&install.audio_inject_path).join("AudioMappings.txt");
 
     if !mappings_path.exists() {
@@ -14,6 +17,11 @@ pub fn read_audio_mappings(install: Fm26Installation) -> Result<Vec<AudioMapping
     let content = fs::read_to_string(&mappings_path)
         .map_err(|e| format!("Failed to read AudioMappings.txt: {}", e))?;
 
+    Ok(parse_audio_mappings(&content))
+}
+
+/// Parses AudioMappings.txt, skipping comments and malformed lines
+pub fn parse_audio_mappings(content: &str) -> Vec<AudioMapping> {
     let mut mappings = Vec::new();
 
     for line in content.lines() {
@@ -33,15 +41,27 @@ pub fn read_audio_mappings(install: Fm26Installation) -> Result<Vec<AudioMapping
         }
     }
 
-    Ok(mappings)
+    mappings
 }
 
 /// Writes audio mappings to AudioMappings.txt
 #[tauri::command]
 pub fn write_audio_mappings(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
     mappings: Vec<AudioMapping>,
-) -> Result<(), String> {
+) -> Result<(), NameError> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+
+    // Reject names that would escape AudioInject or break the file, row by row
+    let mut checks = NameChecks::default();
+    for (row, mapping) in mappings.iter().enumerate() {
+        let row = Some(row as u32);
+        checks.check(NameField::TeamKey, row, &mapping.team_key, names::check_team_key(&mapping.team_key));
+        checks.check(NameField::FolderName, row, &mapping.folder_name, names::check_component(&mapping.folder_name));
+    }
+    checks.finish()?;
+
     let audio_inject_path = Path::new(&install.audio_inject_path);
 
     // Create directory if it doesn't exist
@@ -62,7 +82,7 @@ pub fn write_audio_mappings(
     }
 
     // Write to file
-    let mappings_path = audio_inject_path.join("AudioMappings.txt");
+    let mappings_path = registry::install_file(&install, &audio_inject_path.join("AudioMappings.txt"))?;
     fs::write(&mappings_path, content)
         .map_err(|e| format!("Failed to write AudioMappings.txt: {}", e))?;
 
@@ -71,7 +91,8 @@ pub fn write_audio_mappings(
 
 /// Lists all audio folders (subdirectories) in the AudioInject folder
 #[tauri::command]
-pub fn list_audio_folders(install: Fm26Installation) -> Result<Vec<String>, String> {
+pub fn list_audio_folders(app_handle: tauri::AppHandle, install_id: String) -> Result<Vec<String>, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let audio_inject_path = Path::new(&install.audio_inject_path);
 
     if !audio_inject_path.exists() {
@@ -102,25 +123,42 @@ pub fn list_audio_folders(install: Fm26Installation) -> Result<Vec<String>, Stri
     Ok(folders)
 }
 
+/// Sounds every audio folder must have, as .wav or .ogg
+pub const REQUIRED_SOUNDS: [&str; 3] = ["anthem", "goal_home", "goal_away"];
+
+/// Checks whether an audio folder has a sound, as .wav or .ogg
+pub fn has_sound(folder_path: &Path, name: &str) -> bool {
+    ["wav", "ogg"]
+        .iter()
+        .any(|ext| folder_path.join(format!("{}.{}", name, ext)).exists())
+}
+
 /// Inspects an audio folder for required files
 #[tauri::command]
 pub fn inspect_audio_folder(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
     folder_name: String,
-) -> Result<AudioFolderStatus, String> {
-    let folder_path = Path::new(&install.audio_inject_path).join(&folder_name);
+) -> Result<AudioFolderStatus, NameError> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+
+    let mut checks = NameChecks::default();
+    checks.check(NameField::FolderName, None, &folder_name, names::check_component(&folder_name));
+    checks.finish()?;
+
+    let folder_path = registry::ensure_inside(
+        Path::new(&install.root_path),
+        &Path::new(&install.audio_inject_path).join(&folder_name),
+    )?;
 
     if !folder_path.exists() {
-        return Err(format!("Audio folder does not exist: {}", folder_name));
+        return Err(format!("Audio folder does not exist: {}", folder_name).into());
     }
 
     // Check for required files
-    let anthem_exists = folder_path.join("anthem.wav").exists()
-        || folder_path.join("anthem.ogg").exists();
-    let goal_home_exists = folder_path.join("goal_home.wav").exists()
-        || folder_path.join("goal_home.ogg").exists();
-    let goal_away_exists = folder_path.join("goal_away.wav").exists()
-        || folder_path.join("goal_away.ogg").exists();
+    let anthem_exists = has_sound(&folder_path, "anthem");
+    let goal_home_exists = has_sound(&folder_path, "goal_home");
+    let goal_away_exists = has_sound(&folder_path, "goal_away");
 
     // List other audio files
     let mut other_files = Vec::new();
@@ -0,0 +1,429 @@
+use crate::{bepinex, preflight, registry};
+use crate::hash::sha256_bytes;
+use crate::models::{BackupInfo, BackupLocation, BackupSettings};
+use chrono::{Local, NaiveDateTime, TimeZone};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Backups are named BepInEx_backup_<timestamp>
+pub const BACKUP_PREFIX: &str = "BepInEx_backup_";
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+const SETTINGS_FILE: &str = "backup_settings.json";
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+/// Loads backup settings, falling back to defaults
+pub fn load_settings(app: &AppHandle) -> BackupSettings {
+    settings_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Checks a custom backup folder: absolute, and outside the game folder it backs up
+/// Backups inside the folder would be swept up by the next backup or uninstall.
+fn check_custom_location(path: &Path, root: &Path) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err(format!("Backup folder must be an absolute path: {}", path.display()));
+    }
+    if registry::ensure_inside(root, path).is_ok() {
+        return Err(format!("Backup folder {} is inside the game folder", path.display()));
+    }
+    Ok(())
+}
+
+/// Folder outside the game root for one installation's backups
+/// Each install gets its own subfolder, keyed by a hash of its root path.
+fn external_dir(app: &AppHandle, settings: &BackupSettings, root: &Path) -> Result<Option<PathBuf>, String> {
+    let base = match &settings.location {
+        BackupLocation::GameFolder => return Ok(None),
+        BackupLocation::AppData => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+            .join("backups"),
+        BackupLocation::Custom { path } => {
+            let path = PathBuf::from(path);
+            check_custom_location(&path, root)?;
+            path
+        }
+    };
+
+    let key = sha256_bytes(root.to_string_lossy().as_bytes());
+    Ok(Some(base.join(&key[..12])))
+}
+
+/// Every folder that may hold backups for an install, game folder first
+fn backup_dirs(app: &AppHandle, root: &Path) -> Vec<(PathBuf, bool)> {
+    let settings = load_settings(app);
+    let mut dirs = vec![(root.to_path_buf(), false)];
+    if let Ok(Some(external)) = external_dir(app, &settings, root) {
+        dirs.push((external, true));
+    }
+    dirs
+}
+
+/// Checks that a name refers to a backup folder and nothing else
+fn validate_backup_name(name: &str) -> Result<(), String> {
+    let valid = name.starts_with(BACKUP_PREFIX)
+        && !name.contains(['/', '\\'])
+        && !name.contains("..");
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid backup name: {}", name))
+    }
+}
+
+/// Finds a backup by name in any backup location
+pub fn find_backup(app: &AppHandle, root: &Path, name: &str) -> Result<PathBuf, String> {
+    validate_backup_name(name)?;
+
+    backup_dirs(app, root)
+        .into_iter()
+        .map(|(dir, _)| dir.join(name))
+        .find(|path| path.is_dir())
+        .ok_or_else(|| format!("Backup not found: {}", name))
+}
+
+/// Total size of the files in a folder, recursively
+/// Symlinks count as themselves; their targets are not followed.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| {
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                dir_size(&entry.path())
+            } else {
+                entry.metadata().map(|m| m.len()).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+/// Recreates a symlink pointing where the original does; its target is not copied
+fn copy_symlink(from: &Path, to: &Path) -> Result<(), String> {
+    let target = fs::read_link(from)
+        .map_err(|e| format!("Failed to read link {}: {}", from.display(), e))?;
+
+    #[cfg(unix)]
+    let created = std::os::unix::fs::symlink(&target, to);
+    #[cfg(windows)]
+    let created = if fs::metadata(from).is_ok_and(|m| m.is_dir()) {
+        std::os::windows::fs::symlink_dir(&target, to)
+    } else {
+        std::os::windows::fs::symlink_file(&target, to)
+    };
+
+    created.map_err(|e| format!("Failed to copy link {}: {}", from.display(), e))
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> Result<(), String> {
+    fs::create_dir_all(dst)
+        .map_err(|e| format!("Failed to create {}: {}", dst.display(), e))?;
+
+    let entries = fs::read_dir(src)
+        .map_err(|e| format!("Failed to read {}: {}", src.display(), e))?;
+
+    for entry in entries.flatten() {
+        let from = entry.path();
+        let to = dst.join(entry.file_name());
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+        if file_type.is_symlink() {
+            copy_symlink(&from, &to)?;
+        } else if file_type.is_dir() {
+            copy_dir(&from, &to)?;
+        } else {
+            fs::copy(&from, &to)
+                .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves a folder, copying when it crosses drives
+pub fn move_dir(src: &Path, dst: &Path) -> Result<(), String> {
+    if fs::rename(src, dst).is_ok() {
+        return Ok(());
+    }
+
+    if let Err(e) = copy_dir(src, dst) {
+        let _ = fs::remove_dir_all(dst);
+        return Err(e);
+    }
+
+    fs::remove_dir_all(src)
+        .map_err(|e| format!("Failed to remove {} after copying: {}", src.display(), e))
+}
+
+/// Picks an unused backup path in a folder
+fn new_backup_path(dir: &Path) -> PathBuf {
+    let timestamp = Local::now().format(TIMESTAMP_FORMAT).to_string();
+    let mut path = dir.join(format!("{}{}", BACKUP_PREFIX, timestamp));
+    let mut n = 1;
+    while path.exists() {
+        path = dir.join(format!("{}{}_{}", BACKUP_PREFIX, timestamp, n));
+        n += 1;
+    }
+    path
+}
+
+fn backup_created_at(name: &str) -> Option<String> {
+    let stamp = name.strip_prefix(BACKUP_PREFIX)?.get(..15)?;
+    let naive = NaiveDateTime::parse_from_str(stamp, TIMESTAMP_FORMAT).ok()?;
+    Local.from_local_datetime(&naive).single().map(|t| t.to_rfc3339())
+}
+
+/// Lists backups in every location, newest first
+fn collect_backups(app: &AppHandle, root: &Path) -> Vec<BackupInfo> {
+    let mut backups: Vec<BackupInfo> = backup_dirs(app, root)
+        .into_iter()
+        .flat_map(|(dir, external)| {
+            fs::read_dir(&dir)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(move |entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    if !name.starts_with(BACKUP_PREFIX) {
+                        return None;
+                    }
+                    let path = entry.path();
+                    let plugins = bepinex::discover_plugins(&path.join("plugins"))
+                        .into_iter()
+                        .map(|p| p.name)
+                        .collect();
+                    Some(BackupInfo {
+                        created_at: backup_created_at(&name),
+                        size_bytes: dir_size(&path),
+                        path: path.to_string_lossy().to_string(),
+                        name,
+                        plugins,
+                        external,
+                    })
+                })
+        })
+        .collect();
+
+    // Names embed the timestamp, so they sort chronologically
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    backups
+}
+
+/// Deletes the oldest backups beyond the retention count
+fn prune_backups(app: &AppHandle, root: &Path, retention: u32) {
+    for backup in collect_backups(app, root).iter().skip(retention as usize) {
+        let _ = fs::remove_dir_all(&backup.path);
+    }
+}
+
+/// Moves a replaced BepInEx folder into a new backup and prunes old ones
+pub fn store_backup(app: &AppHandle, root: &Path, source: &Path) -> Result<PathBuf, String> {
+    let settings = load_settings(app);
+    let dir = match external_dir(app, &settings, root)? {
+        Some(dir) => {
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+            dir
+        }
+        None => root.to_path_buf(),
+    };
+
+    let backup_path = new_backup_path(&dir);
+    move_dir(source, &backup_path)
+        .map_err(|e| format!("Failed to backup existing BepInEx folder: {}", e))?;
+
+    // Always keep the backup just made
+    prune_backups(app, root, settings.retention.max(1));
+
+    Ok(backup_path)
+}
+
+/// Lists BepInEx backups with their size, date and plugins
+#[tauri::command]
+pub fn list_backups(app_handle: AppHandle, install_id: String) -> Result<Vec<BackupInfo>, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    Ok(collect_backups(&app_handle, Path::new(&install.root_path)))
+}
+
+/// Restores a backup, swapping it with the current BepInEx folder
+/// The current folder is kept as a new backup, so a restore can be undone.
+#[tauri::command]
+pub fn restore_backup(app_handle: AppHandle, install_id: String, name: String) -> Result<(), String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let root = Path::new(&install.root_path);
+    let bepinex_path = root.join("BepInEx");
+    let backup_path = find_backup(&app_handle, root, &name)?;
+    preflight::ensure_ready(&preflight::check_restore(&install, &backup_path))?;
+
+    // Bring an external backup next to BepInEx first, so the swap below is two renames
+    let staged = root.join("BepInEx_restoring");
+    if staged.exists() {
+        fs::remove_dir_all(&staged)
+            .map_err(|e| format!("Failed to clear previous restore: {}", e))?;
+    }
+    move_dir(&backup_path, &staged)
+        .map_err(|e| format!("Failed to stage backup: {}", e))?;
+
+    let previous = if bepinex_path.exists() {
+        let previous = new_backup_path(root);
+        if let Err(e) = fs::rename(&bepinex_path, &previous) {
+            let _ = move_dir(&staged, &backup_path);
+            return Err(format!("Failed to move current BepInEx folder aside: {}", e));
+        }
+        Some(previous)
+    } else {
+        None
+    };
+
+    if let Err(e) = fs::rename(&staged, &bepinex_path) {
+        // Put everything back where it was
+        if let Some(previous) = &previous {
+            let _ = fs::rename(previous, &bepinex_path);
+        }
+        let _ = move_dir(&staged, &backup_path);
+        return Err(format!("Failed to restore backup: {}", e));
+    }
+
+    // Move the replaced folder to the configured backup location
+    if let Some(previous) = previous {
+        let settings = load_settings(&app_handle);
+        if let Ok(Some(dir)) = external_dir(&app_handle, &settings, root) {
+            if fs::create_dir_all(&dir).is_ok() {
+                let _ = move_dir(&previous, &new_backup_path(&dir));
+            }
+        }
+        prune_backups(&app_handle, root, settings.retention.max(1));
+    }
+
+    registry::refresh(&app_handle, &install.id);
+    Ok(())
+}
+
+/// Deletes a BepInEx backup
+#[tauri::command]
+pub fn delete_backup(app_handle: AppHandle, install_id: String, name: String) -> Result<(), String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let backup_path = find_backup(&app_handle, Path::new(&install.root_path), &name)?;
+
+    fs::remove_dir_all(&backup_path)
+        .map_err(|e| format!("Failed to delete backup: {}", e))
+}
+
+/// Gets the backup retention count and location
+#[tauri::command]
+pub fn get_backup_settings(app_handle: AppHandle) -> BackupSettings {
+    load_settings(&app_handle)
+}
+
+/// Saves the backup retention count and location
+#[tauri::command]
+pub fn set_backup_settings(app_handle: AppHandle, settings: BackupSettings) -> Result<(), String> {
+    if settings.retention == 0 {
+        return Err("Backup retention must be at least 1".to_string());
+    }
+    if let BackupLocation::Custom { path } = &settings.location {
+        for install in registry::load(&app_handle)?.installs {
+            check_custom_location(Path::new(path), Path::new(&install.root_path))?;
+        }
+    }
+
+    let path = settings_path(&app_handle)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize backup settings: {}", e))?;
+
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write backup settings: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn accepts_only_backup_folder_names() {
+        assert!(validate_backup_name("BepInEx_backup_20260101_120000").is_ok());
+        assert!(validate_backup_name("BepInEx").is_err());
+        assert!(validate_backup_name("BepInEx_backup_1/../../etc").is_err());
+        assert!(validate_backup_name("BepInEx_backup_1\\x").is_err());
+    }
+
+    #[test]
+    fn reads_the_time_from_the_name() {
+        let created = backup_created_at("BepInEx_backup_20260102_030405_1").unwrap();
+        assert!(created.starts_with("2026-01-02T03:04:05"), "{}", created);
+        assert_eq!(backup_created_at("BepInEx_backup_yesterday"), None);
+    }
+
+    #[test]
+    fn picks_an_unused_backup_path() {
+        let dir = TempDir::new("backups_path");
+        let first = new_backup_path(&dir);
+        fs::create_dir_all(&first).unwrap();
+
+        let second = new_backup_path(&dir);
+        assert_ne!(first, second);
+        assert!(validate_backup_name(&second.file_name().unwrap().to_string_lossy()).is_ok());
+    }
+
+    #[test]
+    fn keeps_custom_locations_outside_the_game_folder() {
+        let root = TempDir::new("backups_custom");
+        assert!(check_custom_location(&root.join("backups"), &root).is_err());
+        assert!(check_custom_location(&root, &root).is_err());
+        assert!(check_custom_location(Path::new("backups"), &root).is_err());
+        assert!(check_custom_location(&std::env::temp_dir().join("elsewhere"), &root).is_ok());
+    }
+
+    #[test]
+    fn moves_folders_with_their_contents() {
+        let base = TempDir::new("backups_move");
+        fs::create_dir_all(base.join("src/plugins")).unwrap();
+        fs::write(base.join("src/plugins/a.dll"), "dll").unwrap();
+
+        move_dir(&base.join("src"), &base.join("dst")).unwrap();
+        assert!(!base.join("src").exists());
+        assert_eq!(fs::read_to_string(base.join("dst/plugins/a.dll")).unwrap(), "dll");
+        assert_eq!(dir_size(&base.join("dst")), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copies_links_without_following_them() {
+        let base = TempDir::new("backups_links");
+        fs::create_dir_all(base.join("src/plugins")).unwrap();
+        fs::create_dir_all(base.join("outside")).unwrap();
+        fs::write(base.join("outside/big.bin"), "0123456789").unwrap();
+        std::os::unix::fs::symlink(base.join("src"), base.join("src/plugins/loop")).unwrap();
+        std::os::unix::fs::symlink(base.join("outside"), base.join("src/plugins/outside")).unwrap();
+
+        copy_dir(&base.join("src"), &base.join("dst")).unwrap();
+        let copied = base.join("dst/plugins/outside");
+        assert!(fs::symlink_metadata(&copied).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_link(&copied).unwrap(), base.join("outside"));
+        assert!(fs::symlink_metadata(base.join("dst/plugins/loop")).unwrap().file_type().is_symlink());
+    }
+}
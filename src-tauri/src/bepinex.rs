@@ -0,0 +1,234 @@
+use crate::dotnet::{self, AttributeArg, TypeAttribute};
+use crate::models::BepInExFlavor;
+use crate::pe::PeFile;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Loader files BepInEx's doorstop places in the game root
+pub const DOORSTOP_ROOT_FILES: [&str; 3] = ["winhttp.dll", "doorstop_config.ini", ".doorstop_version"];
+
+/// What we could learn about the BepInEx loader in a game folder
+#[derive(Debug, Clone)]
+pub struct LoaderInfo {
+    pub core_version: Option<String>,
+    pub flavor: BepInExFlavor,
+    pub doorstop_version: Option<String>,
+    pub has_winhttp_dll: bool,
+    pub has_doorstop_config: bool,
+    pub warnings: Vec<String>,
+}
+
+/// BepInEx's DependencyFlags.SoftDependency
+const SOFT_DEPENDENCY_FLAG: i32 = 2;
+
+/// A plugin found by reading `[BepInPlugin]` from a DLL's metadata
+#[derive(Debug, Clone)]
+pub struct DiscoveredPlugin {
+    pub guid: String,
+    pub name: String,
+    pub version: String,
+    pub path: PathBuf,
+    pub dependencies: Vec<DiscoveredDependency>,
+}
+
+/// A `[BepInDependency]` declared by a plugin
+#[derive(Debug, Clone)]
+pub struct DiscoveredDependency {
+    pub guid: String,
+    pub min_version: Option<String>,
+    pub soft: bool,
+}
+
+/// Works out which BepInEx build is installed from the assemblies in BepInEx/core
+fn detect_flavor(core_path: &Path) -> BepInExFlavor {
+    let has = |name: &str| core_path.join(name).is_file();
+
+    if has("BepInEx.Unity.IL2CPP.dll") || has("BepInEx.IL2CPP.dll") {
+        BepInExFlavor::BepInEx6Il2Cpp
+    } else if has("BepInEx.Unity.Mono.dll") || (has("BepInEx.Core.dll") && has("BepInEx.Unity.dll")) {
+        BepInExFlavor::BepInEx6Mono
+    } else if has("BepInEx.dll") && !has("BepInEx.Core.dll") {
+        BepInExFlavor::BepInEx5
+    } else {
+        BepInExFlavor::Unknown
+    }
+}
+
+/// Reads the BepInEx core version from the PE version resource
+fn read_core_version(core_path: &Path) -> Option<String> {
+    ["BepInEx.Core.dll", "BepInEx.dll"]
+        .iter()
+        .map(|name| core_path.join(name))
+        .filter(|path| path.is_file())
+        .filter_map(|path| PeFile::open(&path).ok())
+        .find_map(|pe| pe.version_info())
+        .map(|info| info.product_version.unwrap_or(info.file_version))
+}
+
+/// Reads the doorstop version recorded in `.doorstop_version`
+fn read_doorstop_version(root: &Path) -> Option<String> {
+    fs::read_to_string(root.join(".doorstop_version"))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Inspects the BepInEx core and doorstop loader in a game folder
+pub fn inspect_loader(root: &Path) -> LoaderInfo {
+    let core_path = root.join("BepInEx").join("core");
+
+    let core_version = read_core_version(&core_path);
+    let flavor = detect_flavor(&core_path);
+    let doorstop_version = read_doorstop_version(root);
+    let has_winhttp_dll = root.join("winhttp.dll").is_file();
+    let has_doorstop_config = root.join("doorstop_config.ini").is_file();
+
+    let mut warnings = Vec::new();
+
+    if core_path.is_dir() {
+        // FM26 is an IL2CPP game, so only the IL2CPP build of BepInEx 6 can load
+        let is_il2cpp_game = root.join("GameAssembly.dll").is_file();
+        if is_il2cpp_game && matches!(flavor, BepInExFlavor::BepInEx5 | BepInExFlavor::BepInEx6Mono) {
+            warnings.push(format!(
+                "{} is installed but FM26 is an IL2CPP game; BepInEx 6 IL2CPP is required",
+                flavor.label()
+            ));
+        }
+
+        if !has_winhttp_dll {
+            warnings.push("winhttp.dll is missing from the game folder, so BepInEx will not load".to_string());
+        }
+
+        if !has_doorstop_config {
+            warnings.push("doorstop_config.ini is missing from the game folder".to_string());
+        }
+
+        // BepInEx 6 needs doorstop 4; doorstop 3 configs use a different format
+        if let Some(version) = &doorstop_version {
+            let major = version.split('.').next().and_then(|m| m.parse::<u32>().ok());
+            let is_v6 = matches!(flavor, BepInExFlavor::BepInEx6Mono | BepInExFlavor::BepInEx6Il2Cpp);
+            if is_v6 && major.is_some_and(|m| m < 4) {
+                warnings.push(format!(
+                    "Doorstop {} is too old for BepInEx 6; doorstop 4 or newer is required",
+                    version
+                ));
+            }
+        }
+    }
+
+    LoaderInfo {
+        core_version,
+        flavor,
+        doorstop_version,
+        has_winhttp_dll,
+        has_doorstop_config,
+        warnings,
+    }
+}
+
+/// Collects every .dll under a directory, recursively
+/// Symlinks are skipped, so a link loop or a link out of the game folder isn't followed.
+fn find_dlls(dir: &Path, dlls: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            find_dlls(&path, dlls);
+        } else if file_type.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("dll")) {
+            dlls.push(path);
+        }
+    }
+}
+
+fn string_arg(args: &[AttributeArg], index: usize) -> Option<String> {
+    match args.get(index) {
+        Some(AttributeArg::String(value)) => value.clone(),
+        _ => None,
+    }
+}
+
+/// Builds a dependency from a `[BepInDependency(guid, flags)]` or `(guid, version)` attribute
+fn parse_dependency(attribute: &TypeAttribute) -> Option<DiscoveredDependency> {
+    let args = dotnet::decode_fixed_args(&attribute.value, &attribute.ctor_params)?;
+    let guid = string_arg(&args, 0)?;
+
+    let (min_version, soft) = match args.get(1) {
+        Some(AttributeArg::Int32(flags)) => (None, flags & SOFT_DEPENDENCY_FLAG != 0),
+        Some(AttributeArg::String(version)) => (version.clone(), false),
+        None => (None, false),
+    };
+
+    Some(DiscoveredDependency {
+        guid,
+        min_version,
+        soft,
+    })
+}
+
+/// Reads the plugins declared in one DLL (a DLL may hold several plugin classes)
+fn read_plugins_from_dll(path: &Path) -> Vec<DiscoveredPlugin> {
+    let Ok(attributes) = dotnet::read_type_attributes(path) else {
+        return Vec::new();
+    };
+
+    let is_bepinex = |a: &TypeAttribute, name: &str| a.attribute_name == name && a.attribute_namespace == "BepInEx";
+
+    attributes
+        .iter()
+        .filter(|a| is_bepinex(a, "BepInPlugin"))
+        .filter_map(|plugin| {
+            let args = dotnet::decode_fixed_args(&plugin.value, &plugin.ctor_params)?;
+
+            let dependencies = attributes
+                .iter()
+                .filter(|a| a.type_name == plugin.type_name && is_bepinex(a, "BepInDependency"))
+                .filter_map(parse_dependency)
+                .collect();
+
+            Some(DiscoveredPlugin {
+                guid: string_arg(&args, 0)?,
+                name: string_arg(&args, 1).unwrap_or_else(|| plugin.type_name.clone()),
+                version: string_arg(&args, 2).unwrap_or_default(),
+                path: path.to_path_buf(),
+                dependencies,
+            })
+        })
+        .collect()
+}
+
+/// Finds every BepInEx plugin under BepInEx/plugins by reading DLL metadata
+pub fn discover_plugins(plugins_path: &Path) -> Vec<DiscoveredPlugin> {
+    let mut dlls = Vec::new();
+    find_dlls(plugins_path, &mut dlls);
+    dlls.sort();
+
+    dlls.iter().flat_map(|dll| read_plugins_from_dll(dll)).collect()
+}
+
+/// Compares dotted versions numerically, e.g. "1.10.0" >= "1.9"
+/// Ranges like ">=1.2.0" or "^1.2" are checked against their lower bound.
+pub fn version_satisfies(actual: &str, minimum: &str) -> bool {
+    let parse = |v: &str| -> Option<Vec<u64>> {
+        let v = v.trim_start_matches(|c: char| !c.is_ascii_digit());
+        // Drop pre-release and build metadata ("6.0.0-be.735+abc")
+        let v = v.split(['-', '+', ' ']).next()?;
+        v.split('.').map(|part| part.parse::<u64>().ok()).collect()
+    };
+
+    match (parse(actual), parse(minimum)) {
+        (Some(mut actual), Some(mut minimum)) => {
+            let len = actual.len().max(minimum.len());
+            actual.resize(len, 0);
+            minimum.resize(len, 0);
+            actual >= minimum
+        }
+        // Unparseable versions can't be compared; presence is the best we can check
+        _ => true,
+    }
+}
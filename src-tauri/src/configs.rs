@@ -1,16 +1,19 @@
-use crate::models::{AdboardsConfig, AudioInjectConfig, CrowdInjectConfig, Fm26Installation, StadiumInjectionConfig};
+use crate::models::{AdboardsConfig, AudioInjectConfig, CrowdInjectConfig, StadiumInjectionConfig};
+use crate::registry;
 use std::fs;
 use std::path::Path;
 
-const STADIUM_INJECTION_CONFIG: &str = "com.bassy.fm26.stadiuminjection.cfg";
+pub const STADIUM_INJECTION_CONFIG: &str = "com.bassy.fm26.stadiuminjection.cfg";
 const AUDIO_INJECT_CONFIG: &str = "com.bassy.fm26.audioinject.cfg";
 const CROWD_INJECT_CONFIG: &str = "com.bassy.fm26.crowdinject.cfg";
 
 /// Reads the StadiumInjection plugin configuration
 #[tauri::command]
 pub fn read_stadium_injection_config(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
 ) -> Result<StadiumInjectionConfig, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let config_path = Path::new(&install.config_path).join(STADIUM_INJECTION_CONFIG);
 
     if !config_path.exists() {
@@ -26,9 +29,11 @@ pub fn read_stadium_injection_config(
 /// Writes the StadiumInjection plugin configuration
 #[tauri::command]
 pub fn write_stadium_injection_config(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
     config: StadiumInjectionConfig,
 ) -> Result<(), String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let config_path_dir = Path::new(&install.config_path);
 
     // Create config directory if it doesn't exist
@@ -38,7 +43,7 @@ pub fn write_stadium_injection_config(
     }
 
     let content = format_stadium_injection_config(&config);
-    let config_file_path = config_path_dir.join(STADIUM_INJECTION_CONFIG);
+    let config_file_path = registry::install_file(&install, &config_path_dir.join(STADIUM_INJECTION_CONFIG))?;
 
     fs::write(&config_file_path, content)
         .map_err(|e| format!("Failed to write {}: {}", STADIUM_INJECTION_CONFIG, e))?;
@@ -49,8 +54,10 @@ pub fn write_stadium_injection_config(
 /// Reads the AudioInject plugin configuration
 #[tauri::command]
 pub fn read_audio_inject_config(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
 ) -> Result<AudioInjectConfig, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let config_path = Path::new(&install.config_path).join(AUDIO_INJECT_CONFIG);
 
     if !config_path.exists() {
@@ -66,9 +73,11 @@ pub fn read_audio_inject_config(
 /// Writes the AudioInject plugin configuration
 #[tauri::command]
 pub fn write_audio_inject_config(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
     config: AudioInjectConfig,
 ) -> Result<(), String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let config_path_dir = Path::new(&install.config_path);
 
     if !config_path_dir.exists() {
@@ -77,7 +86,7 @@ pub fn write_audio_inject_config(
     }
 
     let content = format_audio_inject_config(&config);
-    let config_file_path = config_path_dir.join(AUDIO_INJECT_CONFIG);
+    let config_file_path = registry::install_file(&install, &config_path_dir.join(AUDIO_INJECT_CONFIG))?;
 
     fs::write(&config_file_path, content)
         .map_err(|e| format!("Failed to write {}: {}", AUDIO_INJECT_CONFIG, e))?;
@@ -88,8 +97,10 @@ pub fn write_audio_inject_config(
 /// Reads the CrowdInject plugin configuration
 #[tauri::command]
 pub fn read_crowd_inject_config(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
 ) -> Result<CrowdInjectConfig, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let config_path = Path::new(&install.config_path).join(CROWD_INJECT_CONFIG);
 
     if !config_path.exists() {
@@ -105,9 +116,11 @@ pub fn read_crowd_inject_config(
 /// Writes the CrowdInject plugin configuration
 #[tauri::command]
 pub fn write_crowd_inject_config(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
     config: CrowdInjectConfig,
 ) -> Result<(), String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let config_path_dir = Path::new(&install.config_path);
 
     if !config_path_dir.exists() {
@@ -116,7 +129,7 @@ pub fn write_crowd_inject_config(
     }
 
     let content = format_crowd_inject_config(&config);
-    let config_file_path = config_path_dir.join(CROWD_INJECT_CONFIG);
+    let config_file_path = registry::install_file(&install, &config_path_dir.join(CROWD_INJECT_CONFIG))?;
 
     fs::write(&config_file_path, content)
         .map_err(|e| format!("Failed to write {}: {}", CROWD_INJECT_CONFIG, e))?;
@@ -127,8 +140,10 @@ pub fn write_crowd_inject_config(
 /// Reads the Adboards configuration from StadiumInjection config
 #[tauri::command]
 pub fn read_adboards_config(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
 ) -> Result<AdboardsConfig, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let config_path = Path::new(&install.config_path).join(STADIUM_INJECTION_CONFIG);
 
     if !config_path.exists() {
@@ -144,10 +159,12 @@ pub fn read_adboards_config(
 /// Writes the Adboards configuration to StadiumInjection config
 #[tauri::command]
 pub fn write_adboards_config(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
     config: AdboardsConfig,
 ) -> Result<(), String> {
-    let config_path = Path::new(&install.config_path).join(STADIUM_INJECTION_CONFIG);
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let config_path = registry::install_file(&install, &Path::new(&install.config_path).join(STADIUM_INJECTION_CONFIG))?;
 
     // Read existing config to preserve other settings
     let existing_content = if config_path.exists() {
@@ -172,7 +189,8 @@ pub fn write_adboards_config(
 
 /// Lists all .cfg files in the config directory
 #[tauri::command]
-pub fn list_config_files(install: Fm26Installation) -> Result<Vec<String>, String> {
+pub fn list_config_files(app_handle: tauri::AppHandle, install_id: String) -> Result<Vec<String>, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let config_path = Path::new(&install.config_path);
 
     if !config_path.exists() {
@@ -202,7 +220,7 @@ pub fn list_config_files(install: Fm26Installation) -> Result<Vec<String>, Strin
 }
 
 /// Parses BepInEx INI-style config into StadiumInjectionConfig
-fn parse_stadium_injection_config(content: &str) -> Result<StadiumInjectionConfig, String> {
+pub fn parse_stadium_injection_config(content: &str) -> Result<StadiumInjectionConfig, String> {
     let mut config = StadiumInjectionConfig::default();
 
     for line in content.lines() {
@@ -522,6 +540,39 @@ fn parse_adboards_config(content: &str) -> Result<AdboardsConfig, String> {
     Ok(config)
 }
 
+/// Line ending a config file uses, so rewrites keep Windows files as CRLF
+pub fn line_ending(content: &str) -> &'static str {
+    if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Sets one key of one section, leaving every other line and the line endings as they are
+pub fn set_cfg_value(content: &str, section: &str, key: &str, value: &str) -> String {
+    let mut current = String::new();
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                current = trimmed[1..trimmed.len() - 1].to_string();
+            } else if current == section && trimmed.split_once('=').is_some_and(|(k, _)| k.trim() == key) {
+                return format!("{} = {}", key, value);
+            }
+            line.to_string()
+        })
+        .collect();
+
+    let ending = line_ending(content);
+    let mut updated = lines.join(ending);
+    if content.ends_with('\n') {
+        updated.push_str(ending);
+    }
+    updated
+}
+
 /// Updates just the Adboards section in StadiumInjection config
 fn update_adboards_in_config(existing_content: &str, config: &AdboardsConfig) -> String {
     let mut lines: Vec<String> = Vec::new();
@@ -592,3 +643,18 @@ fn format_bool(value: bool) -> &'static str {
         "false"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_a_value_only_in_its_section() {
+        let content = "[General]\r\nReplaceAllStadiums = true\r\n\r\n[Other]\r\nReplaceAllStadiums = true\r\n";
+        assert_eq!(
+            set_cfg_value(content, "General", "ReplaceAllStadiums", "false"),
+            "[General]\r\nReplaceAllStadiums = false\r\n\r\n[Other]\r\nReplaceAllStadiums = true\r\n"
+        );
+        assert_eq!(set_cfg_value("[General]\nKey=1", "General", "Key", "2"), "[General]\nKey = 2");
+    }
+}
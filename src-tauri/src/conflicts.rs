@@ -0,0 +1,184 @@
+use crate::archive::Archive;
+use crate::hash::{sha256_file, sha256_reader};
+use crate::install_plan::open_pack;
+use crate::models::{BundleConflict, PackKind, RenamedBundle, RouteDestination};
+use crate::routing::Router;
+use crate::registry;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Name of a routed CustomStadium file as team_mappings.txt refers to it
+pub fn bundle_name(relative: &Path) -> String {
+    relative
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Lowercased names of every file a pack puts in CustomStadium
+pub fn incoming_bundles(archive: &Archive, router: &Router) -> HashSet<String> {
+    archive
+        .entries()
+        .iter()
+        .filter_map(|entry| router.route(entry))
+        .filter(|route| route.destination == RouteDestination::CustomStadium)
+        .map(|route| bundle_name(&route.relative).to_lowercase())
+        .collect()
+}
+
+/// First free `<name>_<n>.<ext>` for a bundle kept alongside an existing one
+pub fn rename_target(custom_stadium_path: &Path, file: &str, incoming: &HashSet<String>) -> String {
+    let (stem, extension) = match file.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (file, String::new()),
+    };
+
+    (2..)
+        .map(|n| format!("{}_{}{}", stem, n, extension))
+        .find(|candidate| {
+            !custom_stadium_path.join(candidate).exists() && !incoming.contains(&candidate.to_lowercase())
+        })
+        .unwrap_or_default()
+}
+
+/// Checks whether a pack file differs from the installed one, hashing only when sizes match
+pub fn differs(existing: &Path, size: u64, mut contents: &mut dyn Read) -> Result<bool, String> {
+    let existing_size = fs::metadata(existing)
+        .map_err(|e| format!("Failed to read {}: {}", existing.display(), e))?
+        .len();
+    if existing_size != size {
+        return Ok(true);
+    }
+
+    let incoming = sha256_reader(&mut contents)
+        .map_err(|e| format!("Failed to read {} from archive: {}", existing.display(), e))?;
+    Ok(sha256_file(existing)? != incoming)
+}
+
+/// Lists the bundles of a stadium pack that differ from installed ones
+/// Identical bundles aren't conflicts; the install skips them on its own.
+#[tauri::command]
+pub fn check_stadium_conflicts(
+    zip_path: String,
+    layout_prefix: Option<String>,
+    app_handle: tauri::AppHandle,
+    install_id: String,
+) -> Result<Vec<BundleConflict>, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let (archive, layout) = open_pack(Some(zip_path), PackKind::CustomStadiums, layout_prefix, &app_handle)?;
+    let router = Router::new(&install, &layout.strip_prefix)?;
+    let root = Path::new(&install.root_path);
+    let incoming = incoming_bundles(&archive, &router);
+
+    let mut conflicts = Vec::new();
+
+    archive.for_each_entry(|entry, contents| {
+        let Some(route) = router.route(entry) else {
+            return Ok(());
+        };
+        let existing = root.join(&route.relative);
+        if route.destination != RouteDestination::CustomStadium || !existing.is_file() {
+            return Ok(());
+        }
+        if !differs(&existing, entry.size, contents)? {
+            return Ok(());
+        }
+
+        let file = bundle_name(&route.relative);
+        conflicts.push(BundleConflict {
+            rename_to: rename_target(Path::new(&install.custom_stadium_path), &file, &incoming),
+            existing_size: fs::metadata(&existing).map(|m| m.len()).unwrap_or(0),
+            incoming_size: entry.size,
+            file,
+        });
+        Ok(())
+    })?;
+
+    Ok(conflicts)
+}
+
+/// Points team_mappings.txt lines at the new names of renamed bundles
+pub fn rewrite_mapping_references(content: &str, renames: &[RenamedBundle]) -> String {
+    let mut rewritten: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let separator = if line.contains('=') { '=' } else { ':' };
+            let Some((key, bundle)) = line.split_once(separator) else {
+                return line.to_string();
+            };
+            if key.trim().parse::<i32>().is_err() {
+                return line.to_string();
+            }
+
+            match renames.iter().find(|r| r.from.eq_ignore_ascii_case(bundle.trim())) {
+                Some(rename) => format!("{}{}{}", key.trim(), separator, rename.to),
+                None => line.to_string(),
+            }
+        })
+        .collect();
+
+    if content.ends_with('\n') {
+        rewritten.push(String::new());
+    }
+    rewritten.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::io::Cursor;
+
+    #[test]
+    fn names_bundles_by_file_name() {
+        assert_eq!(bundle_name(Path::new("BepInEx/plugins/CustomStadium/wembley.bundle")), "wembley.bundle");
+    }
+
+    #[test]
+    fn renames_to_the_first_free_name() {
+        let dir = TempDir::new("conflicts_rename");
+        fs::write(dir.join("wembley_2.bundle"), "").unwrap();
+
+        // Taken on disk, and by another bundle of the same pack
+        let incoming = HashSet::from(["wembley_3.bundle".to_string()]);
+        assert_eq!(rename_target(&dir, "wembley.bundle", &incoming), "wembley_4.bundle");
+        assert_eq!(rename_target(&dir, "noextension", &incoming), "noextension_2");
+        assert_eq!(rename_target(&dir, ".hidden", &incoming), ".hidden_2");
+    }
+
+    /// Archive contents that must never be read
+    struct Unread;
+
+    impl Read for Unread {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            panic!("contents were read although the sizes differ");
+        }
+    }
+
+    #[test]
+    fn compares_sizes_before_hashing() {
+        let dir = TempDir::new("conflicts_differs");
+        let path = dir.join("a.bundle");
+        fs::write(&path, "same").unwrap();
+
+        assert!(!differs(&path, 4, &mut Cursor::new("same")).unwrap());
+        assert!(differs(&path, 4, &mut Cursor::new("diff")).unwrap());
+        assert!(differs(&path, 5, &mut Unread).unwrap());
+    }
+
+    #[test]
+    fn points_mappings_at_renamed_bundles() {
+        let renames = [RenamedBundle {
+            from: "wembley.bundle".to_string(),
+            to: "wembley_2.bundle".to_string(),
+        }];
+        let content = "# comment about wembley.bundle\n1:Wembley.bundle\n2=wembley.bundle\n3:anfield.bundle\n";
+
+        assert_eq!(
+            rewrite_mapping_references(content, &renames),
+            "# comment about wembley.bundle\n1:wembley_2.bundle\n2=wembley_2.bundle\n3:anfield.bundle\n"
+        );
+    }
+}
@@ -0,0 +1,470 @@
+use crate::models::{DiagnosisCheck, DiagnosisFinding, DiagnosisReport, DiagnosisSeverity, Fm26Installation, FixAction};
+use crate::{audio, bepinex, configs, fm26, logs, names, proton, registry, routing, stadium, staging};
+use chrono::Local;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Most log errors listed in a report; the newest are kept
+const MAX_LOG_ERRORS: usize = 20;
+
+fn finding(check: DiagnosisCheck, severity: DiagnosisSeverity, message: impl Into<String>) -> DiagnosisFinding {
+    DiagnosisFinding {
+        check,
+        severity,
+        message: message.into(),
+        details: Vec::new(),
+        fix: None,
+    }
+}
+
+/// Checks the BepInEx core, the doorstop loader and the Proton override
+fn check_loader(root: &Path, findings: &mut Vec<DiagnosisFinding>) {
+    let loader = bepinex::inspect_loader(root);
+
+    match &loader.core_version {
+        Some(version) => findings.push(finding(
+            DiagnosisCheck::Loader,
+            DiagnosisSeverity::Ok,
+            format!("BepInEx {} ({})", version, loader.flavor.label()),
+        )),
+        None => findings.push(DiagnosisFinding {
+            fix: Some(FixAction::InstallPack),
+            ..finding(DiagnosisCheck::Loader, DiagnosisSeverity::Error, "BepInEx is not installed")
+        }),
+    }
+
+    let mut missing = Vec::new();
+    if !loader.has_winhttp_dll {
+        missing.push("winhttp.dll".to_string());
+    }
+    if !loader.has_doorstop_config {
+        missing.push("doorstop_config.ini".to_string());
+    }
+    if missing.is_empty() {
+        findings.push(finding(
+            DiagnosisCheck::Loader,
+            DiagnosisSeverity::Ok,
+            format!("Doorstop {}", loader.doorstop_version.as_deref().unwrap_or("(unknown version)")),
+        ));
+    } else {
+        findings.push(DiagnosisFinding {
+            details: missing,
+            fix: Some(FixAction::InstallPack),
+            ..finding(DiagnosisCheck::Loader, DiagnosisSeverity::Error, "The doorstop loader is incomplete")
+        });
+    }
+
+    if !loader.warnings.is_empty() {
+        findings.push(DiagnosisFinding {
+            details: loader.warnings,
+            fix: Some(FixAction::InstallPack),
+            ..finding(DiagnosisCheck::Loader, DiagnosisSeverity::Warning, "The BepInEx install looks inconsistent")
+        });
+    }
+
+    if proton::blocks_bepinex(root) {
+        findings.push(DiagnosisFinding {
+            fix: Some(FixAction::ApplyProtonLaunchOptions),
+            ..finding(
+                DiagnosisCheck::Loader,
+                DiagnosisSeverity::Error,
+                "Proton will load its own winhttp.dll and skip BepInEx",
+            )
+        });
+    }
+}
+
+/// Checks the plugin DLLs shipped in the stadium pack
+fn check_plugins(install: &Fm26Installation, findings: &mut Vec<DiagnosisFinding>) {
+    for (name, _, rel_path) in fm26::KNOWN_PLUGINS {
+        if Path::new(&install.plugins_path).join(rel_path).is_file() {
+            findings.push(finding(DiagnosisCheck::Plugins, DiagnosisSeverity::Ok, format!("{} is installed", name)));
+        } else {
+            findings.push(DiagnosisFinding {
+                details: vec![format!("plugins/{}", rel_path)],
+                fix: Some(FixAction::InstallPack),
+                ..finding(DiagnosisCheck::Plugins, DiagnosisSeverity::Error, format!("{} is missing", name))
+            });
+        }
+    }
+}
+
+/// Lines of a BepInEx .cfg that are not blank, a comment, a [Section] or Key = Value
+fn cfg_syntax_errors(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim();
+            let is_section = line.starts_with('[') && line.ends_with(']') && line.len() > 2;
+            let is_setting = line.split_once('=').is_some_and(|(key, _)| !key.trim().is_empty());
+            !(line.is_empty() || line.starts_with('#') || line.starts_with(';') || is_section || is_setting)
+        })
+        .map(|(i, line)| format!("line {}: {}", i + 1, line.trim()))
+        .collect()
+}
+
+/// Checks that every .cfg in BepInEx/config parses
+fn check_configs(install: &Fm26Installation, findings: &mut Vec<DiagnosisFinding>) {
+    let Ok(entries) = fs::read_dir(&install.config_path) else {
+        findings.push(finding(
+            DiagnosisCheck::Configs,
+            DiagnosisSeverity::Info,
+            "No config folder yet; BepInEx creates it on first launch",
+        ));
+        return;
+    };
+
+    let mut files: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "cfg"))
+        .collect();
+    files.sort();
+
+    let mut failed = Vec::new();
+    for path in &files {
+        let file = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let errors = match fs::read_to_string(path) {
+            Ok(content) => cfg_syntax_errors(&content),
+            Err(e) => vec![format!("Failed to read: {}", e)],
+        };
+
+        if errors.is_empty() {
+            continue;
+        }
+
+        findings.push(DiagnosisFinding {
+            details: errors,
+            fix: Some(FixAction::SetAsideConfig { file: file.clone() }),
+            ..finding(DiagnosisCheck::Configs, DiagnosisSeverity::Error, format!("{} does not parse", file))
+        });
+        failed.push(file);
+    }
+
+    // Each broken file has its own finding; the summary names them all
+    let severity = if failed.is_empty() { DiagnosisSeverity::Ok } else { DiagnosisSeverity::Info };
+    let message = format!("{} of {} config files parse", files.len() - failed.len(), files.len());
+    findings.push(DiagnosisFinding {
+        details: failed,
+        ..finding(DiagnosisCheck::Configs, severity, message)
+    });
+}
+
+/// Checks whether a bundle named in a mapping or config exists in CustomStadium
+fn bundle_exists(install: &Fm26Installation, bundle_file: &str) -> bool {
+    names::check_component(bundle_file).is_ok()
+        && Path::new(&install.custom_stadium_path).join(bundle_file).is_file()
+}
+
+/// Checks that every team mapping points to an existing bundle
+fn check_team_mappings(install: &Fm26Installation, findings: &mut Vec<DiagnosisFinding>) {
+    let mappings_path = Path::new(&install.plugins_path).join("StadiumInjection").join("team_mappings.txt");
+    let Ok(content) = fs::read_to_string(&mappings_path) else {
+        return;
+    };
+
+    let mappings = stadium::parse_team_mappings(&content);
+    let broken: Vec<_> = mappings
+        .iter()
+        .filter(|mapping| !bundle_exists(install, &mapping.bundle_file))
+        .collect();
+
+    if broken.is_empty() {
+        findings.push(finding(
+            DiagnosisCheck::TeamMappings,
+            DiagnosisSeverity::Ok,
+            format!("All {} team mappings point to existing bundles", mappings.len()),
+        ));
+        return;
+    }
+
+    findings.push(DiagnosisFinding {
+        details: broken
+            .iter()
+            .map(|mapping| format!("team {}: {}", mapping.team_id, mapping.bundle_file))
+            .collect(),
+        fix: Some(FixAction::RemoveTeamMappings {
+            team_ids: broken.iter().map(|mapping| mapping.team_id).collect(),
+        }),
+        ..finding(
+            DiagnosisCheck::TeamMappings,
+            DiagnosisSeverity::Warning,
+            format!("{} team mappings point to missing bundles", broken.len()),
+        )
+    });
+}
+
+/// Checks that DefaultBundle exists when ReplaceAllStadiums is on
+fn check_default_bundle(install: &Fm26Installation, findings: &mut Vec<DiagnosisFinding>) {
+    let config_path = Path::new(&install.config_path).join(configs::STADIUM_INJECTION_CONFIG);
+    let Some(config) = fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|content| configs::parse_stadium_injection_config(&content).ok())
+    else {
+        return;
+    };
+
+    if !config.enable_custom_stadiums || !config.replace_all_stadiums {
+        return;
+    }
+
+    let message = if config.default_bundle.is_empty() {
+        "ReplaceAllStadiums is on but no DefaultBundle is set".to_string()
+    } else if !bundle_exists(install, &config.default_bundle) {
+        format!("DefaultBundle {} does not exist", config.default_bundle)
+    } else {
+        findings.push(finding(
+            DiagnosisCheck::DefaultBundle,
+            DiagnosisSeverity::Ok,
+            format!("Every stadium is replaced with {}", config.default_bundle),
+        ));
+        return;
+    };
+
+    findings.push(DiagnosisFinding {
+        fix: Some(FixAction::DisableReplaceAllStadiums),
+        ..finding(DiagnosisCheck::DefaultBundle, DiagnosisSeverity::Error, message)
+    });
+}
+
+/// Checks that every audio mapping folder exists and has the required sounds
+fn check_audio_mappings(install: &Fm26Installation, findings: &mut Vec<DiagnosisFinding>) {
+    let audio_inject_path = Path::new(&install.audio_inject_path);
+    let Ok(content) = fs::read_to_string(audio_inject_path.join("AudioMappings.txt")) else {
+        return;
+    };
+
+    let mappings = audio::parse_audio_mappings(&content);
+    let mut missing = Vec::new();
+    let mut incomplete = Vec::new();
+
+    for mapping in &mappings {
+        let folder_path = audio_inject_path.join(&mapping.folder_name);
+        if names::check_component(&mapping.folder_name).is_err() || !folder_path.is_dir() {
+            missing.push(mapping);
+            continue;
+        }
+
+        let absent: Vec<_> = audio::REQUIRED_SOUNDS
+            .iter()
+            .filter(|sound| !audio::has_sound(&folder_path, sound))
+            .copied()
+            .collect();
+        if !absent.is_empty() {
+            incomplete.push(format!("{}: {} is missing {}", mapping.team_key, mapping.folder_name, absent.join(", ")));
+        }
+    }
+
+    if missing.is_empty() && incomplete.is_empty() {
+        findings.push(finding(
+            DiagnosisCheck::AudioMappings,
+            DiagnosisSeverity::Ok,
+            format!("All {} audio mappings are complete", mappings.len()),
+        ));
+        return;
+    }
+
+    if !missing.is_empty() {
+        findings.push(DiagnosisFinding {
+            details: missing
+                .iter()
+                .map(|mapping| format!("{}: {}", mapping.team_key, mapping.folder_name))
+                .collect(),
+            fix: Some(FixAction::RemoveAudioMappings {
+                team_keys: missing.iter().map(|mapping| mapping.team_key.clone()).collect(),
+            }),
+            ..finding(
+                DiagnosisCheck::AudioMappings,
+                DiagnosisSeverity::Warning,
+                format!("{} audio mappings point to missing folders", missing.len()),
+            )
+        });
+    }
+
+    if !incomplete.is_empty() {
+        findings.push(DiagnosisFinding {
+            details: incomplete,
+            ..finding(
+                DiagnosisCheck::AudioMappings,
+                DiagnosisSeverity::Warning,
+                "Some audio folders are missing required sounds",
+            )
+        });
+    }
+}
+
+/// Reports staging folders left in the game root by interrupted installs
+fn check_staging(root: &Path, findings: &mut Vec<DiagnosisFinding>) {
+    let leftovers = staging::leftover_workspaces(root);
+    if leftovers.is_empty() {
+        return;
+    }
+
+    findings.push(DiagnosisFinding {
+        details: leftovers.iter().map(|path| path.display().to_string()).collect(),
+        ..finding(
+            DiagnosisCheck::Staging,
+            DiagnosisSeverity::Warning,
+            format!(
+                "{} folders are left from interrupted installs; check them for replaced files, then delete them",
+                leftovers.len()
+            ),
+        )
+    });
+}
+
+/// Collects error and fatal entries from the last session's LogOutput.log
+fn check_log(install: &Fm26Installation, findings: &mut Vec<DiagnosisFinding>) {
+    let Ok(bytes) = fs::read(&install.log_path) else {
+        findings.push(finding(
+            DiagnosisCheck::Log,
+            DiagnosisSeverity::Info,
+            "No LogOutput.log yet; launch the game once to create it",
+        ));
+        return;
+    };
+
+    let content = String::from_utf8_lossy(&bytes);
+    let errors = logs::error_lines(&content);
+
+    if errors.is_empty() {
+        findings.push(finding(DiagnosisCheck::Log, DiagnosisSeverity::Ok, "No errors in the last session's log"));
+        return;
+    }
+
+    let total = errors.len();
+    findings.push(DiagnosisFinding {
+        details: errors[total.saturating_sub(MAX_LOG_ERRORS)..].to_vec(),
+        ..finding(
+            DiagnosisCheck::Log,
+            DiagnosisSeverity::Warning,
+            format!("{} errors in the last session's log", total),
+        )
+    });
+}
+
+/// Runs every health check on an installation and returns one report
+#[tauri::command]
+pub fn diagnose_install(app_handle: tauri::AppHandle, install_id: String) -> Result<DiagnosisReport, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let mut findings = Vec::new();
+
+    check_loader(Path::new(&install.root_path), &mut findings);
+    check_plugins(&install, &mut findings);
+    check_configs(&install, &mut findings);
+    check_team_mappings(&install, &mut findings);
+    check_default_bundle(&install, &mut findings);
+    check_audio_mappings(&install, &mut findings);
+    check_log(&install, &mut findings);
+    check_staging(Path::new(&install.root_path), &mut findings);
+
+    // Stable sort keeps checks in order within a severity
+    findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+
+    let count = |severity| findings.iter().filter(|f| f.severity == severity).count() as u32;
+    Ok(DiagnosisReport {
+        errors: count(DiagnosisSeverity::Error),
+        warnings: count(DiagnosisSeverity::Warning),
+        findings,
+        checked_at: Local::now().to_rfc3339(),
+    })
+}
+
+/// Drops mapping lines whose key is in `keys`, keeping comments and everything else
+fn remove_mapping_lines(content: &str, keys: &HashSet<String>, key_of: fn(&str) -> Option<String>) -> String {
+    let mut kept = String::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        let is_comment = trimmed.starts_with('#') || trimmed.starts_with("//");
+        if !is_comment && key_of(trimmed).is_some_and(|key| keys.contains(&key)) {
+            continue;
+        }
+        kept.push_str(line);
+        kept.push('\n');
+    }
+    kept
+}
+
+/// Rewrites a mapping file without the given keys
+fn remove_mappings(
+    install: &Fm26Installation,
+    path: &Path,
+    keys: HashSet<String>,
+    key_of: fn(&str) -> Option<String>,
+) -> Result<(), String> {
+    let path = &registry::install_file(install, path)?;
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    fs::write(path, remove_mapping_lines(&content, &keys, key_of))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Applies a fix suggested by diagnose_install
+#[tauri::command]
+pub fn apply_fix(app_handle: tauri::AppHandle, install_id: String, fix: FixAction) -> Result<(), String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+
+    match fix {
+        FixAction::InstallPack => Err("Reinstall the stadium pack from the install dialog".to_string()),
+        FixAction::ApplyProtonLaunchOptions => {
+            proton::apply_proton_launch_options(app_handle, install_id).map(|_| ())
+        }
+        FixAction::RemoveTeamMappings { team_ids } => remove_mappings(
+            &install,
+            &Path::new(&install.plugins_path).join("StadiumInjection").join("team_mappings.txt"),
+            team_ids.iter().map(|id| id.to_string()).collect(),
+            routing::team_mapping_key,
+        ),
+        FixAction::RemoveAudioMappings { team_keys } => remove_mappings(
+            &install,
+            &Path::new(&install.audio_inject_path).join("AudioMappings.txt"),
+            team_keys.into_iter().collect(),
+            routing::audio_mapping_key,
+        ),
+        FixAction::DisableReplaceAllStadiums => {
+            let config_path =
+                registry::install_file(&install, &Path::new(&install.config_path).join(configs::STADIUM_INJECTION_CONFIG))?;
+            let content = fs::read_to_string(&config_path)
+                .map_err(|e| format!("Failed to read config: {}", e))?;
+
+            // Only touch the one setting so comments and other sections survive
+            let updated = configs::set_cfg_value(&content, "General", "ReplaceAllStadiums", "false");
+            fs::write(&config_path, updated)
+                .map_err(|e| format!("Failed to write config: {}", e))
+        }
+        FixAction::SetAsideConfig { file } => {
+            names::check_component(&file).map_err(|_| format!("Invalid config file name: {}", file))?;
+            let config_path = Path::new(&install.config_path).join(&file);
+            let broken_path = config_path.with_file_name(format!("{}.broken", file));
+            fs::rename(&config_path, &broken_path)
+                .map_err(|e| format!("Failed to set aside {}: {}", file, e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn reports_how_many_configs_parse() {
+        let root = TempDir::new("diagnose_configs");
+        let install = fm26::installation_paths("test", &root);
+        fs::create_dir_all(&install.config_path).unwrap();
+        let config_dir = Path::new(&install.config_path);
+        fs::write(config_dir.join("good.cfg"), "[General]\nEnabled = true\n").unwrap();
+        fs::write(config_dir.join("broken.cfg"), "[General]\nnot a setting\n").unwrap();
+
+        let mut findings = Vec::new();
+        check_configs(&install, &mut findings);
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].message, "broken.cfg does not parse");
+        assert_eq!(findings[0].details, ["line 2: not a setting"]);
+        assert_eq!(findings[1].message, "1 of 2 config files parse");
+        assert_eq!(findings[1].details, ["broken.cfg"]);
+    }
+}
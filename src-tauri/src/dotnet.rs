@@ -0,0 +1,580 @@
+use crate::pe::{read_u16, read_u32, PeFile};
+use std::path::Path;
+
+/// Data directory index of the CLI (.NET) header
+const CLI_HEADER_DIRECTORY: usize = 14;
+
+/// Signature of the metadata root ("BSJB")
+const METADATA_SIGNATURE: u32 = 0x424A_5342;
+
+// Metadata table ids (ECMA-335 II.22)
+const TABLE_MODULE: usize = 0x00;
+const TABLE_TYPE_REF: usize = 0x01;
+const TABLE_TYPE_DEF: usize = 0x02;
+const TABLE_FIELD_PTR: usize = 0x03;
+const TABLE_FIELD: usize = 0x04;
+const TABLE_METHOD_PTR: usize = 0x05;
+const TABLE_METHOD_DEF: usize = 0x06;
+const TABLE_PARAM_PTR: usize = 0x07;
+const TABLE_PARAM: usize = 0x08;
+const TABLE_INTERFACE_IMPL: usize = 0x09;
+const TABLE_MEMBER_REF: usize = 0x0A;
+const TABLE_CONSTANT: usize = 0x0B;
+const TABLE_CUSTOM_ATTRIBUTE: usize = 0x0C;
+const TABLE_DECL_SECURITY: usize = 0x0E;
+const TABLE_STANDALONE_SIG: usize = 0x11;
+const TABLE_EVENT: usize = 0x14;
+const TABLE_PROPERTY: usize = 0x17;
+const TABLE_MODULE_REF: usize = 0x1A;
+const TABLE_TYPE_SPEC: usize = 0x1B;
+const TABLE_ASSEMBLY: usize = 0x20;
+const TABLE_ASSEMBLY_REF: usize = 0x23;
+const TABLE_FILE: usize = 0x26;
+const TABLE_EXPORTED_TYPE: usize = 0x27;
+const TABLE_MANIFEST_RESOURCE: usize = 0x28;
+const TABLE_GENERIC_PARAM: usize = 0x2A;
+const TABLE_METHOD_SPEC: usize = 0x2B;
+const TABLE_GENERIC_PARAM_CONSTRAINT: usize = 0x2C;
+
+// Coded index tag layouts (ECMA-335 II.24.2.6)
+const RESOLUTION_SCOPE: &[usize] = &[TABLE_MODULE, TABLE_MODULE_REF, TABLE_ASSEMBLY_REF, TABLE_TYPE_REF];
+const TYPE_DEF_OR_REF: &[usize] = &[TABLE_TYPE_DEF, TABLE_TYPE_REF, TABLE_TYPE_SPEC];
+const HAS_CONSTANT: &[usize] = &[TABLE_FIELD, TABLE_PARAM, TABLE_PROPERTY];
+const MEMBER_REF_PARENT: &[usize] = &[
+    TABLE_TYPE_DEF,
+    TABLE_TYPE_REF,
+    TABLE_MODULE_REF,
+    TABLE_METHOD_DEF,
+    TABLE_TYPE_SPEC,
+];
+const HAS_CUSTOM_ATTRIBUTE: &[usize] = &[
+    TABLE_METHOD_DEF,
+    TABLE_FIELD,
+    TABLE_TYPE_REF,
+    TABLE_TYPE_DEF,
+    TABLE_PARAM,
+    TABLE_INTERFACE_IMPL,
+    TABLE_MEMBER_REF,
+    TABLE_MODULE,
+    TABLE_DECL_SECURITY,
+    TABLE_PROPERTY,
+    TABLE_EVENT,
+    TABLE_STANDALONE_SIG,
+    TABLE_MODULE_REF,
+    TABLE_TYPE_SPEC,
+    TABLE_ASSEMBLY,
+    TABLE_ASSEMBLY_REF,
+    TABLE_FILE,
+    TABLE_EXPORTED_TYPE,
+    TABLE_MANIFEST_RESOURCE,
+    TABLE_GENERIC_PARAM,
+    TABLE_GENERIC_PARAM_CONSTRAINT,
+    TABLE_METHOD_SPEC,
+];
+// Tags 0, 1 and 4 are unused; 2 is MethodDef and 3 is MemberRef
+const CUSTOM_ATTRIBUTE_TYPE: &[usize] = &[usize::MAX, usize::MAX, TABLE_METHOD_DEF, TABLE_MEMBER_REF, usize::MAX];
+
+/// A custom attribute applied to a type, with its raw constructor arguments
+#[derive(Debug, Clone)]
+pub struct TypeAttribute {
+    pub type_name: String,      // the class the attribute is applied to
+    pub attribute_name: String, // e.g. "BepInPlugin"
+    pub attribute_namespace: String,
+    pub ctor_params: Vec<ParamKind>,
+    pub value: Vec<u8>, // custom attribute blob (prolog + fixed args + named args)
+}
+
+/// Constructor parameter types we know how to decode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    String,
+    Int32, // also used for enums, which BepInEx backs with int
+    Other,
+}
+
+/// Layout of the #~ stream, enough to read rows of the tables we care about
+struct Tables<'a> {
+    data: &'a [u8],
+    rows: [u32; 64],
+    offsets: [usize; 64],
+    row_sizes: [usize; 64],
+    string_index_size: usize,
+    blob_index_size: usize,
+}
+
+/// The heaps and tables of a .NET assembly's metadata
+pub struct Metadata<'a> {
+    tables: Tables<'a>,
+    strings: &'a [u8],
+    blobs: &'a [u8],
+}
+
+impl<'a> Metadata<'a> {
+    /// Locates and parses the metadata of a .NET PE image
+    pub fn parse(pe: &'a PeFile) -> Option<Self> {
+        let (cli_rva, cli_size) = pe.data_directory(CLI_HEADER_DIRECTORY)?;
+        let cli = pe.slice_at_rva(cli_rva, cli_size)?;
+        let md_rva = read_u32(cli, 8)?;
+        let md_size = read_u32(cli, 12)?;
+        let root = pe.slice_at_rva(md_rva, md_size)?;
+
+        if read_u32(root, 0)? != METADATA_SIGNATURE {
+            return None;
+        }
+
+        // Skip the padded version string to reach the stream headers
+        let version_len = read_u32(root, 12)? as usize;
+        let mut offset = 16 + version_len + 2;
+        let stream_count = read_u16(root, offset)? as usize;
+        offset += 2;
+
+        let mut tables_stream = None;
+        let mut strings = &[][..];
+        let mut blobs = &[][..];
+
+        for _ in 0..stream_count {
+            let stream_offset = read_u32(root, offset)? as usize;
+            let stream_size = read_u32(root, offset + 4)? as usize;
+            let name_start = offset + 8;
+            let name_len = root.get(name_start..)?.iter().position(|b| *b == 0)?;
+            let name = std::str::from_utf8(&root[name_start..name_start + name_len]).ok()?;
+            offset = (name_start + name_len + 1 + 3) & !3;
+
+            let stream = root.get(stream_offset..stream_offset + stream_size)?;
+            match name {
+                "#~" | "#-" => tables_stream = Some(stream),
+                "#Strings" => strings = stream,
+                "#Blob" => blobs = stream,
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            tables: Tables::parse(tables_stream?)?,
+            strings,
+            blobs,
+        })
+    }
+
+    fn string(&self, index: u32) -> Option<String> {
+        let start = index as usize;
+        let len = self.strings.get(start..)?.iter().position(|b| *b == 0)?;
+        Some(String::from_utf8_lossy(&self.strings[start..start + len]).to_string())
+    }
+
+    fn blob(&self, index: u32) -> Option<&'a [u8]> {
+        let (len, header) = read_compressed_u32(self.blobs.get(index as usize..)?)?;
+        let start = index as usize + header;
+        self.blobs.get(start..start + len as usize)
+    }
+
+    /// Returns (name, namespace) of a TypeRef or TypeDef row
+    fn type_name(&self, table: usize, row: u32) -> Option<(String, String)> {
+        let t = &self.tables;
+        let base = t.row_offset(table, row)?;
+        let s = t.string_index_size;
+        let name_offset = match table {
+            TABLE_TYPE_REF => base + t.coded_size(RESOLUTION_SCOPE),
+            TABLE_TYPE_DEF => base + 4,
+            _ => return None,
+        };
+        let name = self.string(t.read_index(name_offset, s)?)?;
+        let namespace = self.string(t.read_index(name_offset + s, s)?)?;
+        Some((name, namespace))
+    }
+
+    /// Resolves an attribute constructor to its declaring type and parameter list
+    fn resolve_constructor(&self, coded: u32) -> Option<((String, String), Vec<ParamKind>)> {
+        let (table, row) = decode_coded_index(coded, CUSTOM_ATTRIBUTE_TYPE)?;
+        let t = &self.tables;
+
+        match table {
+            TABLE_MEMBER_REF => {
+                let base = t.row_offset(TABLE_MEMBER_REF, row)?;
+                let parent_size = t.coded_size(MEMBER_REF_PARENT);
+                let parent = t.read_index(base, parent_size)?;
+                let sig_offset = base + parent_size + t.string_index_size;
+                let signature = self.blob(t.read_index(sig_offset, t.blob_index_size)?)?;
+
+                let (parent_table, parent_row) = decode_coded_index(parent, MEMBER_REF_PARENT)?;
+                Some((self.type_name(parent_table, parent_row)?, parse_method_params(signature)?))
+            }
+            TABLE_METHOD_DEF => {
+                // Attribute declared in the same assembly: find the TypeDef owning the method
+                let base = t.row_offset(TABLE_METHOD_DEF, row)?;
+                let sig_offset = base + 8 + t.string_index_size;
+                let signature = self.blob(t.read_index(sig_offset, t.blob_index_size)?)?;
+                let owner = self.method_owner(row)?;
+                Some((self.type_name(TABLE_TYPE_DEF, owner)?, parse_method_params(signature)?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Finds the TypeDef whose method list contains a MethodDef row
+    fn method_owner(&self, method_row: u32) -> Option<u32> {
+        let t = &self.tables;
+        let method_list_offset = 4
+            + 2 * t.string_index_size
+            + t.coded_size(TYPE_DEF_OR_REF)
+            + t.table_index_size(TABLE_FIELD);
+
+        (1..=t.rows[TABLE_TYPE_DEF])
+            .rev()
+            .find(|&type_row| {
+                t.row_offset(TABLE_TYPE_DEF, type_row)
+                    .and_then(|base| t.read_index(base + method_list_offset, t.table_index_size(TABLE_METHOD_DEF)))
+                    .is_some_and(|start| start <= method_row)
+            })
+    }
+
+    /// Lists every custom attribute applied to a TypeDef
+    pub fn type_attributes(&self) -> Vec<TypeAttribute> {
+        let t = &self.tables;
+        let parent_size = t.coded_size(HAS_CUSTOM_ATTRIBUTE);
+        let type_size = t.coded_size(CUSTOM_ATTRIBUTE_TYPE);
+
+        (1..=t.rows[TABLE_CUSTOM_ATTRIBUTE])
+            .filter_map(|row| {
+                let base = t.row_offset(TABLE_CUSTOM_ATTRIBUTE, row)?;
+                let parent = t.read_index(base, parent_size)?;
+                let ctor = t.read_index(base + parent_size, type_size)?;
+                let value = t.read_index(base + parent_size + type_size, t.blob_index_size)?;
+
+                let (parent_table, parent_row) = decode_coded_index(parent, HAS_CUSTOM_ATTRIBUTE)?;
+                if parent_table != TABLE_TYPE_DEF {
+                    return None;
+                }
+
+                let (type_name, _) = self.type_name(TABLE_TYPE_DEF, parent_row)?;
+                let ((attribute_name, attribute_namespace), ctor_params) = self.resolve_constructor(ctor)?;
+
+                Some(TypeAttribute {
+                    type_name,
+                    attribute_name,
+                    attribute_namespace,
+                    ctor_params,
+                    value: self.blob(value)?.to_vec(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl<'a> Tables<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let heap_sizes = *data.get(6)?;
+        let valid = u64::from_le_bytes(data.get(8..16)?.try_into().ok()?);
+
+        let mut rows = [0u32; 64];
+        let mut offset = 24;
+        for (table, count) in rows.iter_mut().enumerate() {
+            if valid & (1 << table) != 0 {
+                *count = read_u32(data, offset)?;
+                offset += 4;
+            }
+        }
+
+        // Uncompressed (#-) streams may carry 4 extra bytes here
+        if heap_sizes & 0x40 != 0 {
+            offset += 4;
+        }
+
+        let mut tables = Self {
+            data,
+            rows,
+            offsets: [0; 64],
+            row_sizes: [0; 64],
+            string_index_size: if heap_sizes & 0x01 != 0 { 4 } else { 2 },
+            blob_index_size: if heap_sizes & 0x04 != 0 { 4 } else { 2 },
+        };
+        let guid_size = if heap_sizes & 0x02 != 0 { 4 } else { 2 };
+
+        // We only read up to CustomAttribute, so only earlier tables need sizes
+        for table in 0..=TABLE_CUSTOM_ATTRIBUTE {
+            let row_size = tables.row_size(table, guid_size)?;
+            tables.row_sizes[table] = row_size;
+            tables.offsets[table] = offset;
+            offset += row_size * tables.rows[table] as usize;
+        }
+
+        Some(tables)
+    }
+
+    fn row_size(&self, table: usize, guid: usize) -> Option<usize> {
+        let s = self.string_index_size;
+        let b = self.blob_index_size;
+        let idx = |t: usize| self.table_index_size(t);
+
+        Some(match table {
+            TABLE_MODULE => 2 + s + guid * 3,
+            TABLE_TYPE_REF => self.coded_size(RESOLUTION_SCOPE) + s * 2,
+            TABLE_TYPE_DEF => 4 + s * 2 + self.coded_size(TYPE_DEF_OR_REF) + idx(TABLE_FIELD) + idx(TABLE_METHOD_DEF),
+            TABLE_FIELD_PTR => idx(TABLE_FIELD),
+            TABLE_FIELD => 2 + s + b,
+            TABLE_METHOD_PTR => idx(TABLE_METHOD_DEF),
+            TABLE_METHOD_DEF => 8 + s + b + idx(TABLE_PARAM),
+            TABLE_PARAM_PTR => idx(TABLE_PARAM),
+            TABLE_PARAM => 4 + s,
+            TABLE_INTERFACE_IMPL => idx(TABLE_TYPE_DEF) + self.coded_size(TYPE_DEF_OR_REF),
+            TABLE_MEMBER_REF => self.coded_size(MEMBER_REF_PARENT) + s + b,
+            TABLE_CONSTANT => 2 + self.coded_size(HAS_CONSTANT) + b,
+            TABLE_CUSTOM_ATTRIBUTE => self.coded_size(HAS_CUSTOM_ATTRIBUTE) + self.coded_size(CUSTOM_ATTRIBUTE_TYPE) + b,
+            _ => return None,
+        })
+    }
+
+    fn table_index_size(&self, table: usize) -> usize {
+        if self.rows[table] < 0x1_0000 { 2 } else { 4 }
+    }
+
+    fn coded_size(&self, tables: &[usize]) -> usize {
+        let tag_bits = tag_bits(tables.len());
+        let max_rows = tables
+            .iter()
+            .filter(|&&t| t < 64)
+            .map(|&t| self.rows[t])
+            .max()
+            .unwrap_or(0);
+        if max_rows < (1 << (16 - tag_bits)) { 2 } else { 4 }
+    }
+
+    /// File offset of a 1-based row within the #~ stream
+    fn row_offset(&self, table: usize, row: u32) -> Option<usize> {
+        if row == 0 || row > self.rows[table] {
+            return None;
+        }
+        Some(self.offsets[table] + (row as usize - 1) * self.row_sizes[table])
+    }
+
+    fn read_index(&self, offset: usize, size: usize) -> Option<u32> {
+        match size {
+            2 => read_u16(self.data, offset).map(u32::from),
+            _ => read_u32(self.data, offset),
+        }
+    }
+}
+
+fn tag_bits(count: usize) -> u32 {
+    usize::BITS - (count - 1).leading_zeros()
+}
+
+fn decode_coded_index(value: u32, tables: &[usize]) -> Option<(usize, u32)> {
+    let bits = tag_bits(tables.len());
+    let tag = (value & ((1 << bits) - 1)) as usize;
+    let table = *tables.get(tag)?;
+    (table < 64).then_some((table, value >> bits))
+}
+
+/// Reads an ECMA-335 compressed unsigned integer, returning (value, bytes used)
+pub fn read_compressed_u32(data: &[u8]) -> Option<(u32, usize)> {
+    let first = *data.first()? as u32;
+    if first & 0x80 == 0 {
+        Some((first, 1))
+    } else if first & 0xC0 == 0x80 {
+        Some((((first & 0x3F) << 8) | *data.get(1)? as u32, 2))
+    } else {
+        let b = data.get(1..4)?;
+        Some((
+            ((first & 0x1F) << 24) | ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | b[2] as u32,
+            4,
+        ))
+    }
+}
+
+/// Reads the parameter types from a method signature blob
+fn parse_method_params(signature: &[u8]) -> Option<Vec<ParamKind>> {
+    let mut offset = 1; // calling convention
+    let (count, used) = read_compressed_u32(signature.get(offset..)?)?;
+    offset += used;
+
+    // Constructors return void
+    if *signature.get(offset)? != 0x01 {
+        return None;
+    }
+    offset += 1;
+
+    let mut params = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let element = *signature.get(offset)?;
+        offset += 1;
+        let kind = match element {
+            0x0E => ParamKind::String,
+            0x08 => ParamKind::Int32,
+            0x11 => {
+                // Value type (enum) followed by a TypeDefOrRef coded token
+                let (_, used) = read_compressed_u32(signature.get(offset..)?)?;
+                offset += used;
+                ParamKind::Int32
+            }
+            _ => ParamKind::Other,
+        };
+        params.push(kind);
+
+        // We can't size arbitrary types, so decoding stops at the first unknown one
+        if kind == ParamKind::Other {
+            break;
+        }
+    }
+
+    Some(params)
+}
+
+/// A decoded fixed constructor argument
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeArg {
+    String(Option<String>),
+    Int32(i32),
+}
+
+/// Decodes the fixed arguments of a custom attribute value blob
+pub fn decode_fixed_args(value: &[u8], params: &[ParamKind]) -> Option<Vec<AttributeArg>> {
+    if read_u16(value, 0)? != 0x0001 {
+        return None;
+    }
+
+    let mut offset = 2;
+    let mut args = Vec::with_capacity(params.len());
+
+    for param in params {
+        match param {
+            ParamKind::String => {
+                // SerString: 0xFF means null, otherwise compressed length + UTF-8
+                if *value.get(offset)? == 0xFF {
+                    offset += 1;
+                    args.push(AttributeArg::String(None));
+                } else {
+                    let (len, used) = read_compressed_u32(value.get(offset..)?)?;
+                    offset += used;
+                    let bytes = value.get(offset..offset + len as usize)?;
+                    offset += len as usize;
+                    args.push(AttributeArg::String(Some(String::from_utf8_lossy(bytes).to_string())));
+                }
+            }
+            ParamKind::Int32 => {
+                args.push(AttributeArg::Int32(read_u32(value, offset)? as i32));
+                offset += 4;
+            }
+            ParamKind::Other => break,
+        }
+    }
+
+    Some(args)
+}
+
+/// Reads all type-level custom attributes from a .NET DLL on disk
+pub fn read_type_attributes(path: &Path) -> Result<Vec<TypeAttribute>, String> {
+    let pe = PeFile::open(path)?;
+    let metadata = Metadata::parse(&pe)
+        .ok_or_else(|| format!("Not a .NET assembly: {}", path.display()))?;
+    Ok(metadata.type_attributes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn reads_compressed_integers() {
+        // Examples from ECMA-335 II.23.2
+        assert_eq!(read_compressed_u32(&[0x03]), Some((0x03, 1)));
+        assert_eq!(read_compressed_u32(&[0x7F]), Some((0x7F, 1)));
+        assert_eq!(read_compressed_u32(&[0x80, 0x80]), Some((0x80, 2)));
+        assert_eq!(read_compressed_u32(&[0xBF, 0xFF]), Some((0x3FFF, 2)));
+        assert_eq!(read_compressed_u32(&[0xC0, 0x00, 0x40, 0x00]), Some((0x4000, 4)));
+        assert_eq!(read_compressed_u32(&[0xC0, 0x00]), None);
+        assert_eq!(read_compressed_u32(&[]), None);
+    }
+
+    #[test]
+    fn parses_constructor_parameters() {
+        // BepInPlugin(string GUID, string Name, string Version)
+        let plugin = [0x20, 0x03, 0x01, 0x0E, 0x0E, 0x0E];
+        assert_eq!(
+            parse_method_params(&plugin),
+            Some(vec![ParamKind::String, ParamKind::String, ParamKind::String])
+        );
+
+        // BepInDependency(string, DependencyFlags) with the enum as a value type token
+        let dependency = [0x20, 0x02, 0x01, 0x0E, 0x11, 0x49];
+        assert_eq!(parse_method_params(&dependency), Some(vec![ParamKind::String, ParamKind::Int32]));
+
+        // Decoding stops at the first type it can't size
+        let object = [0x20, 0x02, 0x01, 0x1C, 0x0E];
+        assert_eq!(parse_method_params(&object), Some(vec![ParamKind::Other]));
+
+        // Not a constructor: returns int
+        assert_eq!(parse_method_params(&[0x20, 0x00, 0x08]), None);
+    }
+
+    #[test]
+    fn decodes_attribute_arguments() {
+        let mut blob = vec![0x01, 0x00];
+        blob.extend([0x03]);
+        blob.extend(b"a.b");
+        blob.push(0xFF); // null string
+        blob.extend(2i32.to_le_bytes());
+        blob.extend([0x00, 0x00]); // no named arguments
+
+        let params = [ParamKind::String, ParamKind::String, ParamKind::Int32];
+        assert_eq!(
+            decode_fixed_args(&blob, &params),
+            Some(vec![
+                AttributeArg::String(Some("a.b".to_string())),
+                AttributeArg::String(None),
+                AttributeArg::Int32(2),
+            ])
+        );
+
+        // Wrong prolog, or a value cut short
+        assert_eq!(decode_fixed_args(&[0x02, 0x00], &params), None);
+        assert_eq!(decode_fixed_args(&blob[..5], &params), None);
+    }
+
+    #[test]
+    fn decodes_coded_indexes() {
+        // CustomAttributeType has five tags, so three tag bits
+        assert_eq!(decode_coded_index((7 << 3) | 3, CUSTOM_ATTRIBUTE_TYPE), Some((TABLE_MEMBER_REF, 7)));
+        assert_eq!(decode_coded_index((7 << 3) | 2, CUSTOM_ATTRIBUTE_TYPE), Some((TABLE_METHOD_DEF, 7)));
+        // Unused and out of range tags
+        assert_eq!(decode_coded_index(7 << 3, CUSTOM_ATTRIBUTE_TYPE), None);
+        assert_eq!(decode_coded_index(6, CUSTOM_ATTRIBUTE_TYPE), None);
+    }
+
+    #[test]
+    fn reads_plugin_attributes_from_an_assembly() {
+        // Built by tests/fixtures/make_plugin_dll.py
+        let dir = TempDir::new("dotnet_fixture");
+        let path = dir.join("FixturePlugin.dll");
+        std::fs::write(&path, include_bytes!("../tests/fixtures/FixturePlugin.dll")).unwrap();
+
+        let attributes = read_type_attributes(&path).unwrap();
+        let decoded: Vec<(&str, Vec<AttributeArg>)> = attributes
+            .iter()
+            .map(|a| {
+                assert_eq!((a.type_name.as_str(), a.attribute_namespace.as_str()), ("FixturePlugin", "BepInEx"));
+                (a.attribute_name.as_str(), decode_fixed_args(&a.value, &a.ctor_params).unwrap())
+            })
+            .collect();
+
+        let string = |s: &str| AttributeArg::String(Some(s.to_string()));
+        assert_eq!(
+            decoded,
+            [
+                ("BepInPlugin", vec![string("com.bassy.fixture"), string("Fixture Plugin"), string("1.2.3")]),
+                ("BepInDependency", vec![string("com.bassy.fm26.stadiuminjection"), AttributeArg::Int32(1)]),
+                ("BepInDependency", vec![string("com.bassy.fm26.audioinject"), AttributeArg::Int32(2)]),
+                ("BepInDependency", vec![string("com.bassy.core"), string("1.0.0")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_assemblies() {
+        let dir = TempDir::new("dotnet_not_pe");
+        let path = dir.join("plugin.dll");
+        std::fs::write(&path, b"not a PE file").unwrap();
+        assert!(read_type_attributes(&path).is_err());
+    }
+}
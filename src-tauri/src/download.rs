@@ -1,7 +1,8 @@
+use crate::archive::Archive;
 use futures_util::StreamExt;
 use reqwest::Client;
 use serde::Serialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -13,6 +14,9 @@ use tokio::sync::Mutex;
 /// Default R2 URL for the BepInEx pack
 pub const BEPINEX_R2_URL: &str = "https://r2.justinlevine.me/bepinex_pack.zip";
 
+/// Name downloads are saved under; the extension is added once the format is known
+const DOWNLOAD_NAME: &str = "bepinex_pack_download";
+
 /// Progress information emitted during download
 #[derive(Clone, Serialize)]
 pub struct DownloadProgress {
@@ -150,34 +154,38 @@ async fn download_file_with_progress(
     Ok(())
 }
 
-/// Validates that a file is a valid zip archive
-fn validate_zip_file(path: &PathBuf) -> Result<(), String> {
-    let file = std::fs::File::open(path)
-        .map_err(|e| format!("Failed to open file for validation: {}", e))?;
-
-    let archive = zip::ZipArchive::new(file)
-        .map_err(|e| format!("Invalid zip file: {}", e))?;
+/// Validates a downloaded pack by its actual format, not its name
+/// The file is renamed to the matching extension; returns the new path.
+fn validate_archive_file(path: &Path) -> Result<PathBuf, String> {
+    let archive = Archive::open(path)
+        .map_err(|e| format!("Invalid pack archive: {}", e))?;
 
     // Check that it has some content
-    if archive.len() == 0 {
-        return Err("Zip file is empty".to_string());
+    if archive.entries().is_empty() {
+        return Err("Archive is empty".to_string());
     }
 
-    Ok(())
+    let validated = path.with_file_name(format!("{}.{}", DOWNLOAD_NAME, archive.format().extension()));
+    if validated != path {
+        std::fs::rename(path, &validated)
+            .map_err(|e| format!("Failed to rename downloaded pack: {}", e))?;
+    }
+
+    Ok(validated)
 }
 
 /// Downloads BepInEx pack from the default R2 URL
 #[tauri::command]
 pub async fn download_bepinex_from_r2(app: AppHandle) -> Result<String, String> {
     let temp_dir = std::env::temp_dir();
-    let dest_path = temp_dir.join("bepinex_pack_download.zip");
+    let dest_path = temp_dir.join(DOWNLOAD_NAME);
 
     download_file_with_progress(&app, BEPINEX_R2_URL, &dest_path).await?;
 
     // Validate the downloaded file
-    validate_zip_file(&dest_path)?;
+    let pack_path = validate_archive_file(&dest_path)?;
 
-    Ok(dest_path.to_string_lossy().to_string())
+    Ok(pack_path.to_string_lossy().to_string())
 }
 
 /// Downloads BepInEx pack from a custom URL
@@ -189,14 +197,14 @@ pub async fn download_bepinex_from_url(app: AppHandle, url: String) -> Result<St
     }
 
     let temp_dir = std::env::temp_dir();
-    let dest_path = temp_dir.join("bepinex_pack_download.zip");
+    let dest_path = temp_dir.join(DOWNLOAD_NAME);
 
     download_file_with_progress(&app, &url, &dest_path).await?;
 
     // Validate the downloaded file
-    validate_zip_file(&dest_path)?;
+    let pack_path = validate_archive_file(&dest_path)?;
 
-    Ok(dest_path.to_string_lossy().to_string())
+    Ok(pack_path.to_string_lossy().to_string())
 }
 
 /// Cancels an in-progress download
@@ -0,0 +1,168 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An app install resolved through the Epic Games Launcher's metadata
+#[derive(Debug, Clone)]
+pub struct EpicAppInstall {
+    pub app_name: String,
+    pub display_name: Option<String>,
+    pub install_path: PathBuf,
+}
+
+/// Subset of an Epic launcher `Manifests/*.item` file
+#[derive(Debug, Deserialize)]
+struct EpicItemManifest {
+    #[serde(rename = "AppName", default)]
+    app_name: String,
+    #[serde(rename = "MainGameAppName", default)]
+    main_game_app_name: String,
+    #[serde(rename = "DisplayName", default)]
+    display_name: String,
+    #[serde(rename = "InstallLocation", default)]
+    install_location: String,
+    #[serde(rename = "bIsIncompleteInstall", default)]
+    is_incomplete_install: bool,
+}
+
+/// `LauncherInstalled.dat` is a JSON document listing every installed app
+#[derive(Debug, Deserialize)]
+struct LauncherInstalled {
+    #[serde(rename = "InstallationList", default)]
+    installation_list: Vec<LauncherInstalledEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LauncherInstalledEntry {
+    #[serde(rename = "InstallLocation", default)]
+    install_location: String,
+    #[serde(rename = "AppName", default)]
+    app_name: String,
+}
+
+/// Root of the Epic launcher's shared data directory
+fn epic_data_root() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        let program_data = std::env::var("PROGRAMDATA").unwrap_or_else(|_| r"C:\ProgramData".to_string());
+        Some(PathBuf::from(program_data).join("Epic"))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        dirs::home_dir().map(|home| home.join("Library/Application Support/Epic"))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Checks whether an Epic display name refers to FM26 rather than another FM edition
+fn is_fm26_display_name(display_name: &str) -> bool {
+    let name = display_name.to_lowercase();
+    name.contains("football manager") && (name.contains("2026") || name.contains(" 26"))
+}
+
+/// Checks whether an install folder name looks like FM26 (e.g. "FootballManager2026")
+fn is_fm26_folder_name(path: &Path) -> bool {
+    let name: String = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect();
+
+    name.starts_with("footballmanager") && (name.ends_with("2026") || name.ends_with("26"))
+}
+
+/// Reads every `Manifests/*.item` file in the launcher data directory
+fn read_item_manifests(data_root: &Path) -> Vec<EpicItemManifest> {
+    let manifests_dir = data_root
+        .join("EpicGamesLauncher")
+        .join("Data")
+        .join("Manifests");
+
+    let Ok(entries) = fs::read_dir(&manifests_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("item")))
+        .filter_map(|path| fs::read_to_string(&path).ok())
+        .filter_map(|content| serde_json::from_str::<EpicItemManifest>(&content).ok())
+        .collect()
+}
+
+/// Reads the install list from `UnrealEngineLauncher/LauncherInstalled.dat`
+fn read_launcher_installed(data_root: &Path) -> Vec<LauncherInstalledEntry> {
+    let dat_path = data_root
+        .join("UnrealEngineLauncher")
+        .join("LauncherInstalled.dat");
+
+    fs::read_to_string(&dat_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<LauncherInstalled>(&content).ok())
+        .map(|installed| installed.installation_list)
+        .unwrap_or_default()
+}
+
+/// Finds FM26 installs registered with the Epic Games Launcher
+pub fn find_fm26_installs() -> Vec<EpicAppInstall> {
+    let Some(data_root) = epic_data_root() else {
+        return Vec::new();
+    };
+
+    let mut installs: Vec<EpicAppInstall> = Vec::new();
+
+    // Item manifests carry the display name, so they identify FM26's AppName
+    for manifest in read_item_manifests(&data_root) {
+        if manifest.is_incomplete_install || manifest.install_location.is_empty() {
+            continue;
+        }
+
+        // Skip DLC manifests, which point back at the main game's AppName
+        if !manifest.main_game_app_name.is_empty() && manifest.main_game_app_name != manifest.app_name {
+            continue;
+        }
+
+        if !is_fm26_display_name(&manifest.display_name) {
+            continue;
+        }
+
+        installs.push(EpicAppInstall {
+            app_name: manifest.app_name,
+            display_name: Some(manifest.display_name),
+            install_path: PathBuf::from(manifest.install_location),
+        });
+    }
+
+    // LauncherInstalled.dat survives when manifests are cleaned up, but has no display name
+    for entry in read_launcher_installed(&data_root) {
+        if entry.install_location.is_empty() {
+            continue;
+        }
+
+        let install_path = PathBuf::from(&entry.install_location);
+        let known_app = installs.iter().any(|i| i.app_name == entry.app_name);
+        if !known_app && !is_fm26_folder_name(&install_path) {
+            continue;
+        }
+
+        if installs.iter().any(|i| i.install_path == install_path) {
+            continue;
+        }
+
+        installs.push(EpicAppInstall {
+            app_name: entry.app_name,
+            display_name: None,
+            install_path,
+        });
+    }
+
+    installs
+}
@@ -1,169 +1,219 @@
-use crate::models::{BepInExStatus, Fm26Installation, PluginStatus};
+use crate::models::{BepInExStatus, DetectedInstall, Fm26Installation, GameStore, InstallEvidence, MigrationReport, PackKind, PackSource, PluginDependency, PluginState, PluginStatus, ConflictAction, ConflictResolution, RenamedBundle, RouteDestination, StadiumPackReport};
+use crate::archive::{self, Archive};
+use crate::install_progress::InstallToken;
+use crate::{archive_guard, backups, bepinex, conflicts, epic, game_version, install_manifest, install_progress, layout, migrate, preflight, proton, registry, routing, staging, steam};
 use std::fs;
-use std::io;
 use std::path::{Path, PathBuf};
-use chrono::Local;
 use tauri::Manager;
-use zip::ZipArchive;
+
+/// Folder name FM26 uses under steamapps/common when no appmanifest is available
+const FM26_STEAM_FOLDER: &str = "Football Manager 2026";
 
 /// Detects possible FM26 installation paths on the system
 #[tauri::command]
-pub fn detect_fm26_paths() -> Vec<String> {
-    let mut paths: Vec<String> = Vec::new();
-
-    #[cfg(target_os = "windows")]
-    {
-        // Common Steam paths
-        let steam_paths = vec![
-            r"C:\Program Files (x86)\Steam\steamapps\common\Football Manager 2026",
-            r"C:\Program Files\Steam\steamapps\common\Football Manager 2026",
-            r"D:\Steam\steamapps\common\Football Manager 2026",
-            r"D:\SteamLibrary\steamapps\common\Football Manager 2026",
-            r"E:\Steam\steamapps\common\Football Manager 2026",
-            r"E:\SteamLibrary\steamapps\common\Football Manager 2026",
-            r"F:\Steam\steamapps\common\Football Manager 2026",
-            r"F:\SteamLibrary\steamapps\common\Football Manager 2026",
-        ];
-
-        // Common Epic Games paths
-        let epic_paths = vec![
-            r"C:\Program Files\Epic Games\FootballManager2026",
-            r"C:\Program Files (x86)\Epic Games\FootballManager2026",
-            r"D:\Epic Games\FootballManager2026",
-            r"E:\Epic Games\FootballManager2026",
-        ];
-
-        // Check Steam paths
-        for path_str in steam_paths {
-            let path = Path::new(path_str);
-            if path.exists() && is_valid_fm26_dir(path) {
-                paths.push(path_str.to_string());
-            }
-        }
+pub fn detect_fm26_paths() -> Vec<DetectedInstall> {
+    let mut installs: Vec<DetectedInstall> = Vec::new();
 
-        // Check Epic paths
-        for path_str in epic_paths {
-            let path = Path::new(path_str);
-            if path.exists() && is_valid_fm26_dir(path) {
-                paths.push(path_str.to_string());
-            }
-        }
+    // Steam installs resolved through libraryfolders.vdf and appmanifest_<appid>.acf
+    for app in steam::find_app_installs(steam::FM26_STEAM_APP_ID) {
+        push_if_valid(&mut installs, &app.install_path, GameStore::Steam);
+    }
 
-        // Try to find Steam library folders from libraryfolders.vdf
-        if let Some(steam_libs) = find_steam_library_folders() {
-            for lib in steam_libs {
-                let fm_path = lib.join("steamapps").join("common").join("Football Manager 2026");
-                if fm_path.exists() && is_valid_fm26_dir(&fm_path) {
-                    let path_str = fm_path.to_string_lossy().to_string();
-                    if !paths.contains(&path_str) {
-                        paths.push(path_str);
-                    }
-                }
-            }
-        }
+    // Fall back to the default folder name in each library, for installs whose
+    // appmanifest has gone missing (e.g. a game folder copied between drives)
+    for (_, library) in steam::find_all_library_folders() {
+        let fm_path = library.join("steamapps").join("common").join(FM26_STEAM_FOLDER);
+        push_if_valid(&mut installs, &fm_path, GameStore::Steam);
     }
 
-    #[cfg(target_os = "macos")]
-    {
-        // Steam on macOS
-        if let Some(home) = dirs::home_dir() {
-            let steam_path = home
-                .join("Library/Application Support/Steam/steamapps/common/Football Manager 2026");
-            if steam_path.exists() && is_valid_fm26_dir(&steam_path) {
-                paths.push(steam_path.to_string_lossy().to_string());
-            }
-        }
+    // Epic installs resolved through the launcher's manifests
+    for app in epic::find_fm26_installs() {
+        push_if_valid(&mut installs, &app.install_path, GameStore::Epic);
     }
 
-    #[cfg(target_os = "linux")]
-    {
-        // Steam on Linux
-        if let Some(home) = dirs::home_dir() {
-            let steam_paths = vec![
-                home.join(".steam/steam/steamapps/common/Football Manager 2026"),
-                home.join(".local/share/Steam/steamapps/common/Football Manager 2026"),
-            ];
-            for steam_path in steam_paths {
-                if steam_path.exists() && is_valid_fm26_dir(&steam_path) {
-                    paths.push(steam_path.to_string_lossy().to_string());
-                }
-            }
+    installs
+}
+
+/// Minimum confidence for a folder to be reported by auto-detection
+const MIN_DETECT_CONFIDENCE: u8 = 50;
+
+/// Adds a detected path if it looks like FM26 and hasn't been found already
+fn push_if_valid(installs: &mut Vec<DetectedInstall>, path: &Path, store: GameStore) {
+    if !path.is_dir() {
+        return;
+    }
+
+    if installs.iter().any(|existing| steam::same_path(Path::new(&existing.path), path)) {
+        return;
+    }
+
+    let detected = probe_fm26_dir(path, store);
+    if detected.confidence >= MIN_DETECT_CONFIDENCE {
+        installs.push(detected);
+    }
+}
+
+/// Weight of each piece of evidence towards the confidence score
+fn evidence_weight(evidence: InstallEvidence) -> u32 {
+    match evidence {
+        InstallEvidence::FmExe => 30,
+        InstallEvidence::UnityDataFolder => 25,
+        InstallEvidence::GameAssemblyDll => 20,
+        InstallEvidence::UnityPlayerDll => 15,
+        InstallEvidence::SteamAppId => 10,
+    }
+}
+
+/// How the game's Unity files are laid out on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GameLayout {
+    /// fm.exe next to `fm_Data`, UnityPlayer.dll and GameAssembly.dll
+    Windows,
+    /// An `fm.app` bundle with the data under Contents/Resources
+    MacOs,
+}
+
+impl GameLayout {
+    /// Layout of the build this system runs (Linux runs the Windows build under Proton)
+    fn host() -> Self {
+        if cfg!(target_os = "macos") {
+            GameLayout::MacOs
+        } else {
+            GameLayout::Windows
         }
     }
+}
+
+/// Finds the first top-level folder with a suffix, preferring `preferred`
+fn find_top_level_dir(path: &Path, preferred: &str, suffix: &str) -> Option<String> {
+    if path.join(preferred).is_dir() {
+        return Some(preferred.to_string());
+    }
 
-    paths
+    let mut candidates: Vec<String> = fs::read_dir(path)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(suffix))
+        .collect();
+
+    candidates.sort();
+    candidates.into_iter().next()
 }
 
-/// Checks if a directory looks like a valid FM26 installation
-fn is_valid_fm26_dir(path: &Path) -> bool {
-    // Look for common FM files/folders
-    let exe_path = path.join("fm.exe");
-    let exe_path_alt = path.join("Football Manager 2026.exe");
-    let data_path = path.join("data");
+/// Finds the macOS `.app` bundle, preferring `fm.app`
+fn find_app_bundle(path: &Path) -> Option<String> {
+    find_top_level_dir(path, "fm.app", ".app")
+}
 
-    exe_path.exists() || exe_path_alt.exists() || data_path.exists()
+/// Finds the Unity data folder relative to the root for a layout
+fn unity_data_folder(path: &Path, layout: GameLayout) -> Option<String> {
+    match layout {
+        GameLayout::Windows => find_top_level_dir(path, "fm_Data", "_Data"),
+        GameLayout::MacOs => {
+            let data = format!("{}/Contents/Resources/Data", find_app_bundle(path)?);
+            path.join(&data).is_dir().then_some(data)
+        }
+    }
 }
 
-/// Find Steam library folders from libraryfolders.vdf
-#[cfg(target_os = "windows")]
-fn find_steam_library_folders() -> Option<Vec<PathBuf>> {
-    let vdf_paths = vec![
-        PathBuf::from(r"C:\Program Files (x86)\Steam\steamapps\libraryfolders.vdf"),
-        PathBuf::from(r"C:\Program Files\Steam\steamapps\libraryfolders.vdf"),
-    ];
+/// Finds the Unity data folder relative to the root: `fm_Data` (or another
+/// `<exe>_Data`) on Windows, `fm.app/Contents/Resources/Data` on macOS
+pub fn find_unity_data_folder(path: &Path) -> Option<String> {
+    unity_data_folder(path, GameLayout::host())
+}
 
-    for vdf_path in vdf_paths {
-        if vdf_path.exists() {
-            if let Ok(content) = fs::read_to_string(&vdf_path) {
-                let mut folders = Vec::new();
-                // Simple parsing - look for "path" entries
-                for line in content.lines() {
-                    let line = line.trim();
-                    if line.starts_with("\"path\"") {
-                        // Extract path between quotes
-                        if let Some(start) = line.find("\"path\"") {
-                            let rest = &line[start + 6..];
-                            if let Some(path_start) = rest.find('"') {
-                                let rest = &rest[path_start + 1..];
-                                if let Some(path_end) = rest.find('"') {
-                                    let path_str = &rest[..path_end];
-                                    // Handle escaped backslashes
-                                    let path_str = path_str.replace("\\\\", "\\");
-                                    folders.push(PathBuf::from(path_str));
-                                }
-                            }
-                        }
-                    }
-                }
-                if !folders.is_empty() {
-                    return Some(folders);
-                }
-            }
+/// Path of a native Unity library (`UnityPlayer`, `GameAssembly`) for a layout
+fn native_library(path: &Path, layout: GameLayout, name: &str) -> Option<PathBuf> {
+    match layout {
+        GameLayout::Windows => Some(path.join(format!("{}.dll", name))),
+        GameLayout::MacOs => Some(
+            path.join(find_app_bundle(path)?)
+                .join("Contents")
+                .join("Frameworks")
+                .join(format!("{}.dylib", name)),
+        ),
+    }
+}
+
+/// Path of GameAssembly for the layout this system runs
+pub fn game_assembly_path(path: &Path) -> Option<PathBuf> {
+    native_library(path, GameLayout::host(), "GameAssembly")
+}
+
+/// Whether the game executable (or app bundle) is present for a layout
+fn has_game_executable(path: &Path, layout: GameLayout) -> bool {
+    match layout {
+        GameLayout::Windows => {
+            path.join("fm.exe").is_file() || path.join("Football Manager 2026.exe").is_file()
         }
+        GameLayout::MacOs => find_app_bundle(path).is_some(),
     }
-    None
 }
 
-#[cfg(not(target_os = "windows"))]
-fn find_steam_library_folders() -> Option<Vec<PathBuf>> {
-    None
+/// Collects the evidence that a directory is an FM26 installation
+pub fn probe_fm26_dir(path: &Path, store: GameStore) -> DetectedInstall {
+    probe_fm26_layout(path, store, GameLayout::host())
 }
 
-/// Inspects an FM26 installation directory and returns structured paths
-#[tauri::command]
-pub fn inspect_fm26_install(root_path: String) -> Result<Fm26Installation, String> {
-    let root = Path::new(&root_path);
+/// Collects the evidence for a specific game layout
+fn probe_fm26_layout(path: &Path, store: GameStore, layout: GameLayout) -> DetectedInstall {
+    let data_folder = unity_data_folder(path, layout);
+    let steam_app_id = steam::fm26_app_id_for_install(path);
+    let is_library = |name: &str| native_library(path, layout, name).is_some_and(|p| p.is_file());
+
+    let checks = [
+        (InstallEvidence::FmExe, has_game_executable(path, layout)),
+        (InstallEvidence::UnityDataFolder, data_folder.is_some()),
+        (InstallEvidence::UnityPlayerDll, is_library("UnityPlayer")),
+        (InstallEvidence::GameAssemblyDll, is_library("GameAssembly")),
+        (InstallEvidence::SteamAppId, steam_app_id.is_some()),
+    ];
+
+    let mut found = Vec::new();
+    let mut missing = Vec::new();
+    let mut score: u32 = 0;
+    let mut max_score: u32 = 0;
 
-    // Check if the root path exists
-    if !root.exists() {
-        return Err(format!("Directory does not exist: {}", root_path));
+    for (evidence, present) in checks {
+        // A Steam app id is only expected from Steam installs
+        if evidence == InstallEvidence::SteamAppId && store != GameStore::Steam && !present {
+            continue;
+        }
+
+        max_score += evidence_weight(evidence);
+        if present {
+            score += evidence_weight(evidence);
+            found.push(evidence);
+        } else {
+            missing.push(evidence);
+        }
     }
 
-    if !root.is_dir() {
-        return Err(format!("Path is not a directory: {}", root_path));
+    // A Steam app id means Steam owns the folder, whatever the caller assumed
+    let store = if steam_app_id.is_some() { GameStore::Steam } else { store };
+
+    DetectedInstall {
+        path: path.to_string_lossy().to_string(),
+        store,
+        confidence: (score * 100 / max_score.max(1)) as u8,
+        found,
+        missing,
+        data_folder,
+        steam_app_id,
     }
+}
 
-    // Build the expected paths
+/// Explains how closely a saved installation's folder matches FM26
+#[tauri::command]
+pub fn probe_fm26_install(app_handle: tauri::AppHandle, install_id: String) -> Result<DetectedInstall, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    Ok(probe_fm26_dir(Path::new(&install.root_path), GameStore::Manual))
+}
+
+/// Builds the paths of an installation from its root folder
+pub fn installation_paths(id: &str, root: &Path) -> Fm26Installation {
     let bep_in_ex_path = root.join("BepInEx");
     let plugins_path = bep_in_ex_path.join("plugins");
     let custom_stadium_path = plugins_path.join("CustomStadium");
@@ -171,147 +221,41 @@ pub fn inspect_fm26_install(root_path: String) -> Result<Fm26Installation, Strin
     let config_path = bep_in_ex_path.join("config");
     let log_path = bep_in_ex_path.join("LogOutput.log");
 
-    Ok(Fm26Installation {
-        root_path: root_path.clone(),
+    Fm26Installation {
+        id: id.to_string(),
+        root_path: root.to_string_lossy().to_string(),
         bep_in_ex_path: bep_in_ex_path.to_string_lossy().to_string(),
         plugins_path: plugins_path.to_string_lossy().to_string(),
         custom_stadium_path: custom_stadium_path.to_string_lossy().to_string(),
         audio_inject_path: audio_inject_path.to_string_lossy().to_string(),
         config_path: config_path.to_string_lossy().to_string(),
         log_path: log_path.to_string_lossy().to_string(),
-    })
-}
-
-/// Installs the BepInEx pack by extracting a zip file
-/// If zip_path is provided, uses that file; otherwise falls back to bundled resource
-#[tauri::command]
-pub fn install_bepinex_pack(
-    zip_path: Option<String>,
-    app_handle: tauri::AppHandle,
-    install: Fm26Installation,
-) -> Result<(), String> {
-    let root = Path::new(&install.root_path);
-    let bepinex_path = root.join("BepInEx");
-
-    // Backup existing BepInEx folder if it exists
-    if bepinex_path.exists() {
-        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let backup_name = format!("BepInEx_backup_{}", timestamp);
-        let backup_path = root.join(&backup_name);
-
-        // Remove any previous backup (keep only most recent)
-        if let Ok(entries) = fs::read_dir(root) {
-            for entry in entries.flatten() {
-                let name = entry.file_name();
-                let name_str = name.to_string_lossy();
-                if name_str.starts_with("BepInEx_backup_") && entry.path() != backup_path {
-                    if entry.path().is_dir() {
-                        let _ = fs::remove_dir_all(entry.path());
-                    }
-                }
-            }
-        }
-
-        // Rename current BepInEx to backup
-        fs::rename(&bepinex_path, &backup_path)
-            .map_err(|e| format!("Failed to backup existing BepInEx folder: {}", e))?;
     }
+}
 
-    // Determine the zip file path
-    let zip_file_path: PathBuf = if let Some(path) = zip_path {
-        // Use provided path (from download or local file selection)
-        let p = PathBuf::from(&path);
-        if !p.exists() {
-            return Err(format!("Zip file not found: {}", path));
-        }
-        p
-    } else {
-        // Fall back to bundled resource
-        let resource_path = app_handle
-            .path()
-            .resolve("resources/bepinex_pack.zip", tauri::path::BaseDirectory::Resource)
-            .map_err(|e| format!("Failed to resolve resource path: {}", e))?;
-
-        if !resource_path.exists() {
-            return Err("BepInEx pack not found. Please download from server or select a local file.".to_string());
-        }
-        resource_path
-    };
-
-    // Extract the zip file
-    let file = fs::File::open(&zip_file_path)
-        .map_err(|e| format!("Failed to open BepInEx pack: {}", e))?;
-
-    let mut archive = ZipArchive::new(file)
-        .map_err(|e| format!("Failed to read BepInEx pack archive: {}", e))?;
-
-    // Detect the root folder prefix to strip (e.g., "BepInExStadiums/" or "bepinex_pack/")
-    // Collect first few file names to analyze the structure
-    let mut first_names: Vec<String> = Vec::new();
-    for i in 0..archive.len().min(10) {
-        if let Ok(file) = archive.by_index(i) {
-            if let Some(path) = file.enclosed_name() {
-                first_names.push(path.to_string_lossy().to_string());
-            }
-        }
-    }
-
-    // Detect wrapper folder to strip (e.g., "bepinex_pack/" or "BepInExStadiums/")
-    // but NOT "BepInEx/" itself since that's the target folder structure
-    let strip_prefix: Option<String> = if !first_names.is_empty() {
-        if let Some(slash_pos) = first_names[0].find('/') {
-            let potential_root = &first_names[0][..=slash_pos];
-
-            // Don't strip "BepInEx/" - that's the actual folder we want to preserve
-            if potential_root.eq_ignore_ascii_case("BepInEx/") {
-                None
-            } else {
-                // Verify most entries start with this root
-                let matching_count = first_names.iter()
-                    .filter(|n| n.starts_with(potential_root))
-                    .count();
-
-                if matching_count >= first_names.len() / 2 {
-                    Some(potential_root.to_string())
-                } else {
-                    None
-                }
-            }
-        } else {
-            None
-        }
-    } else {
-        None
-    };
-
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)
-            .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+/// Extracts every file of an archive under a prefix into a folder, stripping the prefix
+/// The archive must pass the safety checks first, and each written file is
+/// checked against the size recorded in the archive.
+/// Returns the number of files written.
+fn extract_archive(
+    archive: &Archive,
+    dest: &Path,
+    strip_prefix: &str,
+    progress: &mut install_progress::ProgressReporter,
+) -> Result<u32, String> {
+    archive_guard::ensure_safe(&archive_guard::check_archive(archive, strip_prefix))?;
 
-        let file_path = match file.enclosed_name() {
-            Some(path) => path.to_path_buf(),
-            None => continue,
-        };
+    let mut files_extracted: u32 = 0;
 
-        // Strip the root folder prefix if detected
-        let relative_path = if let Some(ref prefix) = strip_prefix {
-            file_path
-                .to_string_lossy()
-                .strip_prefix(prefix)
-                .map(|s| std::path::PathBuf::from(s))
-                .unwrap_or(file_path)
-        } else {
-            file_path
+    archive.for_each_entry(|entry, contents| {
+        progress.begin_entry(entry)?;
+        let Some(relative_path) = layout::entry_relative_path(entry, strip_prefix) else {
+            return Ok(());
         };
 
-        // Skip if this is just the root folder itself
-        if relative_path.as_os_str().is_empty() {
-            continue;
-        }
-
-        let outpath = root.join(&relative_path);
+        let outpath = dest.join(&relative_path);
 
-        if file.name().ends_with('/') {
+        if entry.is_dir {
             fs::create_dir_all(&outpath)
                 .map_err(|e| format!("Failed to create directory {}: {}", outpath.display(), e))?;
         } else {
@@ -325,169 +269,556 @@ pub fn install_bepinex_pack(
             let mut outfile = fs::File::create(&outpath)
                 .map_err(|e| format!("Failed to create file {}: {}", outpath.display(), e))?;
 
-            io::copy(&mut file, &mut outfile)
-                .map_err(|e| format!("Failed to write file {}: {}", outpath.display(), e))?;
+            archive::copy_entry(entry, &mut progress.reader(contents), &mut outfile)?;
+
+            files_extracted += 1;
         }
 
         // Set permissions on Unix systems
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
-            if let Some(mode) = file.unix_mode() {
+            if let Some(mode) = entry.unix_mode {
                 fs::set_permissions(&outpath, fs::Permissions::from_mode(mode)).ok();
             }
         }
+
+        Ok(())
+    })?;
+
+    Ok(files_extracted)
+}
+
+/// Finds the BepInEx pack archive: the given file, or the bundled resource
+pub fn resolve_pack_path(zip_path: Option<&str>, app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    if let Some(path) = zip_path {
+        // Use provided path (from download or local file selection)
+        let p = PathBuf::from(path);
+        if !p.exists() {
+            return Err(format!("Archive not found: {}", path));
+        }
+        return Ok(p);
     }
 
-    Ok(())
+    // Fall back to bundled resource
+    let resource_path = app_handle
+        .path()
+        .resolve("resources/bepinex_pack.zip", tauri::path::BaseDirectory::Resource)
+        .map_err(|e| format!("Failed to resolve resource path: {}", e))?;
+
+    if !resource_path.exists() {
+        return Err("BepInEx pack not found. Please download from server or select a local file.".to_string());
+    }
+    Ok(resource_path)
 }
 
-/// Installs custom stadiums from a user-selected zip file
+/// Installs the BepInEx pack by extracting a .zip, .7z, .tar.gz or .tar.xz archive
+/// If zip_path is provided, uses that file; otherwise falls back to bundled resource.
+/// layout_prefix overrides the detected wrapper folder (see detect_archive_layout).
+/// The pack is extracted into a staging folder, verified, then swapped into place;
+/// on any error the previous BepInEx folder and loader files are put back.
+/// Every file written is recorded in BepInEx/install_manifest.json.
+/// User configs, mappings and added bundles are migrated from the old folder.
+/// Runs off the main thread, emitting install-progress; see cancel_install.
 #[tauri::command]
-pub fn install_custom_stadiums_pack(
-    zip_path: String,
+pub async fn install_bepinex_pack(
+    zip_path: Option<String>,
+    source: Option<PackSource>,
+    layout_prefix: Option<String>,
+    app_handle: tauri::AppHandle,
+    install_id: String,
+) -> Result<MigrationReport, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    run_install(move |token| install_bepinex_pack_blocking(zip_path, source, layout_prefix, app_handle, install, token)).await
+}
+
+/// Runs an install on a blocking thread, reporting a cancelled one as such
+async fn run_install<T: Clone + Send + Unpin + 'static>(
+    install: impl FnOnce(InstallToken) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let token = InstallToken::begin();
+    tauri::async_runtime::spawn_blocking(move || install(token))
+        .await
+        .map_err(|e| format!("Install task failed: {}", e))?
+        .map_err(|e| {
+            // Errors wrapping the cancellation, such as a failed copy, still mean cancelled
+            if token.was_stopped() {
+                install_progress::CANCELLED.to_string()
+            } else {
+                e
+            }
+        })
+}
+
+fn install_bepinex_pack_blocking(
+    zip_path: Option<String>,
+    source: Option<PackSource>,
+    layout_prefix: Option<String>,
+    app_handle: tauri::AppHandle,
     install: Fm26Installation,
-) -> Result<u32, String> {
-    let zip_file = Path::new(&zip_path);
+    token: InstallToken,
+) -> Result<MigrationReport, String> {
+    let root = Path::new(&install.root_path);
 
-    if !zip_file.exists() {
-        return Err(format!("Zip file not found: {}", zip_path));
-    }
+    let zip_file_path = resolve_pack_path(zip_path.as_deref(), &app_handle)?;
 
-    let custom_stadium_path = Path::new(&install.custom_stadium_path);
+    // Open the pack, whatever format it is in
+    let archive = Archive::open(&zip_file_path)
+        .map_err(|e| format!("Failed to read BepInEx pack: {}", e))?;
+
+    // Find where the pack's tree starts, however deeply it is wrapped
+    let layout = layout::resolve_layout(&archive, PackKind::BepInEx, layout_prefix)?;
+
+    // Refuse to start if the game is running, space is short or the folder isn't writable
+    let report = preflight::check_install(&archive, PackKind::BepInEx, &layout, &install)?;
+    preflight::ensure_ready(&report)?;
 
-    // Create CustomStadium directory if it doesn't exist
-    if !custom_stadium_path.exists() {
-        fs::create_dir_all(custom_stadium_path)
-            .map_err(|e| format!("Failed to create CustomStadium directory: {}", e))?;
+    // Extract and verify in staging; the game folder is untouched until this succeeds
+    let mut progress = install_progress::ProgressReporter::start(&app_handle, &archive, token);
+    let mut transaction = staging::Transaction::begin(root, root)?;
+    let staged = extract_archive(&archive, &transaction.staging_dir(), &layout.strip_prefix, &mut progress)
+        .and_then(|_| {
+            if transaction.staging_dir().join("BepInEx").is_dir() {
+                Ok(())
+            } else {
+                Err("BepInEx pack does not contain a BepInEx folder".to_string())
+            }
+        });
+    if let Err(e) = staged {
+        transaction.rollback();
+        return Err(e);
     }
 
-    // Open and extract the zip
-    let file = fs::File::open(zip_file)
-        .map_err(|e| format!("Failed to open zip file: {}", e))?;
+    // Swap: the old BepInEx folder is moved aside whole, root loader files one by one
+    progress.finish();
+    let source = match (source, zip_path) {
+        (Some(source), _) => source,
+        (None, Some(path)) => PackSource::LocalFile { path },
+        (None, None) => PackSource::Bundled,
+    };
+    let swapped = transaction.move_aside(Path::new("BepInEx"))
+        .and_then(|_| transaction.place_all())
+        .and_then(|written| {
+            // Hash what the pack wrote before user data is merged into it
+            let mut manifest = install_manifest::build_manifest(root, &zip_file_path, source, &written)?;
+
+            // Carry configs, mappings and added bundles over from the old folder
+            let migration = migrate::migrate_user_data(
+                &transaction.replaced_path(Path::new("BepInEx")),
+                &root.join("BepInEx"),
+            );
+
+            // Record what was written so it can be verified or removed later
+            install_manifest::mark_user_data(&mut manifest, &migration.migrated);
+            install_manifest::write_manifest(root, &manifest)?;
+            Ok(migration)
+        });
+    let migration = match swapped {
+        Ok(migration) => migration,
+        Err(e) => {
+            transaction.rollback();
+            return Err(e);
+        }
+    };
+
+    // Keep the replaced BepInEx folder as a backup, pruning old backups
+    transaction.commit(|replaced| {
+        let previous = replaced.join("BepInEx");
+        if previous.is_dir() {
+            backups::store_backup(&app_handle, root, &previous)?;
+        }
+        Ok(())
+    })?;
 
-    let mut archive = ZipArchive::new(file)
-        .map_err(|e| format!("Failed to read zip archive: {}", e))?;
+    // Remember which game build this pack was installed against (non-fatal)
+    let _ = game_version::record_game_version(&install);
+    registry::refresh(&app_handle, &install.id);
 
-    // The zip has a root folder "CustomStadium/" that we need to strip
-    let strip_prefix = "CustomStadium/";
-    let mut files_extracted: u32 = 0;
+    Ok(migration)
+}
 
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)
-            .map_err(|e| format!("Failed to read archive entry: {}", e))?;
+/// Extracts a stadium pack into staging, each entry at its routed place under the root
+/// Mapping and config files are merged with the user's current ones rather than
+/// replacing them. Bundles identical to installed ones are skipped; differing ones follow the
+/// user's resolution, and any left unresolved fail the install.
+fn stage_routed(
+    archive: &Archive,
+    router: &routing::Router,
+    install: &Fm26Installation,
+    resolutions: &[ConflictResolution],
+    dest: &Path,
+    progress: &mut install_progress::ProgressReporter,
+) -> Result<StadiumPackReport, String> {
+    // Entries anywhere in the archive may be installed, so all of them are checked,
+    // and no two may land on the same file
+    let mut violations = archive_guard::check_archive(archive, "");
+    violations.extend(router.collisions(archive));
+    archive_guard::ensure_safe(&violations)?;
 
-        let file_path = match file.enclosed_name() {
-            Some(path) => path.to_path_buf(),
-            None => continue,
+    let root = Path::new(&install.root_path);
+    let custom_stadium_path = Path::new(&install.custom_stadium_path);
+    let incoming_bundles = conflicts::incoming_bundles(archive, router);
+
+    let mut report = StadiumPackReport {
+        destinations: router.summaries(root),
+        skipped: Vec::new(),
+        identical: Vec::new(),
+        kept: Vec::new(),
+        renamed: Vec::new(),
+    };
+    let mut unresolved = Vec::new();
+    let mut mappings = Vec::new();
+
+    archive.for_each_entry(|entry, contents| {
+        progress.begin_entry(entry)?;
+        let Some(mut route) = router.route(entry) else {
+            if !entry.is_dir {
+                report.skipped.push(entry.name.clone());
+            }
+            return Ok(());
         };
 
-        // Strip the root folder prefix
-        let relative_path = file_path
-            .to_string_lossy()
-            .strip_prefix(strip_prefix)
-            .map(|s| std::path::PathBuf::from(s))
-            .unwrap_or(file_path);
+        if route.merge {
+            let mut incoming = Vec::new();
+            archive::copy_entry(entry, &mut progress.reader(contents), &mut incoming)?;
+            mappings.push((route, String::from_utf8_lossy(&incoming).to_string()));
+            return Ok(());
+        }
 
-        // Skip if this is just the root folder itself
-        if relative_path.as_os_str().is_empty() {
-            continue;
+        let outpath = dest.join(&route.relative);
+        if let Some(parent) = outpath.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create parent directory: {}", e))?;
         }
 
-        let outpath = custom_stadium_path.join(&relative_path);
+        let mut outfile = fs::File::create(&outpath)
+            .map_err(|e| format!("Failed to create file {}: {}", outpath.display(), e))?;
+        archive::copy_entry(entry, &mut progress.reader(contents), &mut outfile)?;
+        drop(outfile);
 
-        if file.name().ends_with('/') {
-            fs::create_dir_all(&outpath)
-                .map_err(|e| format!("Failed to create directory {}: {}", outpath.display(), e))?;
-        } else {
-            if let Some(parent) = outpath.parent() {
-                if !parent.exists() {
-                    fs::create_dir_all(parent)
-                        .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+        let existing = root.join(&route.relative);
+        if route.destination == RouteDestination::CustomStadium && existing.is_file() {
+            let file = conflicts::bundle_name(&route.relative);
+            let mut staged = fs::File::open(&outpath)
+                .map_err(|e| format!("Failed to open staged {}: {}", outpath.display(), e))?;
+
+            // None: nothing to install, the staged copy is dropped
+            let action = if !conflicts::differs(&existing, entry.size, &mut staged)? {
+                report.identical.push(file.clone());
+                None
+            } else {
+                match resolutions.iter().find(|r| r.file.eq_ignore_ascii_case(&file)) {
+                    Some(resolution) => Some(resolution.action),
+                    None => {
+                        unresolved.push(file.clone());
+                        None
+                    }
+                }
+            };
+
+            match action {
+                None | Some(ConflictAction::Keep) => {
+                    if action.is_some() {
+                        report.kept.push(file);
+                    }
+                    drop(staged);
+                    let _ = fs::remove_file(&outpath);
+                    return Ok(());
+                }
+                Some(ConflictAction::Overwrite) => {}
+                Some(ConflictAction::Rename) => {
+                    drop(staged);
+                    let to = conflicts::rename_target(custom_stadium_path, &file, &incoming_bundles);
+                    route.relative = router.dir(RouteDestination::CustomStadium).join(&to);
+                    fs::rename(&outpath, dest.join(&route.relative))
+                        .map_err(|e| format!("Failed to rename {}: {}", file, e))?;
+                    report.renamed.push(RenamedBundle { from: file, to });
                 }
             }
+        }
 
-            let mut outfile = fs::File::create(&outpath)
-                .map_err(|e| format!("Failed to create file {}: {}", outpath.display(), e))?;
+        if let Some(summary) = report.destinations.iter_mut().find(|d| d.destination == route.destination) {
+            summary.files += 1;
+        }
+        Ok(())
+    })?;
+
+    if !unresolved.is_empty() {
+        return Err(format!(
+            "These bundles differ from the installed ones, choose to overwrite, keep or rename them: {}",
+            unresolved.join(", ")
+        ));
+    }
 
-            io::copy(&mut file, &mut outfile)
-                .map_err(|e| format!("Failed to write file {}: {}", outpath.display(), e))?;
+    for (route, incoming) in mappings {
+        let staged = dest.join(&route.relative);
 
-            files_extracted += 1;
+        // A second mapping or config file in the same pack merges into the first
+        let already_staged = staged.is_file();
+        let existing = if already_staged {
+            fs::read_to_string(&staged)
+        } else {
+            fs::read_to_string(root.join(&route.relative))
+        }
+        .unwrap_or_default();
+
+        let (merged, added, kept) = match route.destination {
+            // Settings the user already has keep their values
+            RouteDestination::Config if existing.is_empty() => (incoming, 0, 0),
+            RouteDestination::Config => (migrate::merge_pack_cfg(&existing, &incoming), 0, 0),
+            // The pack's own mappings follow its bundles to their new names
+            RouteDestination::StadiumInjection => {
+                let incoming = conflicts::rewrite_mapping_references(&incoming, &report.renamed);
+                routing::merge_mappings(&existing, &incoming, routing::team_mapping_key)
+            }
+            _ => routing::merge_mappings(&existing, &incoming, routing::audio_mapping_key),
+        };
+
+        if let Some(parent) = staged.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create parent directory: {}", e))?;
         }
+        fs::write(&staged, merged)
+            .map_err(|e| format!("Failed to write {}: {}", staged.display(), e))?;
 
-        // Set permissions on Unix systems
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            if let Some(mode) = file.unix_mode() {
-                fs::set_permissions(&outpath, fs::Permissions::from_mode(mode)).ok();
+        if let Some(summary) = report.destinations.iter_mut().find(|d| d.destination == route.destination) {
+            if !already_staged {
+                summary.files += 1;
             }
+            summary.mappings_added += added;
+            summary.mappings_kept += kept;
         }
     }
 
-    Ok(files_extracted)
+    Ok(report)
 }
 
-/// Gets the status of all BepInEx plugins
+/// Installs a stadium pack from a user-selected archive
+/// Entries are routed by content: bundles to CustomStadium, team and audio
+/// mappings merged into the plugins' own files, audio folders to AudioInject
+/// and .cfg files merged by key into BepInEx/config (BepInEx.cfg is never
+/// touched). Everything is staged and rolled back on error.
+/// Conflicting bundles need a resolution; see check_stadium_conflicts.
+/// Runs off the main thread, emitting install-progress; see cancel_install.
 #[tauri::command]
-pub fn get_plugin_status(install: Fm26Installation) -> Vec<PluginStatus> {
-    let plugins = vec![
-        ("StadiumInjection", "StadiumInjection/StadiumInjection.dll"),
-        // TODO: Re-enable AudioInject check when audio injection support is ready
-        // ("AudioInject", "AudioInject/AudioInject.dll"),
-        ("CrowdInject", "CrowdInject/CrowdInject.dll"),
-    ];
+pub async fn install_custom_stadiums_pack(
+    zip_path: String,
+    layout_prefix: Option<String>,
+    resolutions: Option<Vec<ConflictResolution>>,
+    app_handle: tauri::AppHandle,
+    install_id: String,
+) -> Result<StadiumPackReport, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    run_install(move |token| {
+        install_custom_stadiums_pack_blocking(zip_path, layout_prefix, resolutions, app_handle, install, token)
+    }).await
+}
 
+fn install_custom_stadiums_pack_blocking(
+    zip_path: String,
+    layout_prefix: Option<String>,
+    resolutions: Option<Vec<ConflictResolution>>,
+    app_handle: tauri::AppHandle,
+    install: Fm26Installation,
+    token: InstallToken,
+) -> Result<StadiumPackReport, String> {
+    let zip_file = Path::new(&zip_path);
+
+    if !zip_file.exists() {
+        return Err(format!("Archive not found: {}", zip_path));
+    }
+
+    let root = Path::new(&install.root_path);
+
+    let archive = Archive::open(zip_file)?;
+
+    // Bundles may sit at the archive root, in CustomStadium/ or under any wrapper folder
+    let layout = layout::resolve_layout(&archive, PackKind::CustomStadiums, layout_prefix)?;
+
+    let report = preflight::check_install(&archive, PackKind::CustomStadiums, &layout, &install)?;
+    preflight::ensure_ready(&report)?;
+    let router = routing::Router::new(&install, &layout.strip_prefix)?;
+
+    // Destinations span several plugin folders, so the whole root is the target
+    let mut progress = install_progress::ProgressReporter::start(&app_handle, &archive, token);
+    let mut transaction = staging::Transaction::begin(root, root)?;
+    let resolutions = resolutions.unwrap_or_default();
+    let installed = stage_routed(&archive, &router, &install, &resolutions, &transaction.staging_dir(), &mut progress)
+        .and_then(|report| transaction.place_all().map(|_| report));
+
+    match installed {
+        Ok(report) => {
+            progress.finish();
+            // Replaced bundles are dropped, as before
+            transaction.commit(|_| Ok(()))?;
+            Ok(report)
+        }
+        Err(e) => {
+            transaction.rollback();
+            Err(e)
+        }
+    }
+}
+
+/// Plugins shipped in the stadium pack: (name, GUID, path relative to plugins/)
+pub const KNOWN_PLUGINS: [(&str, &str, &str); 2] = [
+    ("StadiumInjection", "com.bassy.fm26.stadiuminjection", "StadiumInjection/StadiumInjection.dll"),
+    // TODO: Re-enable AudioInject check when audio injection support is ready
+    // ("AudioInject", "com.bassy.fm26.audioinject", "AudioInject/AudioInject.dll"),
+    ("CrowdInject", "com.bassy.fm26.crowdinject", "CrowdInject/CrowdInject.dll"),
+];
+
+/// Gets the status of all BepInEx plugins, including third-party ones
+#[tauri::command]
+pub fn get_plugin_status(app_handle: tauri::AppHandle, install_id: String) -> Result<Vec<PluginStatus>, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let plugins_path = Path::new(&install.plugins_path);
+    let proton_blocked = proton::blocks_bepinex(Path::new(&install.root_path));
+    let discovered = bepinex::discover_plugins(plugins_path);
+
+    let is_satisfied = |dep: &bepinex::DiscoveredDependency| {
+        discovered.iter().any(|p| {
+            p.guid == dep.guid
+                && dep
+                    .min_version
+                    .as_deref()
+                    .is_none_or(|min| bepinex::version_satisfies(&p.version, min))
+        })
+    };
+
+    let mut statuses: Vec<PluginStatus> = discovered
+        .iter()
+        .map(|plugin| {
+            let dependencies: Vec<PluginDependency> = plugin
+                .dependencies
+                .iter()
+                .map(|dep| PluginDependency {
+                    guid: dep.guid.clone(),
+                    min_version: dep.min_version.clone(),
+                    soft: dep.soft,
+                    satisfied: is_satisfied(dep),
+                })
+                .collect();
+
+            let missing_hard = dependencies.iter().any(|d| !d.soft && !d.satisfied);
+            let state = if proton_blocked {
+                PluginState::ProtonBlocked
+            } else if missing_hard {
+                PluginState::MissingDependency
+            } else {
+                PluginState::Installed
+            };
 
-    plugins
-        .into_iter()
-        .map(|(name, rel_path)| {
-            let full_path = plugins_path.join(rel_path);
             PluginStatus {
-                name: name.to_string(),
-                path: full_path.to_string_lossy().to_string(),
-                installed: full_path.exists(),
+                name: plugin.name.clone(),
+                path: plugin.path.to_string_lossy().to_string(),
+                installed: true,
+                state,
+                guid: Some(plugin.guid.clone()),
+                version: Some(plugin.version.clone()).filter(|v| !v.is_empty()),
+                dependencies,
             }
         })
-        .collect()
+        .collect();
+
+    // Always list the pack's own plugins, so a missing one is visible
+    for (name, guid, rel_path) in KNOWN_PLUGINS {
+        if discovered.iter().any(|p| p.guid == guid) {
+            continue;
+        }
+
+        // Present but unreadable metadata still counts as installed
+        let full_path = plugins_path.join(rel_path);
+        let installed = full_path.exists();
+        let state = match (installed, proton_blocked) {
+            (false, _) => PluginState::Missing,
+            (true, true) => PluginState::ProtonBlocked,
+            (true, false) => PluginState::Installed,
+        };
+
+        statuses.push(PluginStatus {
+            name: name.to_string(),
+            path: full_path.to_string_lossy().to_string(),
+            installed,
+            state,
+            guid: Some(guid.to_string()),
+            version: None,
+            dependencies: Vec::new(),
+        });
+    }
+
+    Ok(statuses)
 }
 
 /// Checks if BepInEx is installed and returns status for overwrite warning
 #[tauri::command]
-pub fn check_bepinex_installed(install: Fm26Installation) -> BepInExStatus {
+pub fn check_bepinex_installed(app_handle: tauri::AppHandle, install_id: String) -> Result<BepInExStatus, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let bepinex_path = Path::new(&install.bep_in_ex_path);
     let plugins_path = Path::new(&install.plugins_path);
 
     let installed = bepinex_path.exists();
 
-    // Count installed plugins if BepInEx exists
+    // Count plugins declared through [BepInPlugin] if BepInEx exists
     let mut plugin_count: u32 = 0;
     let mut has_plugins = false;
 
     if installed && plugins_path.exists() {
-        // Check for known plugins
-        let known_plugins = vec![
-            "StadiumInjection/StadiumInjection.dll",
-            // TODO: Re-enable AudioInject check when audio injection support is ready
-            // "AudioInject/AudioInject.dll",
-            "CrowdInject/CrowdInject.dll",
-        ];
-
-        for rel_path in known_plugins {
-            if plugins_path.join(rel_path).exists() {
-                plugin_count += 1;
-            }
-        }
-
+        plugin_count = bepinex::discover_plugins(plugins_path).len() as u32;
         has_plugins = plugin_count > 0;
     }
 
-    BepInExStatus {
+    // Loader versions and root files, for spotting mismatched installs
+    let loader = bepinex::inspect_loader(Path::new(&install.root_path));
+
+    Ok(BepInExStatus {
         installed,
         path: install.bep_in_ex_path,
         has_plugins,
         plugin_count,
+        core_version: loader.core_version,
+        flavor: loader.flavor,
+        doorstop_version: loader.doorstop_version,
+        has_winhttp_dll: loader.has_winhttp_dll,
+        has_doorstop_config: loader.has_doorstop_config,
+        loader_warnings: loader.warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn recognizes_a_windows_install() {
+        let root = TempDir::new("fm26_windows");
+        fs::create_dir_all(root.join("fm_Data")).unwrap();
+        for file in ["fm.exe", "UnityPlayer.dll", "GameAssembly.dll"] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        let detected = probe_fm26_layout(&root, GameStore::Manual, GameLayout::Windows);
+        assert_eq!(detected.confidence, 100);
+        assert_eq!(detected.data_folder.as_deref(), Some("fm_Data"));
+    }
+
+    #[test]
+    fn recognizes_a_macos_app_bundle() {
+        let root = TempDir::new("fm26_macos");
+        let contents = root.join("fm.app").join("Contents");
+        fs::create_dir_all(contents.join("Resources").join("Data")).unwrap();
+        fs::create_dir_all(contents.join("Frameworks")).unwrap();
+        fs::write(contents.join("Frameworks").join("UnityPlayer.dylib"), "").unwrap();
+        fs::write(contents.join("Frameworks").join("GameAssembly.dylib"), "").unwrap();
+
+        let detected = probe_fm26_layout(&root, GameStore::Manual, GameLayout::MacOs);
+        assert_eq!(detected.confidence, 100);
+        assert!(detected.missing.is_empty());
+        assert_eq!(detected.data_folder.as_deref(), Some("fm.app/Contents/Resources/Data"));
+
+        // The same folder has none of the Windows files
+        let as_windows = probe_fm26_layout(&root, GameStore::Manual, GameLayout::Windows);
+        assert!(as_windows.confidence < MIN_DETECT_CONFIDENCE);
     }
 }
@@ -0,0 +1,205 @@
+use crate::fm26::{find_unity_data_folder, game_assembly_path};
+use crate::hash::sha256_file;
+use crate::models::{Fm26Installation, GameVersion, GameVersionCheck, RecordedGameVersion};
+use crate::steam;
+use crate::registry;
+use chrono::Local;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// File under BepInEx/ recording the game version BepInEx was installed against
+pub const RECORDED_VERSION_FILE: &str = "installed_game_version.json";
+
+/// How much of a Unity file header to scan for the version string
+const UNITY_HEADER_SCAN_BYTES: u64 = 64 * 1024;
+
+/// GameAssembly.dll hashes by (path, size, mtime), so dashboard refreshes don't rehash 100+ MB
+static ASSEMBLY_HASHES: Mutex<Vec<(PathBuf, u64, SystemTime, String)>> = Mutex::new(Vec::new());
+
+/// Hashes GameAssembly.dll, reusing the last hash while its size and mtime are unchanged
+fn game_assembly_sha256(path: &Path, metadata: &fs::Metadata) -> Option<String> {
+    let size = metadata.len();
+    let modified = metadata.modified().ok()?;
+
+    let mut cache = ASSEMBLY_HASHES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, _, _, hash)) = cache
+        .iter()
+        .find(|(p, s, m, _)| p == path && *s == size && *m == modified)
+    {
+        return Some(hash.clone());
+    }
+
+    let hash = sha256_file(path).ok()?;
+    cache.retain(|(p, _, _, _)| p != path);
+    cache.push((path.to_path_buf(), size, modified, hash.clone()));
+    Some(hash)
+}
+
+/// Checks if a string looks like a Unity version, e.g. "2022.3.62f1" or "6000.0.58f2"
+fn is_unity_version(s: &str) -> bool {
+    let mut parts = s.splitn(3, '.');
+    let (Some(year), Some(minor), Some(rest)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+
+    let Some(tag_pos) = rest.find(['a', 'b', 'f', 'p', 'x']) else {
+        return false;
+    };
+    let (patch, tag) = rest.split_at(tag_pos);
+
+    year.len() == 4
+        && year.chars().all(|c| c.is_ascii_digit())
+        && !minor.is_empty()
+        && minor.chars().all(|c| c.is_ascii_digit())
+        && !patch.is_empty()
+        && patch.chars().all(|c| c.is_ascii_digit())
+        && tag.len() > 1
+        && tag[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+/// Finds the Unity version string in the header of a serialized file or UnityFS bundle
+fn read_unity_version_from(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    let mut header = Vec::new();
+    file.take(UNITY_HEADER_SCAN_BYTES).read_to_end(&mut header).ok()?;
+
+    // Both formats store the version as a null-terminated ASCII string near the start
+    header
+        .split(|b| *b == 0 || !b.is_ascii_graphic())
+        .filter_map(|chunk| std::str::from_utf8(chunk).ok())
+        .find(|s| is_unity_version(s))
+        .map(|s| s.to_string())
+}
+
+/// Reads the Unity engine version from globalgamemanagers or data.unity3d
+fn read_unity_version(root: &Path) -> Option<String> {
+    let data_path = root.join(find_unity_data_folder(root)?);
+
+    ["globalgamemanagers", "data.unity3d"]
+        .iter()
+        .map(|name| data_path.join(name))
+        .filter(|path| path.is_file())
+        .find_map(|path| read_unity_version_from(&path))
+}
+
+/// Probes the current game build of an FM26 installation
+pub fn probe_game_version(root: &Path) -> GameVersion {
+    let game_assembly = game_assembly_path(root);
+
+    let (game_assembly_sha256, game_assembly_size) = match game_assembly.as_deref().map(fs::metadata) {
+        Some(Ok(metadata)) if metadata.is_file() => (
+            game_assembly.as_deref().and_then(|path| game_assembly_sha256(path, &metadata)),
+            Some(metadata.len()),
+        ),
+        _ => (None, None),
+    };
+
+    GameVersion {
+        steam_build_id: steam::fm26_build_id_for_install(root),
+        unity_version: read_unity_version(root),
+        game_assembly_sha256,
+        game_assembly_size,
+    }
+}
+
+fn recorded_version_path(install: &Fm26Installation) -> PathBuf {
+    Path::new(&install.bep_in_ex_path).join(RECORDED_VERSION_FILE)
+}
+
+/// Records the current game build next to BepInEx, called after a pack install
+pub fn record_game_version(install: &Fm26Installation) -> Result<(), String> {
+    let recorded = RecordedGameVersion {
+        version: probe_game_version(Path::new(&install.root_path)),
+        recorded_at: Local::now().to_rfc3339(),
+    };
+
+    let json = serde_json::to_string_pretty(&recorded)
+        .map_err(|e| format!("Failed to serialize game version: {}", e))?;
+
+    fs::write(registry::install_file(install, &recorded_version_path(install))?, json)
+        .map_err(|e| format!("Failed to write {}: {}", RECORDED_VERSION_FILE, e))
+}
+
+fn read_recorded_version(install: &Fm26Installation) -> Option<RecordedGameVersion> {
+    let content = fs::read_to_string(recorded_version_path(install)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Checks whether the game changed since two versions were taken
+fn has_game_changed(current: &GameVersion, recorded: &GameVersion) -> bool {
+    // The GameAssembly.dll fingerprint is authoritative; fall back to build ids
+    match (&current.game_assembly_sha256, &recorded.game_assembly_sha256) {
+        (Some(current), Some(recorded)) => current != recorded,
+        _ => match (&current.steam_build_id, &recorded.steam_build_id) {
+            (Some(current), Some(recorded)) => current != recorded,
+            _ => false,
+        },
+    }
+}
+
+/// Gets the current FM26 build, Unity version and GameAssembly.dll fingerprint
+#[tauri::command]
+pub fn get_game_version(app_handle: tauri::AppHandle, install_id: String) -> Result<GameVersion, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    Ok(probe_game_version(Path::new(&install.root_path)))
+}
+
+/// Compares the current game build against the one BepInEx was installed on
+#[tauri::command]
+pub fn check_game_version(app_handle: tauri::AppHandle, install_id: String) -> Result<GameVersionCheck, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let current = probe_game_version(Path::new(&install.root_path));
+    let recorded = read_recorded_version(&install);
+
+    let game_updated = recorded
+        .as_ref()
+        .is_some_and(|r| has_game_changed(&current, &r.version));
+
+    let warning = game_updated.then(|| {
+        "The game updated since BepInEx was installed; interop cache and plugins may be stale".to_string()
+    });
+
+    Ok(GameVersionCheck {
+        current,
+        recorded_at: recorded.as_ref().map(|r| r.recorded_at.clone()),
+        installed_against: recorded.map(|r| r.version),
+        game_updated,
+        warning,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn recognizes_unity_versions() {
+        assert!(is_unity_version("2022.3.62f1"));
+        assert!(is_unity_version("6000.0.58f2"));
+        assert!(!is_unity_version("1.2.3"));
+        assert!(!is_unity_version("2022.3.62f"));
+    }
+
+    #[test]
+    fn rehashes_only_when_the_file_changes() {
+        let dir = TempDir::new("game_version_hash");
+        let path = dir.join("GameAssembly.dll");
+
+        fs::write(&path, b"first").unwrap();
+        let first = game_assembly_sha256(&path, &fs::metadata(&path).unwrap()).unwrap();
+        assert_eq!(first, crate::hash::sha256_bytes(b"first"));
+
+        // Same size and mtime: the cached hash is returned without reading the file
+        let metadata = fs::metadata(&path).unwrap();
+        fs::write(&path, b"other").unwrap();
+        assert_eq!(game_assembly_sha256(&path, &metadata).unwrap(), first);
+
+        fs::write(&path, b"changed!").unwrap();
+        let changed = game_assembly_sha256(&path, &fs::metadata(&path).unwrap()).unwrap();
+        assert_eq!(changed, crate::hash::sha256_bytes(b"changed!"));
+    }
+}
@@ -0,0 +1,28 @@
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Computes the SHA-256 of a file as a lowercase hex string
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+    sha256_reader(&mut file).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Computes the SHA-256 of everything a reader yields, e.g. a zip entry
+pub fn sha256_reader(reader: &mut impl Read) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(reader, &mut hasher)?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Computes the SHA-256 of in-memory bytes as a lowercase hex string
+pub fn sha256_bytes(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,84 @@
+use crate::hash::sha256_file;
+use crate::models::{InstallManifest, ManifestFile, PackSource};
+use crate::registry;
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Manifest location, relative to the game root
+pub const MANIFEST_PATH: &str = "BepInEx/install_manifest.json";
+
+/// Converts a path relative to the game root into the manifest's '/' form
+pub fn to_manifest_path(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Builds a manifest by hashing the pack and every file it wrote
+pub fn build_manifest(
+    root: &Path,
+    pack_path: &Path,
+    source: PackSource,
+    written: &[PathBuf],
+) -> Result<InstallManifest, String> {
+    let pack_sha256 = sha256_file(pack_path)?;
+
+    let files = written
+        .iter()
+        .map(|relative| {
+            let full_path = root.join(relative);
+            let size = fs::metadata(&full_path)
+                .map_err(|e| format!("Failed to read {}: {}", full_path.display(), e))?
+                .len();
+            Ok(ManifestFile {
+                path: to_manifest_path(relative),
+                size,
+                sha256: sha256_file(&full_path)?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(InstallManifest {
+        source,
+        pack_sha256,
+        installed_at: Local::now().to_rfc3339(),
+        files,
+        user_data: Vec::new(),
+    })
+}
+
+/// Records files migrated from the replaced install as user data
+/// `migrated` is relative to BepInEx/, as in MigrationReport. A migrated pack
+/// file keeps the pack's hash from before the merge, so it reads as changed by
+/// the user; uninstall leaves user data in place.
+pub fn mark_user_data(manifest: &mut InstallManifest, migrated: &[String]) {
+    manifest.user_data = migrated
+        .iter()
+        .map(|relative| format!("BepInEx/{}", relative))
+        .collect();
+}
+
+/// Writes the manifest into the game's BepInEx folder
+pub fn write_manifest(root: &Path, manifest: &InstallManifest) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize install manifest: {}", e))?;
+
+    fs::write(root.join(MANIFEST_PATH), json)
+        .map_err(|e| format!("Failed to write install manifest: {}", e))
+}
+
+/// Reads the manifest of the last pack install, if there is one
+pub fn read_manifest(root: &Path) -> Option<InstallManifest> {
+    let content = fs::read_to_string(root.join(MANIFEST_PATH)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Gets the manifest of files written by the last BepInEx pack install
+#[tauri::command]
+pub fn get_install_manifest(app_handle: tauri::AppHandle, install_id: String) -> Result<Option<InstallManifest>, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    Ok(read_manifest(Path::new(&install.root_path)))
+}
@@ -0,0 +1,231 @@
+use crate::archive::{Archive, ArchiveEntry};
+use crate::archive_guard;
+use crate::fm26::resolve_pack_path;
+use crate::layout::{self, entry_relative_path};
+use crate::hash::{sha256_file, sha256_reader};
+use crate::install_manifest::to_manifest_path;
+use crate::models::{
+    ArchiveLayout, ArchiveViolation, Fm26Installation, InstallPlan, PackKind, PlanTotal, PlannedAction, PlannedFile,
+    RouteDestination,
+};
+use crate::registry;
+use crate::routing::Router;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Collects every file under a directory, relative to `base`
+/// Symlinks are listed as files of their own and never followed.
+fn collect_relative_files(dir: &Path, base: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_relative_files(&path, base, files);
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            files.push(relative.to_path_buf());
+        }
+    }
+}
+
+fn summarize(files: &[PlannedFile]) -> Vec<PlanTotal> {
+    let mut totals: Vec<PlanTotal> = Vec::new();
+
+    for file in files {
+        match totals.iter_mut().find(|t| t.action == file.action) {
+            Some(total) => {
+                total.files += 1;
+                total.bytes += file.size;
+            }
+            None => totals.push(PlanTotal {
+                action: file.action,
+                files: 1,
+                bytes: file.size,
+            }),
+        }
+    }
+
+    totals
+}
+
+/// Opens the archive for an install, with its layout
+pub fn open_pack(
+    zip_path: Option<String>,
+    kind: PackKind,
+    layout_prefix: Option<String>,
+    app_handle: &tauri::AppHandle,
+) -> Result<(Archive, ArchiveLayout), String> {
+    let zip_file_path = match kind {
+        PackKind::BepInEx => resolve_pack_path(zip_path.as_deref(), app_handle)?,
+        PackKind::CustomStadiums => {
+            let path = zip_path.ok_or("No zip file selected")?;
+            PathBuf::from(path)
+        }
+    };
+
+    let archive = Archive::open(&zip_file_path)?;
+    let layout = layout::resolve_layout(&archive, kind, layout_prefix)?;
+
+    Ok((archive, layout))
+}
+
+/// Where an install puts archive entries, so plans and preflight match the installers
+/// The BepInEx pack is extracted by its layout; stadium packs are routed by content.
+pub enum Placement {
+    Layout { target: PathBuf, strip_prefix: String },
+    Routed { root: PathBuf, router: Router },
+}
+
+impl Placement {
+    pub fn new(kind: PackKind, layout: &ArchiveLayout, install: &Fm26Installation) -> Result<Self, String> {
+        Ok(match kind {
+            PackKind::BepInEx => Placement::Layout {
+                target: PathBuf::from(&install.root_path),
+                strip_prefix: layout.strip_prefix.clone(),
+            },
+            PackKind::CustomStadiums => Placement::Routed {
+                root: PathBuf::from(&install.root_path),
+                router: Router::new(install, &layout.strip_prefix)?,
+            },
+        })
+    }
+
+    /// Folder the plan is reported against; for stadium packs, CustomStadium
+    pub fn target(&self) -> PathBuf {
+        match self {
+            Placement::Layout { target, .. } => target.clone(),
+            Placement::Routed { root, router } => root.join(router.dir(RouteDestination::CustomStadium)),
+        }
+    }
+
+    /// Where a file entry ends up, and whether it is merged into the file already there
+    pub fn destination(&self, entry: &ArchiveEntry) -> Option<(PathBuf, bool)> {
+        if entry.is_dir {
+            return None;
+        }
+        match self {
+            Placement::Layout { target, strip_prefix } => {
+                entry_relative_path(entry, strip_prefix).map(|relative| (target.join(relative), false))
+            }
+            Placement::Routed { root, router } => {
+                router.route(entry).map(|route| (root.join(&route.relative), route.merge))
+            }
+        }
+    }
+
+    /// The safety checks the installer runs before extracting
+    pub fn violations(&self, archive: &Archive) -> Vec<ArchiveViolation> {
+        match self {
+            Placement::Layout { strip_prefix, .. } => archive_guard::check_archive(archive, strip_prefix),
+            Placement::Routed { router, .. } => {
+                let mut violations = archive_guard::check_archive(archive, "");
+                violations.extend(router.collisions(archive));
+                violations
+            }
+        }
+    }
+}
+
+/// Detects how an archive maps onto the install folder, for the user to confirm
+/// The returned strip_prefix can be passed back as layout_prefix to override it.
+#[tauri::command]
+pub fn detect_archive_layout(
+    zip_path: Option<String>,
+    kind: PackKind,
+    app_handle: tauri::AppHandle,
+    install_id: String,
+) -> Result<ArchiveLayout, String> {
+    registry::resolve(&app_handle, &install_id)?;
+    open_pack(zip_path, kind, None, &app_handle).map(|(_, layout)| layout)
+}
+
+/// Works out what installing an archive would change, without writing anything
+/// Entries are placed as the installers place them. Existing files are only
+/// hashed when their size matches the archive entry.
+#[tauri::command]
+pub fn plan_install(
+    zip_path: Option<String>,
+    kind: PackKind,
+    layout_prefix: Option<String>,
+    app_handle: tauri::AppHandle,
+    install_id: String,
+) -> Result<InstallPlan, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let (archive, layout) = open_pack(zip_path, kind, layout_prefix, &app_handle)?;
+    let placement = Placement::new(kind, &layout, &install)?;
+    let target = placement.target();
+
+    let mut files = Vec::new();
+    let mut in_archive = HashSet::new();
+
+    archive.for_each_entry(|entry, mut contents| {
+        let Some((dest, merge)) = placement.destination(entry) else {
+            return Ok(());
+        };
+        // Several mapping files of one pack merge into the same file
+        if !in_archive.insert(to_manifest_path(&dest).to_lowercase()) {
+            return Ok(());
+        }
+
+        let size = entry.size;
+
+        let action = match fs::metadata(&dest) {
+            Err(_) => PlannedAction::New,
+            Ok(_) if merge => PlannedAction::Merged,
+            Ok(meta) if meta.len() != size => PlannedAction::OverwriteDifferent,
+            Ok(_) => {
+                let entry_hash = sha256_reader(&mut contents)
+                    .map_err(|e| format!("Failed to read {} from archive: {}", entry.name, e))?;
+
+                if sha256_file(&dest).ok().as_deref() == Some(entry_hash.as_str()) {
+                    PlannedAction::OverwriteIdentical
+                } else {
+                    PlannedAction::OverwriteDifferent
+                }
+            }
+        };
+
+        files.push(PlannedFile {
+            path: dest.to_string_lossy().to_string(),
+            action,
+            size,
+        });
+
+        Ok(())
+    })?;
+
+    // Existing files the archive doesn't have: the BepInEx pack replaces the
+    // whole BepInEx folder, stadium installs only add and overwrite
+    let (existing_dir, leftover_action) = match kind {
+        PackKind::BepInEx => (target.join("BepInEx"), PlannedAction::MovedToBackup),
+        PackKind::CustomStadiums => (target.clone(), PlannedAction::Unchanged),
+    };
+
+    let mut existing = Vec::new();
+    collect_relative_files(&existing_dir, &target, &mut existing);
+    existing.sort();
+
+    for relative in existing {
+        let path = target.join(&relative);
+        if in_archive.contains(&to_manifest_path(&path).to_lowercase()) {
+            continue;
+        }
+        files.push(PlannedFile {
+            size: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            path: path.to_string_lossy().to_string(),
+            action: leftover_action,
+        });
+    }
+
+    Ok(InstallPlan {
+        target_path: target.to_string_lossy().to_string(),
+        totals: summarize(&files),
+        files,
+    })
+}
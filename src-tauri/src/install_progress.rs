@@ -0,0 +1,179 @@
+use crate::archive::{Archive, ArchiveEntry};
+use serde::Serialize;
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+/// Error returned by an install the user cancelled
+pub const CANCELLED: &str = "Install cancelled";
+
+/// Progress information emitted during extraction
+#[derive(Clone, Serialize)]
+pub struct InstallProgress {
+    pub entries_done: u32,
+    pub entries_total: u32,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub current_file: String,
+    pub percent: f64,
+}
+
+/// Id of the most recently started install, the one cancel_install stops
+static CURRENT_INSTALL: AtomicU64 = AtomicU64::new(0);
+
+/// Id of the install the user last cancelled
+static CANCELLED_INSTALL: AtomicU64 = AtomicU64::new(0);
+
+/// Id of the last install its cancellation check stopped
+static STOPPED_INSTALL: AtomicU64 = AtomicU64::new(0);
+
+/// One install run, so a cancel only ever stops the install it was meant for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstallToken(u64);
+
+impl InstallToken {
+    /// Starts a new install; an earlier cancel doesn't apply to it
+    pub fn begin() -> Self {
+        Self(CURRENT_INSTALL.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    /// Fails with CANCELLED once this install is cancelled, recording that it stopped
+    fn check(self) -> Result<(), String> {
+        if CANCELLED_INSTALL.load(Ordering::SeqCst) != self.0 {
+            return Ok(());
+        }
+        STOPPED_INSTALL.store(self.0, Ordering::SeqCst);
+        Err(CANCELLED.to_string())
+    }
+
+    /// Whether this install failed because its cancellation check stopped it
+    pub fn was_stopped(self) -> bool {
+        STOPPED_INSTALL.load(Ordering::SeqCst) == self.0
+    }
+}
+
+/// Reports extraction progress to the frontend and stops on cancellation
+pub struct ProgressReporter {
+    app: AppHandle,
+    token: InstallToken,
+    progress: InstallProgress,
+    last_emit_time: Instant,
+    in_file: bool,
+}
+
+impl ProgressReporter {
+    /// Starts reporting an install of the files of an archive
+    pub fn start(app: &AppHandle, archive: &Archive, token: InstallToken) -> Self {
+        let files = archive.entries().iter().filter(|e| !e.is_dir);
+        let mut reporter = Self {
+            app: app.clone(),
+            token,
+            progress: InstallProgress {
+                entries_done: 0,
+                entries_total: files.clone().count() as u32,
+                bytes_done: 0,
+                bytes_total: files.map(|e| e.size).sum(),
+                current_file: String::new(),
+                percent: 0.0,
+            },
+            last_emit_time: Instant::now(),
+            in_file: false,
+        };
+
+        // Emit initial progress to show extraction has started
+        reporter.emit();
+        reporter
+    }
+
+    fn emit(&mut self) {
+        let progress = &mut self.progress;
+        progress.percent = if progress.bytes_total > 0 {
+            (progress.bytes_done as f64 / progress.bytes_total as f64) * 100.0
+        } else if progress.entries_total > 0 {
+            (progress.entries_done as f64 / progress.entries_total as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let _ = self.app.emit("install-progress", progress.clone());
+        self.last_emit_time = Instant::now();
+    }
+
+    /// Emit progress every 100ms to avoid flooding
+    fn maybe_emit(&mut self) {
+        if self.last_emit_time.elapsed().as_millis() >= 100 {
+            self.emit();
+        }
+    }
+
+    /// Marks the start of an entry, and the previous one as done
+    /// Fails once the install is cancelled.
+    pub fn begin_entry(&mut self, entry: &ArchiveEntry) -> Result<(), String> {
+        self.token.check()?;
+        if self.in_file {
+            self.progress.entries_done += 1;
+        }
+        self.in_file = !entry.is_dir;
+        self.progress.current_file = entry.name.clone();
+        self.maybe_emit();
+        Ok(())
+    }
+
+    /// Wraps an entry's contents so bytes are counted and cancellation stops the copy
+    pub fn reader<'a>(&'a mut self, contents: &'a mut dyn Read) -> ProgressReader<'a> {
+        ProgressReader { reporter: self, contents }
+    }
+
+    /// Emits the final progress
+    pub fn finish(mut self) {
+        if self.in_file {
+            self.progress.entries_done += 1;
+        }
+        self.progress.current_file.clear();
+        self.emit();
+    }
+}
+
+/// Reader over an entry's contents that feeds a ProgressReporter
+pub struct ProgressReader<'a> {
+    reporter: &'a mut ProgressReporter,
+    contents: &'a mut dyn Read,
+}
+
+impl Read for ProgressReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reporter.token.check().map_err(io::Error::other)?;
+
+        let read = self.contents.read(buf)?;
+        self.reporter.progress.bytes_done += read as u64;
+        self.reporter.maybe_emit();
+        Ok(read)
+    }
+}
+
+/// Asks the running install to stop; it then rolls back and fails with CANCELLED
+/// Returns at once, before the rollback. A cancel with no install running has no effect.
+#[tauri::command]
+pub fn cancel_install() -> Result<(), String> {
+    CANCELLED_INSTALL.store(CURRENT_INSTALL.load(Ordering::SeqCst), Ordering::SeqCst);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancels_only_the_current_install() {
+        let first = InstallToken::begin();
+        cancel_install().unwrap();
+        assert_eq!(first.check(), Err(CANCELLED.to_string()));
+        assert!(first.was_stopped());
+
+        // The cancel was meant for the first install, not the next one
+        let second = InstallToken::begin();
+        assert_eq!(second.check(), Ok(()));
+        assert!(!second.was_stopped());
+    }
+}
@@ -0,0 +1,277 @@
+use crate::models::{GameSessionSummary, LaunchMethod};
+use crate::{logs, process, registry, steam};
+use chrono::{DateTime, Local};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::Emitter;
+
+/// How long to wait for FM26 to show up after asking Steam to start it
+const STEAM_START_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// How often the watcher checks whether the game is still running
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Longest a Steam session is watched before the watcher gives up
+const MAX_SESSION_WATCH: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// Most log errors carried in a session summary; the newest are kept
+const MAX_SESSION_ERRORS: usize = 20;
+
+/// Set while a session started from the app is being watched
+static SESSION_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Set by stop_watching_game to end the watch of a Steam session early
+static WATCH_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// A started game, as far as the watcher can follow it
+pub enum Launched {
+    Steam,        // Steam starts the game; it is found by process name
+    Direct(Child),
+}
+
+/// Asks the OS to open a URL with its registered handler
+fn open_url(url: &str) -> Result<Child, String> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", "", url]).creation_flags(CREATE_NO_WINDOW);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("open");
+        command.arg(url);
+        command
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut command = Command::new("xdg-open");
+        command.arg(url);
+        command
+    };
+
+    command.spawn().map_err(|e| format!("Failed to open {}: {}", url, e))
+}
+
+/// Finds the game executable in an installation folder
+fn find_executable(root: &Path) -> Option<PathBuf> {
+    process::GAME_EXECUTABLES
+        .iter()
+        .map(|exe| root.join(exe))
+        .find(|path| path.is_file())
+}
+
+/// Starts an executable directly, with the given working folder
+pub fn spawn_direct(exe: &Path, args: &[&str], dir: &Path) -> Result<Launched, String> {
+    Command::new(exe)
+        .args(args)
+        .current_dir(dir)
+        .spawn()
+        .map(Launched::Direct)
+        .map_err(|e| format!("Failed to start {}: {}", exe.display(), e))
+}
+
+/// Polls until `done` holds, the timeout passes or the watch is cancelled,
+/// returning whether it held
+fn poll_until(timeout: Duration, done: impl Fn() -> bool) -> bool {
+    let started = SystemTime::now();
+    loop {
+        if done() {
+            return true;
+        }
+        if WATCH_CANCELLED.load(Ordering::SeqCst) || started.elapsed().unwrap_or_default() >= timeout {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Blocks until the launched game exits
+/// Direct launches end with the started process. Steam sessions are followed
+/// by process name, at most for MAX_SESSION_WATCH or until stop_watching_game.
+/// Returns whether the game was seen at all and, for direct launches, its exit code.
+pub fn watch(launched: Launched) -> (bool, Option<i32>) {
+    match launched {
+        Launched::Direct(mut child) => (true, child.wait().ok().and_then(|status| status.code())),
+        Launched::Steam => {
+            if !poll_until(STEAM_START_TIMEOUT, process::is_game_running) {
+                return (false, None);
+            }
+            poll_until(MAX_SESSION_WATCH, || !process::is_game_running());
+            (true, None)
+        }
+    }
+}
+
+/// Builds the summary of a finished session from what BepInEx logged during it
+pub fn summarize(
+    install_id: String,
+    method: LaunchMethod,
+    started_at: DateTime<Local>,
+    watched: (bool, Option<i32>),
+    log_path: &Path,
+) -> GameSessionSummary {
+    // A log older than the launch belongs to an earlier session
+    let started: SystemTime = started_at.into();
+    let log_updated = fs::metadata(log_path)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| modified >= started);
+    let content = if log_updated {
+        fs::read(log_path)
+            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+            .unwrap_or_default()
+    } else {
+        String::new()
+    };
+
+    let errors = logs::error_lines(&content);
+    let (game_detected, exit_code) = watched;
+
+    GameSessionSummary {
+        install_id,
+        method,
+        started_at: started_at.to_rfc3339(),
+        ended_at: Local::now().to_rfc3339(),
+        game_detected,
+        exit_code,
+        log_updated,
+        plugins_loaded: logs::loaded_plugins(&content),
+        error_count: errors.len() as u32,
+        errors: errors[errors.len().saturating_sub(MAX_SESSION_ERRORS)..].to_vec(),
+        stadium_injections: logs::stadium_injections(&content),
+    }
+}
+
+/// Watches a started session until the game exits, then summarizes it from the log
+pub fn finish_session(
+    install_id: String,
+    method: LaunchMethod,
+    started_at: DateTime<Local>,
+    launched: Launched,
+    log_path: &Path,
+) -> GameSessionSummary {
+    let watched = watch(launched);
+    summarize(install_id, method, started_at, watched, log_path)
+}
+
+/// Starts FM26 and watches it until it exits, then emits "game-exited"
+/// Steam installs go through steam://rungameid so launch options and Proton apply.
+#[tauri::command]
+pub fn launch_game(app_handle: tauri::AppHandle, install_id: String) -> Result<LaunchMethod, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let root = Path::new(&install.root_path);
+
+    if process::is_game_running() {
+        return Err("FM26 is already running".to_string());
+    }
+    if SESSION_ACTIVE.swap(true, Ordering::SeqCst) {
+        return Err("FM26 is already being launched".to_string());
+    }
+    WATCH_CANCELLED.store(false, Ordering::SeqCst);
+
+    let started_at = Local::now();
+    let launched = match steam::fm26_app_id_for_install(root) {
+        Some(app_id) => open_url(&format!("steam://rungameid/{}", app_id)).map(|mut opener| {
+            // The opener exits on its own; reap it so it does not linger
+            thread::spawn(move || opener.wait());
+            (LaunchMethod::Steam, Launched::Steam)
+        }),
+        None => find_executable(root)
+            .ok_or_else(|| format!("No game executable found in {}", install.root_path))
+            .and_then(|exe| spawn_direct(&exe, &[], root))
+            .map(|launched| (LaunchMethod::Direct, launched)),
+    };
+
+    let (method, launched) = match launched {
+        Ok(launched) => launched,
+        Err(e) => {
+            SESSION_ACTIVE.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+    };
+
+    let log_path = PathBuf::from(&install.log_path);
+    thread::spawn(move || {
+        let summary = finish_session(install_id, method, started_at, launched, &log_path);
+        SESSION_ACTIVE.store(false, Ordering::SeqCst);
+        let _ = app_handle.emit("game-exited", summary);
+    });
+
+    Ok(method)
+}
+
+/// Checks whether a game launched from the app is still being watched
+#[tauri::command]
+pub fn is_game_session_active() -> bool {
+    SESSION_ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Stops watching a Steam session; the summary is emitted with what was logged so far
+#[tauri::command]
+pub fn stop_watching_game() {
+    if SESSION_ACTIVE.load(Ordering::SeqCst) {
+        WATCH_CANCELLED.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn summarizes_a_direct_session() {
+        let dir = TempDir::new("launch_direct");
+
+        // A stand-in game: runs a while, writes a BepInEx log and exits with an error code
+        let script = "sleep 1; \
+            echo '[Message:   BepInEx] Loading [StadiumInjection 1.2.0]' > LogOutput.log; \
+            echo '[Info   :Stadium Injection] Injected stadium for team 42' >> LogOutput.log; \
+            echo '[Error  :   BepInEx] Could not load CrowdInject' >> LogOutput.log; \
+            exit 3";
+        let started_at = Local::now();
+        let launched = spawn_direct(Path::new("sh"), &["-c", script], &dir).unwrap();
+        let summary = finish_session("test".to_string(), LaunchMethod::Direct, started_at, launched, &dir.join("LogOutput.log"));
+
+        assert!(summary.game_detected);
+        assert_eq!(summary.exit_code, Some(3));
+        let ended_at = DateTime::parse_from_rfc3339(&summary.ended_at).unwrap();
+        let duration = ended_at.signed_duration_since(DateTime::parse_from_rfc3339(&summary.started_at).unwrap());
+        assert!(duration.num_milliseconds() >= 1000, "{}", duration);
+
+        assert!(summary.log_updated);
+        assert_eq!(summary.plugins_loaded, ["StadiumInjection 1.2.0"]);
+        assert_eq!(summary.stadium_injections, 1);
+        assert_eq!(summary.error_count, 1);
+        assert_eq!(summary.errors, ["[Error  :   BepInEx] Could not load CrowdInject"]);
+    }
+
+    #[test]
+    fn ignores_a_log_from_an_earlier_session() {
+        let dir = TempDir::new("launch_stale");
+        let log_path = dir.join("LogOutput.log");
+        fs::write(&log_path, "[Error  :   BepInEx] From last time\n").unwrap();
+
+        let started_at = Local::now() + chrono::Duration::seconds(5);
+        let summary = summarize("test".to_string(), LaunchMethod::Steam, started_at, (false, None), &log_path);
+
+        assert!(!summary.game_detected);
+        assert!(!summary.log_updated);
+        assert_eq!(summary.error_count, 0);
+    }
+
+    #[test]
+    fn stops_polling_when_cancelled() {
+        WATCH_CANCELLED.store(true, Ordering::SeqCst);
+        assert!(!poll_until(MAX_SESSION_WATCH, || false));
+        WATCH_CANCELLED.store(false, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::archive::{Archive, ArchiveEntry};
+use crate::models::{ArchiveLayout, LayoutAnchor, PackKind};
+use std::path::PathBuf;
+
+/// Archive entry names, '/' separated
+fn entry_names(archive: &Archive) -> Vec<String> {
+    archive.entries().iter().map(|e| e.name.clone()).collect()
+}
+
+/// Joins the first `depth` components of a name back into a "a/b/" prefix
+fn prefix_of(components: &[&str], depth: usize) -> String {
+    components[..depth].iter().map(|c| format!("{}/", c)).collect()
+}
+
+/// Finds the shallowest folder with a given name, returning (depth, prefix up to it)
+fn find_folder(names: &[String], folder: &str, include_folder: bool) -> Option<(usize, String)> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let components: Vec<&str> = name.split('/').collect();
+            // The last component is a file name unless the entry ends with '/'
+            let dirs = &components[..components.len() - 1];
+            let depth = dirs.iter().position(|c| c.eq_ignore_ascii_case(folder))?;
+            let end = if include_folder { depth + 1 } else { depth };
+            Some((depth, prefix_of(&components, end)))
+        })
+        .min_by_key(|(depth, _)| *depth)
+}
+
+/// Finds the shallowest file with a given name, returning (depth, its folder prefix)
+fn find_file(names: &[String], file: &str) -> Option<(usize, String)> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let components: Vec<&str> = name.split('/').collect();
+            let last = components.len() - 1;
+            components[last].eq_ignore_ascii_case(file).then(|| (last, prefix_of(&components, last)))
+        })
+        .min_by_key(|(depth, _)| *depth)
+}
+
+/// Longest folder prefix shared by every .bundle file
+fn common_bundle_prefix(names: &[String]) -> Option<String> {
+    let mut bundles = names.iter().filter(|n| n.to_lowercase().ends_with(".bundle"));
+    let first: Vec<&str> = bundles.next()?.split('/').collect();
+    let mut shared = first.len() - 1;
+
+    for name in bundles {
+        let components: Vec<&str> = name.split('/').collect();
+        shared = shared.min(components.len() - 1);
+        shared = (0..shared).find(|&i| components[i] != first[i]).unwrap_or(shared);
+    }
+
+    Some(prefix_of(&first, shared))
+}
+
+/// Finds where the install tree starts inside an archive, at any depth
+/// BepInEx packs anchor on a `BepInEx/` folder (or `winhttp.dll` next to it);
+/// stadium packs on a `CustomStadium/` folder, or else the `.bundle` files.
+fn detect_anchor(names: &[String], kind: PackKind) -> Option<(LayoutAnchor, String)> {
+    match kind {
+        PackKind::BepInEx => {
+            let folder = find_folder(names, "BepInEx", false);
+            let loader = find_file(names, "winhttp.dll");
+            match (folder, loader) {
+                (Some((folder_depth, prefix)), Some((loader_depth, _))) if folder_depth <= loader_depth => {
+                    Some((LayoutAnchor::BepInExFolder, prefix))
+                }
+                (_, Some((_, prefix))) => Some((LayoutAnchor::WinhttpDll, prefix)),
+                (Some((_, prefix)), None) => Some((LayoutAnchor::BepInExFolder, prefix)),
+                (None, None) => None,
+            }
+        }
+        // Stadium pack content is routed by type, so a pack without bundles still installs
+        PackKind::CustomStadiums => find_folder(names, "CustomStadium", true)
+            .map(|(_, prefix)| (LayoutAnchor::CustomStadiumFolder, prefix))
+            .or_else(|| common_bundle_prefix(names).map(|prefix| (LayoutAnchor::BundleFiles, prefix)))
+            .or(Some((LayoutAnchor::ArchiveRoot, String::new()))),
+    }
+}
+
+/// Works out the mapping from archive entries to the install folder
+/// A prefix given by the user overrides detection.
+pub fn resolve_layout(
+    archive: &Archive,
+    kind: PackKind,
+    prefix_override: Option<String>,
+) -> Result<ArchiveLayout, String> {
+    let names = entry_names(archive);
+
+    let (anchor, strip_prefix) = match prefix_override {
+        Some(prefix) => {
+            let prefix = prefix.trim_matches('/').replace('\\', "/");
+            let prefix = if prefix.is_empty() { prefix } else { format!("{}/", prefix) };
+            (LayoutAnchor::UserOverride, prefix)
+        }
+        None => detect_anchor(&names, kind)
+            .ok_or("No BepInEx folder or winhttp.dll found in the archive")?,
+    };
+
+    let (mapped, skipped): (Vec<&String>, Vec<&String>) = names
+        .iter()
+        .filter(|name| !name.ends_with('/'))
+        .partition(|name| name.starts_with(&strip_prefix));
+
+    if mapped.is_empty() {
+        return Err(format!("No files in the archive are under {}", strip_prefix));
+    }
+
+    Ok(ArchiveLayout {
+        anchor,
+        strip_prefix,
+        mapped_entries: mapped.len() as u32,
+        skipped_entries: skipped.into_iter().cloned().collect(),
+    })
+}
+
+/// Path of an archive entry relative to the install folder
+/// Returns None for unsafe names, entries outside the prefix and the prefix folder itself.
+pub fn entry_relative_path(entry: &ArchiveEntry, strip_prefix: &str) -> Option<PathBuf> {
+    let name = entry.path.as_ref()?.to_string_lossy().replace('\\', "/");
+
+    let relative = name.strip_prefix(strip_prefix)?;
+
+    (!relative.is_empty()).then(|| PathBuf::from(relative))
+}
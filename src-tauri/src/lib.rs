@@ -5,8 +5,37 @@ pub mod audio;
 pub mod configs;
 pub mod logs;
 pub mod download;
+pub mod vdf;
+pub mod steam;
+pub mod epic;
+pub mod proton;
+pub mod hash;
+pub mod game_version;
+pub mod pe;
+pub mod dotnet;
+pub mod bepinex;
+pub mod install_manifest;
+pub mod uninstall;
+pub mod backups;
+pub mod staging;
+pub mod install_plan;
+pub mod migrate;
+pub mod process;
+pub mod preflight;
+pub mod archive;
+pub mod archive_guard;
+pub mod layout;
+pub mod routing;
+pub mod conflicts;
+pub mod install_progress;
+pub mod registry;
+pub mod names;
+pub mod diagnose;
+pub mod launch;
+#[cfg(test)]
+mod test_support;
 
-use fm26::{detect_fm26_paths, inspect_fm26_install, install_bepinex_pack, install_custom_stadiums_pack, get_plugin_status, check_bepinex_installed};
+use fm26::{detect_fm26_paths, probe_fm26_install, install_bepinex_pack, install_custom_stadiums_pack, get_plugin_status, check_bepinex_installed};
 use stadium::{list_bundles, read_team_mappings, write_team_mappings};
 use audio::{read_audio_mappings, write_audio_mappings, list_audio_folders, inspect_audio_folder};
 use configs::{
@@ -16,8 +45,23 @@ use configs::{
     read_adboards_config, write_adboards_config,
     list_config_files
 };
+use proton::{get_proton_status, apply_proton_launch_options};
+use game_version::{get_game_version, check_game_version};
+use install_manifest::get_install_manifest;
+use uninstall::uninstall_bepinex;
+use install_plan::{plan_install, detect_archive_layout};
+use conflicts::check_stadium_conflicts;
+use preflight::{preflight_install, preflight_restore};
+use backups::{list_backups, restore_backup, delete_backup, get_backup_settings, set_backup_settings};
 use logs::{read_log, get_log_info, clear_log};
 use download::{download_bepinex_from_r2, download_bepinex_from_url, cancel_download};
+use install_progress::cancel_install;
+use diagnose::{diagnose_install, apply_fix};
+use launch::{launch_game, is_game_session_active, stop_watching_game};
+use registry::{
+    list_installs, add_install, remove_install, label_install,
+    set_default_install, get_default_install, get_install, revalidate_installs
+};
 
 use tauri::Manager;
 #[cfg(target_os = "windows")]
@@ -27,12 +71,15 @@ use tauri_plugin_decorum::WebviewWindowExt;
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_decorum::init())
-        .setup(|_app| {
+        .setup(|app| {
             #[cfg(target_os = "windows")]
             {
-                let main_window = _app.get_webview_window("main").unwrap();
+                let main_window = app.get_webview_window("main").unwrap();
                 main_window.create_overlay_titlebar().unwrap();
             }
+
+            // Saved installs may have moved or been updated since the last run
+            let _ = revalidate_installs(app.handle().clone());
             Ok(())
         })
         .plugin(tauri_plugin_dialog::init())
@@ -43,11 +90,35 @@ pub fn run() {
         .plugin(tauri_plugin_http::init())
         .invoke_handler(tauri::generate_handler![
             detect_fm26_paths,
-            inspect_fm26_install,
+            probe_fm26_install,
+            list_installs,
+            add_install,
+            remove_install,
+            label_install,
+            set_default_install,
+            get_default_install,
+            get_install,
+            revalidate_installs,
             install_bepinex_pack,
             install_custom_stadiums_pack,
             get_plugin_status,
             check_bepinex_installed,
+            get_proton_status,
+            apply_proton_launch_options,
+            get_game_version,
+            check_game_version,
+            get_install_manifest,
+            plan_install,
+            detect_archive_layout,
+            check_stadium_conflicts,
+            preflight_install,
+            preflight_restore,
+            uninstall_bepinex,
+            list_backups,
+            restore_backup,
+            delete_backup,
+            get_backup_settings,
+            set_backup_settings,
             list_bundles,
             read_team_mappings,
             write_team_mappings,
@@ -70,6 +141,12 @@ pub fn run() {
             download_bepinex_from_r2,
             download_bepinex_from_url,
             cancel_download,
+            cancel_install,
+            diagnose_install,
+            apply_fix,
+            launch_game,
+            is_game_session_active,
+            stop_watching_game,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,10 +1,11 @@
-use crate::models::Fm26Installation;
+use crate::registry;
 use std::fs;
 use std::path::Path;
 
 /// Reads the BepInEx log file content
 #[tauri::command]
-pub fn read_log(install: Fm26Installation) -> Result<String, String> {
+pub fn read_log(app_handle: tauri::AppHandle, install_id: String) -> Result<String, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let log_path = Path::new(&install.log_path);
 
     if !log_path.exists() {
@@ -17,7 +18,8 @@ pub fn read_log(install: Fm26Installation) -> Result<String, String> {
 
 /// Gets log file metadata
 #[tauri::command]
-pub fn get_log_info(install: Fm26Installation) -> Result<LogInfo, String> {
+pub fn get_log_info(app_handle: tauri::AppHandle, install_id: String) -> Result<LogInfo, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let log_path = Path::new(&install.log_path);
 
     if !log_path.exists() {
@@ -50,7 +52,8 @@ pub fn get_log_info(install: Fm26Installation) -> Result<LogInfo, String> {
 
 /// Clears the log file
 #[tauri::command]
-pub fn clear_log(install: Fm26Installation) -> Result<(), String> {
+pub fn clear_log(app_handle: tauri::AppHandle, install_id: String) -> Result<(), String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let log_path = Path::new(&install.log_path);
 
     if log_path.exists() {
@@ -61,6 +64,46 @@ pub fn clear_log(install: Fm26Installation) -> Result<(), String> {
     Ok(())
 }
 
+/// Splits a LogOutput.log line like "[Info   :   BepInEx] Loading ..." into level, source and message
+fn parse_entry(line: &str) -> Option<(&str, &str, &str)> {
+    let (header, message) = line.trim().strip_prefix('[')?.split_once(']')?;
+    let (level, source) = header.split_once(':')?;
+    Some((level.trim(), source.trim(), message.trim()))
+}
+
+/// Error and fatal entries of a log, in order
+pub fn error_lines(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter(|line| matches!(parse_entry(line), Some(("Error" | "Fatal", _, _))))
+        .map(|line| line.trim().to_string())
+        .collect()
+}
+
+/// Plugins BepInEx reported loading, as "Name Version"
+pub fn loaded_plugins(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(parse_entry)
+        .filter(|(_, source, _)| *source == "BepInEx")
+        .filter_map(|(_, _, message)| message.strip_prefix("Loading [")?.strip_suffix(']'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Number of entries where Stadium Injection reports injecting a stadium
+pub fn stadium_injections(content: &str) -> u32 {
+    content
+        .lines()
+        .filter_map(parse_entry)
+        .filter(|(level, source, message)| {
+            !matches!(*level, "Error" | "Fatal")
+                && source.replace(' ', "").eq_ignore_ascii_case("StadiumInjection")
+                && message.to_lowercase().contains("inject")
+        })
+        .count() as u32
+}
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,358 @@
+use crate::configs::line_ending;
+use crate::models::MigrationReport;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Plugin configs carried over by key; the prefix of every Bassy plugin GUID
+const CONFIG_PREFIX: &str = "com.bassy.";
+
+/// User files copied over as is, relative to BepInEx/
+const USER_FILES: [&str; 2] = [
+    "plugins/StadiumInjection/team_mappings.txt",
+    "plugins/AudioInject/AudioMappings.txt",
+];
+
+/// Folder of user-added stadium bundles, relative to BepInEx/
+const CUSTOM_STADIUM_DIR: &str = "plugins/CustomStadium";
+
+/// Reads `key = value` pairs of a BepInEx .cfg, keyed by (section, key)
+fn parse_cfg(content: &str) -> HashMap<(String, String), String> {
+    let mut values = HashMap::new();
+    let mut section = String::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            section = trimmed[1..trimmed.len() - 1].to_string();
+            continue;
+        }
+
+        if let Some((key, value)) = trimmed.split_once('=') {
+            values.insert((section.clone(), key.trim().to_string()), value.trim().to_string());
+        }
+    }
+
+    values
+}
+
+/// Merges a user's .cfg into the new pack's one
+/// The new file's layout, comments and defaults are kept; values of keys the
+/// user already had are carried over. Returns the merged text and the user's
+/// keys that no longer exist.
+fn merge_cfg(new_content: &str, old_content: &str) -> (String, Vec<String>) {
+    let old_values = parse_cfg(old_content);
+    let mut used: Vec<&(String, String)> = Vec::new();
+    let mut section = String::new();
+    let mut lines = Vec::new();
+
+    for line in new_content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            section = trimmed[1..trimmed.len() - 1].to_string();
+        } else if !trimmed.starts_with('#') && !trimmed.starts_with(';') {
+            if let Some((key, _)) = trimmed.split_once('=') {
+                let id = (section.clone(), key.trim().to_string());
+                if let Some((id, value)) = old_values.get_key_value(&id) {
+                    lines.push(format!("{} = {}", id.1, value));
+                    used.push(id);
+                    continue;
+                }
+            }
+        }
+
+        lines.push(line.to_string());
+    }
+
+    let mut dropped: Vec<String> = old_values
+        .keys()
+        .filter(|id| !used.contains(id))
+        .map(|(section, key)| format!("[{}] {}", section, key))
+        .collect();
+    dropped.sort();
+
+    let ending = line_ending(new_content);
+    let mut merged = lines.join(ending);
+    if new_content.ends_with('\n') {
+        merged.push_str(ending);
+    }
+
+    (merged, dropped)
+}
+
+/// Adds a setting at the end of its section, creating the section if needed
+fn insert_setting(lines: &mut Vec<String>, section: &str, setting: &str) {
+    let is_section = |line: &String| {
+        let line = line.trim();
+        line.starts_with('[') && line.ends_with(']')
+    };
+    // Settings before the first [Section] belong to the unnamed one
+    let start = if section.is_empty() {
+        Some(0)
+    } else {
+        let header = format!("[{}]", section);
+        lines.iter().position(|line| line.trim() == header).map(|i| i + 1)
+    };
+
+    let Some(start) = start else {
+        if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(format!("[{}]", section));
+        lines.push(setting.to_string());
+        return;
+    };
+
+    let mut end = lines[start..].iter().position(is_section).map_or(lines.len(), |i| start + i);
+    while end > start && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    lines.insert(end, setting.to_string());
+}
+
+/// Merges a pack's .cfg into the user's current one
+/// The user's file and values are kept as they are; settings only the pack
+/// has are added to their section, so installing a pack never resets a setting.
+pub fn merge_pack_cfg(user_content: &str, pack_content: &str) -> String {
+    let mut known: HashSet<(String, String)> = parse_cfg(user_content).into_keys().collect();
+    let mut lines: Vec<String> = user_content.lines().map(|line| line.to_string()).collect();
+    let mut section = String::new();
+
+    for line in pack_content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            section = trimmed[1..trimmed.len() - 1].to_string();
+            continue;
+        }
+
+        if let Some((key, _)) = trimmed.split_once('=') {
+            if known.insert((section.clone(), key.trim().to_string())) {
+                insert_setting(&mut lines, &section, trimmed);
+            }
+        }
+    }
+
+    let ending = line_ending(user_content);
+    let mut merged = lines.join(ending);
+    if user_content.ends_with('\n') {
+        merged.push_str(ending);
+    }
+    merged
+}
+
+fn migrate_configs(old: &Path, new: &Path, report: &mut MigrationReport) {
+    let Ok(entries) = fs::read_dir(old.join("config")) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with(CONFIG_PREFIX) || !name.ends_with(".cfg") {
+            continue;
+        }
+
+        let relative = format!("config/{}", name);
+        let dest = new.join("config").join(&name);
+
+        let old_content = match fs::read_to_string(entry.path()) {
+            Ok(content) => content,
+            Err(e) => {
+                report.not_migrated.push(format!("{}: {}", relative, e));
+                continue;
+            }
+        };
+
+        // Plugins write their .cfg on first run, so the pack may not ship one
+        let merged = match fs::read_to_string(&dest) {
+            Ok(new_content) => {
+                let (merged, dropped) = merge_cfg(&new_content, &old_content);
+                for key in dropped {
+                    report.not_migrated.push(format!("{} {}: no longer in the pack", relative, key));
+                }
+                merged
+            }
+            Err(_) => old_content,
+        };
+
+        let written = fs::create_dir_all(new.join("config")).and_then(|_| fs::write(&dest, merged));
+        match written {
+            Ok(()) => report.migrated.push(relative),
+            Err(e) => report.not_migrated.push(format!("{}: {}", relative, e)),
+        }
+    }
+}
+
+/// Copies a file from the old tree to the new one, creating parent folders
+fn copy_user_file(old: &Path, new: &Path, relative: &str, report: &mut MigrationReport) {
+    let source = old.join(relative);
+    if !source.is_file() {
+        return;
+    }
+
+    let dest = new.join(relative);
+    let copied = match dest.parent() {
+        Some(parent) => fs::create_dir_all(parent).and_then(|_| fs::copy(&source, &dest)),
+        None => fs::copy(&source, &dest),
+    };
+
+    match copied {
+        Ok(_) => report.migrated.push(relative.to_string()),
+        Err(e) => report.not_migrated.push(format!("{}: {}", relative, e)),
+    }
+}
+
+/// Copies stadium bundles the new pack doesn't have, keeping the pack's own versions
+fn migrate_custom_stadiums(old: &Path, new: &Path, dir: &str, report: &mut MigrationReport) {
+    let Ok(entries) = fs::read_dir(old.join(dir)) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let relative = format!("{}/{}", dir, entry.file_name().to_string_lossy());
+
+        if entry.path().is_dir() {
+            migrate_custom_stadiums(old, new, &relative, report);
+        } else if !new.join(&relative).exists() {
+            copy_user_file(old, new, &relative, report);
+        }
+    }
+}
+
+/// Carries user data from a replaced BepInEx folder into a fresh install
+/// Covers com.bassy.*.cfg (merged by key), team and audio mappings, and
+/// user-added CustomStadium bundles.
+pub fn migrate_user_data(old_bepinex: &Path, new_bepinex: &Path) -> MigrationReport {
+    let mut report = MigrationReport {
+        migrated: Vec::new(),
+        not_migrated: Vec::new(),
+    };
+
+    if !old_bepinex.is_dir() {
+        return report;
+    }
+
+    migrate_configs(old_bepinex, new_bepinex, &mut report);
+
+    for relative in USER_FILES {
+        copy_user_file(old_bepinex, new_bepinex, relative, &mut report);
+    }
+
+    migrate_custom_stadiums(old_bepinex, new_bepinex, CUSTOM_STADIUM_DIR, &mut report);
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn merges_user_values_into_the_new_layout() {
+        let new_content = "\
+## Settings file
+[General]
+# Turns the plugin on
+Enabled = true
+Volume = 0.5
+
+[Debug]
+Verbose = false
+";
+        let old_content = "\
+[General]
+Enabled = false
+Volume=0.8
+Removed = 1
+[Debug]
+Verbose = false
+";
+
+        let (merged, dropped) = merge_cfg(new_content, old_content);
+        assert_eq!(
+            merged,
+            "\
+## Settings file
+[General]
+# Turns the plugin on
+Enabled = false
+Volume = 0.8
+
+[Debug]
+Verbose = false
+"
+        );
+        assert_eq!(dropped, ["[General] Removed"]);
+    }
+
+    #[test]
+    fn matches_keys_by_section() {
+        let (merged, dropped) = merge_cfg("[A]\nKey = 1\n[B]\nKey = 2", "[B]\nKey = 3\n");
+        assert_eq!(merged, "[A]\nKey = 1\n[B]\nKey = 3");
+        assert!(dropped.is_empty());
+    }
+
+    #[test]
+    fn keeps_windows_line_endings() {
+        let (merged, _) = merge_cfg("[General]\r\nEnabled = true\r\n", "[General]\nEnabled = false\n");
+        assert_eq!(merged, "[General]\r\nEnabled = false\r\n");
+
+        let merged = merge_pack_cfg("[General]\r\nEnabled = false\r\n", "[General]\nMode = Fast\n");
+        assert_eq!(merged, "[General]\r\nEnabled = false\r\nMode = Fast\r\n");
+    }
+
+    #[test]
+    fn carries_configs_mappings_and_user_bundles_over() {
+        let base = TempDir::new("migrate_user_data");
+        let (old, new) = (base.join("old"), base.join("new"));
+        fs::create_dir_all(old.join("config")).unwrap();
+        fs::create_dir_all(old.join(CUSTOM_STADIUM_DIR).join("Sub")).unwrap();
+        fs::create_dir_all(old.join("plugins/StadiumInjection")).unwrap();
+        fs::create_dir_all(new.join("config")).unwrap();
+        fs::create_dir_all(new.join(CUSTOM_STADIUM_DIR)).unwrap();
+
+        fs::write(old.join("config/com.bassy.test.cfg"), "[General]\nEnabled = false\n").unwrap();
+        fs::write(old.join("config/other.cfg"), "[General]\nEnabled = false\n").unwrap();
+        fs::write(new.join("config/com.bassy.test.cfg"), "[General]\nEnabled = true\n").unwrap();
+        fs::write(old.join(USER_FILES[0]), "1:a.bundle\n").unwrap();
+        fs::write(old.join(CUSTOM_STADIUM_DIR).join("Sub/mine.bundle"), "mine").unwrap();
+        fs::write(old.join(CUSTOM_STADIUM_DIR).join("pack.bundle"), "old").unwrap();
+        fs::write(new.join(CUSTOM_STADIUM_DIR).join("pack.bundle"), "new").unwrap();
+
+        let mut report = migrate_user_data(&old, &new);
+        report.migrated.sort();
+        assert_eq!(
+            report.migrated,
+            [
+                "config/com.bassy.test.cfg",
+                "plugins/CustomStadium/Sub/mine.bundle",
+                "plugins/StadiumInjection/team_mappings.txt",
+            ]
+        );
+        assert!(report.not_migrated.is_empty(), "{:?}", report.not_migrated);
+        assert_eq!(fs::read_to_string(new.join("config/com.bassy.test.cfg")).unwrap(), "[General]\nEnabled = false\n");
+        assert!(!new.join("config/other.cfg").exists());
+        assert_eq!(fs::read_to_string(new.join(CUSTOM_STADIUM_DIR).join("pack.bundle")).unwrap(), "new");
+    }
+
+    #[test]
+    fn adds_only_new_pack_settings_to_the_users_cfg() {
+        let user = "[General]\nEnabled = false\n\n[Audio]\nVolume = 3\n";
+        let pack = "[General]\n# Turns the plugin on\nEnabled = true\nMode = Fast\n\n[Extra]\nLevel = 1\n";
+
+        assert_eq!(
+            merge_pack_cfg(user, pack),
+            "[General]\nEnabled = false\nMode = Fast\n\n[Audio]\nVolume = 3\n\n[Extra]\nLevel = 1\n"
+        );
+    }
+}
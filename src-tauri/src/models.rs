@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 /// Represents a validated FM26 installation with all relevant paths
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fm26Installation {
+    pub id: String, // registry id
     pub root_path: String,
     pub bep_in_ex_path: String,
     pub plugins_path: String,
@@ -12,12 +13,111 @@ pub struct Fm26Installation {
     pub log_path: String,
 }
 
+/// Store or launcher an FM26 installation was found through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameStore {
+    Steam,
+    Epic,
+    Manual,
+}
+
+/// A marker file or folder used to recognise an FM26 installation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallEvidence {
+    FmExe,
+    UnityDataFolder,
+    UnityPlayerDll,
+    GameAssemblyDll,
+    SteamAppId,
+}
+
+/// An FM26 installation candidate with the evidence that identified it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedInstall {
+    pub path: String,
+    pub store: GameStore,
+    pub confidence: u8, // 0-100
+    pub found: Vec<InstallEvidence>,
+    pub missing: Vec<InstallEvidence>,
+    pub data_folder: Option<String>, // e.g. "fm_Data"
+    pub steam_app_id: Option<String>,
+}
+
+/// Identifies the FM26 build an installation is running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameVersion {
+    pub steam_build_id: Option<String>,
+    pub unity_version: Option<String>,
+    pub game_assembly_sha256: Option<String>,
+    pub game_assembly_size: Option<u64>,
+}
+
+/// Game version recorded when the BepInEx pack was installed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedGameVersion {
+    pub version: GameVersion,
+    pub recorded_at: String, // RFC 3339 timestamp
+}
+
+/// Result of comparing the current game build with the recorded one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameVersionCheck {
+    pub current: GameVersion,
+    pub installed_against: Option<GameVersion>,
+    pub recorded_at: Option<String>,
+    pub game_updated: bool,
+    pub warning: Option<String>,
+}
+
+/// Whether a plugin is present and able to load
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginState {
+    Installed,
+    Missing,
+    ProtonBlocked, // installed, but Proton won't load BepInEx without the winhttp override
+    MissingDependency, // installed, but a hard [BepInDependency] is absent or too old
+}
+
+/// A `[BepInDependency]` declared by a plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginDependency {
+    pub guid: String,
+    pub min_version: Option<String>,
+    pub soft: bool,
+    pub satisfied: bool,
+}
+
 /// Status of a BepInEx plugin
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginStatus {
     pub name: String,
     pub path: String,
     pub installed: bool,
+    pub state: PluginState,
+    pub guid: Option<String>,    // from [BepInPlugin], None if the DLL couldn't be read
+    pub version: Option<String>,
+    pub dependencies: Vec<PluginDependency>,
+}
+
+/// FM26 launch options from one Steam user's localconfig.vdf
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtonLaunchOptions {
+    pub localconfig_path: String,
+    pub launch_options: Option<String>,
+    pub has_winhttp_override: bool,
+    pub backup_path: Option<String>, // set when the file was rewritten
+}
+
+/// Proton/Wine status of an FM26 installation on Linux
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtonStatus {
+    pub uses_proton: bool,
+    pub prefix_path: Option<String>, // compatdata/<appid>/pfx
+    pub launch_options: Vec<ProtonLaunchOptions>,
+    pub override_configured: bool,
 }
 
 /// Information about a stadium bundle file
@@ -151,6 +251,31 @@ impl Default for AdboardsConfig {
     }
 }
 
+/// Which BepInEx build is installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BepInExFlavor {
+    #[serde(rename = "bepinex5")]
+    BepInEx5,
+    #[serde(rename = "bepinex6_mono")]
+    BepInEx6Mono,
+    #[serde(rename = "bepinex6_il2cpp")]
+    BepInEx6Il2Cpp,
+    Unknown,
+}
+
+impl BepInExFlavor {
+    /// Human readable name for messages
+    pub fn label(&self) -> &'static str {
+        match self {
+            BepInExFlavor::BepInEx5 => "BepInEx 5",
+            BepInExFlavor::BepInEx6Mono => "BepInEx 6 Mono",
+            BepInExFlavor::BepInEx6Il2Cpp => "BepInEx 6 IL2CPP",
+            BepInExFlavor::Unknown => "Unknown BepInEx",
+        }
+    }
+}
+
 /// Status of BepInEx installation for overwrite warning
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BepInExStatus {
@@ -158,4 +283,422 @@ pub struct BepInExStatus {
     pub path: String,
     pub has_plugins: bool,
     pub plugin_count: u32,
+    pub core_version: Option<String>,
+    pub flavor: BepInExFlavor,
+    pub doorstop_version: Option<String>, // from .doorstop_version
+    pub has_winhttp_dll: bool,
+    pub has_doorstop_config: bool,
+    pub loader_warnings: Vec<String>,
+}
+
+/// Where a BepInEx pack came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PackSource {
+    Bundled,
+    R2, // download::BEPINEX_R2_URL
+    Url { url: String },
+    LocalFile { path: String },
+}
+
+/// A file written by a pack install
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub path: String, // relative to the game root, '/' separated
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Record of everything a pack install wrote, stored at BepInEx/install_manifest.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallManifest {
+    pub source: PackSource,
+    pub pack_sha256: String,
+    pub installed_at: String,
+    pub files: Vec<ManifestFile>,
+    #[serde(default)]
+    pub user_data: Vec<String>, // carried over from the replaced install, '/' separated
+}
+
+/// What uninstall_bepinex removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UninstallReport {
+    pub removed_files: Vec<String>,
+    pub removed_dirs: Vec<String>,
+    pub failed: Vec<String>,         // "path: error" for anything that couldn't be removed
+    pub used_manifest: bool,         // false when falling back to the known loader files
+    pub export_path: Option<String>, // zip of kept user data
+    pub left_behind: Vec<String>,    // files under BepInEx/ the manifest did not list, relative to the root
+}
+
+/// Where BepInEx backups are kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BackupLocation {
+    GameFolder,          // next to BepInEx in the game root
+    AppData,             // the app's data folder, out of reach of Steam "verify files"
+    Custom { path: String },
+}
+
+/// Backup preferences, stored in the app data folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSettings {
+    pub retention: u32, // how many backups to keep per installation
+    pub location: BackupLocation,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            retention: 3,
+            location: BackupLocation::GameFolder,
+        }
+    }
+}
+
+/// A backup of a previous BepInEx folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub path: String,
+    pub created_at: Option<String>,
+    pub size_bytes: u64,
+    pub plugins: Vec<String>, // plugin names found in the backup
+    pub external: bool,       // stored outside the game folder
+}
+
+/// Which kind of archive an install is for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum PackKind {
+    #[serde(rename = "bepinex")]
+    BepInEx,
+    #[serde(rename = "custom_stadiums")]
+    CustomStadiums,
+}
+
+/// What an install would do to one file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlannedAction {
+    New,
+    OverwriteIdentical,
+    OverwriteDifferent,
+    Unchanged,     // already there and not in the archive, left as is
+    MovedToBackup, // in the current BepInEx folder but not in the pack
+    Merged,        // a mapping file the pack's mappings are merged into
+}
+
+/// A file an install would touch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedFile {
+    pub path: String,
+    pub action: PlannedAction,
+    pub size: u64,
+}
+
+/// File count and bytes for one kind of change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanTotal {
+    pub action: PlannedAction,
+    pub files: u32,
+    pub bytes: u64,
+}
+
+/// Dry run of an install, from plan_install
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallPlan {
+    pub target_path: String,
+    pub files: Vec<PlannedFile>,
+    pub totals: Vec<PlanTotal>,
+}
+
+/// User data carried over from the previous BepInEx folder on reinstall
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationReport {
+    pub migrated: Vec<String>,     // paths relative to BepInEx/
+    pub not_migrated: Vec<String>, // "path: reason"
+}
+
+/// A check run before an install or restore
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreflightCheck {
+    DiskSpace,
+    WritePermission,
+    ReadOnlyFile,
+    GameRunning,
+    UnsafeArchive,
+    LeftoverStaging, // a .bst_install_* folder from an interrupted install
+}
+
+/// A problem found by a preflight check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightIssue {
+    pub check: PreflightCheck,
+    pub message: String,
+    pub path: Option<String>,
+}
+
+/// Result of the checks run before an install or restore
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub blockers: Vec<PreflightIssue>, // the operation will refuse to run
+    pub warnings: Vec<PreflightIssue>,
+    pub required_bytes: u64,
+    pub available_bytes: Option<u64>,
+}
+
+/// Why an archive entry was rejected before extraction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveViolationKind {
+    Symlink,
+    OutsideTarget,
+    CompressionRatio,
+    TotalSize,
+    Duplicate,
+    CaseCollision,
+    Unreadable,
+    RouteCollision,
+}
+
+/// An archive entry that failed the extraction safety checks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveViolation {
+    pub kind: ArchiveViolationKind,
+    pub entry: String, // name inside the archive, empty for archive-wide limits
+    pub message: String,
+}
+
+/// What a layout mapping was anchored on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutAnchor {
+    BepInExFolder,
+    WinhttpDll,
+    CustomStadiumFolder,
+    BundleFiles,
+    ArchiveRoot, // stadium packs with no bundles, routed by content alone
+    UserOverride,
+}
+
+/// How archive entries map onto the install folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveLayout {
+    pub anchor: LayoutAnchor,
+    pub strip_prefix: String, // removed from entry names, "" for none
+    pub mapped_entries: u32,
+    pub skipped_entries: Vec<String>, // files outside the prefix, not installed
+}
+
+/// Where a stadium pack entry is installed, by what it contains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteDestination {
+    CustomStadium,    // .bundle files
+    StadiumInjection, // team_mappings.txt, merged
+    AudioInject,      // audio folders and AudioMappings.txt, merged
+    Config,           // .cfg files in BepInEx/config
+}
+
+/// What a stadium pack install put in one destination folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutedDestination {
+    pub destination: RouteDestination,
+    pub path: String,
+    pub files: u32,
+    pub mappings_added: u32,
+    pub mappings_kept: u32, // pack mappings ignored because the user already maps that key
+}
+
+/// Result of installing a stadium pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StadiumPackReport {
+    pub destinations: Vec<RoutedDestination>,
+    pub skipped: Vec<String>,   // archive entries with no known destination
+    pub identical: Vec<String>, // bundles already installed byte for byte
+    pub kept: Vec<String>,      // conflicting bundles left as they were
+    pub renamed: Vec<RenamedBundle>,
+}
+
+/// A pack bundle installed under a new name to keep the existing one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenamedBundle {
+    pub from: String,
+    pub to: String,
+}
+
+/// A pack bundle that differs from one already installed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleConflict {
+    pub file: String, // relative to CustomStadium, as in team_mappings.txt
+    pub existing_size: u64,
+    pub incoming_size: u64,
+    pub rename_to: String, // name used if the user picks rename
+}
+
+/// What to do with a conflicting bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictAction {
+    Overwrite,
+    Keep,
+    Rename,
+}
+
+/// The user's choice for one conflicting bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictResolution {
+    pub file: String,
+    pub action: ConflictAction,
+}
+
+/// An installation saved in the registry, with what was last learned about it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredInstall {
+    pub id: String,
+    pub label: String,
+    pub root_path: String,
+    pub store: GameStore,
+    pub added_at: String,              // RFC 3339 timestamp
+    pub last_verified: Option<String>, // RFC 3339 timestamp of the last successful check
+    pub valid: bool,                   // false when the folder is gone or no longer looks like FM26
+    pub bepinex_version: Option<String>,
+    pub pack_sha256: Option<String>, // from BepInEx/install_manifest.json
+    pub pack_installed_at: Option<String>,
+}
+
+/// Saved installations, stored in the app data folder
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallRegistry {
+    pub installs: Vec<RegisteredInstall>,
+    pub default_id: Option<String>,
+}
+
+/// Which user-supplied name a validation error is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameField {
+    FolderName,
+    BundleFile,
+    TeamKey,
+}
+
+/// Why a user-supplied name was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameProblem {
+    Empty,
+    Separator,        // '/' or '\'
+    RelativeSegment,  // "." or ".."
+    Absolute,         // rooted, or a drive like "C:"
+    Reserved,         // CON, NUL, COM1 and the other reserved Windows names
+    ControlCharacter,
+    MappingSyntax,    // characters the mapping files use as syntax
+    StreamSeparator,  // ':', which Windows reads as an alternate data stream
+    TrailingDotOrSpace, // Windows drops them, so the file gets another name
+}
+
+/// A rejected name, with the row it came from so the UI can highlight it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvalidName {
+    pub field: NameField,
+    pub row: Option<u32>, // index into the submitted list
+    pub value: String,
+    pub problem: NameProblem,
+    pub message: String,
+}
+
+/// Error of a command taking user-supplied names
+/// Serializes as the rejected names, or as a plain message like other commands.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum NameError {
+    Invalid { invalid: Vec<InvalidName> },
+    Message(String),
+}
+
+impl From<String> for NameError {
+    fn from(message: String) -> Self {
+        NameError::Message(message)
+    }
+}
+
+/// Which part of an installation a doctor finding is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosisCheck {
+    Loader,        // BepInEx core and doorstop
+    Plugins,       // plugin DLLs from the stadium pack
+    Configs,       // BepInEx .cfg files
+    TeamMappings,  // team_mappings.txt
+    DefaultBundle, // DefaultBundle used by ReplaceAllStadiums
+    AudioMappings, // AudioMappings.txt and the folders it names
+    Log,           // LogOutput.log
+    Staging,       // .bst_install_* folders left by interrupted installs
+}
+
+/// How serious a doctor finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosisSeverity {
+    Ok,
+    Info,
+    Warning,
+    Error,
+}
+
+/// A change the doctor can make to resolve a finding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FixAction {
+    InstallPack,                                 // handled by the install dialog, not apply_fix
+    ApplyProtonLaunchOptions,
+    RemoveTeamMappings { team_ids: Vec<i32> },
+    DisableReplaceAllStadiums,
+    RemoveAudioMappings { team_keys: Vec<String> },
+    SetAsideConfig { file: String }, // renamed to .broken so the plugin writes a fresh one
+}
+
+/// One result of the installation doctor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisFinding {
+    pub check: DiagnosisCheck,
+    pub severity: DiagnosisSeverity,
+    pub message: String,
+    pub details: Vec<String>, // offending lines, files or log entries
+    pub fix: Option<FixAction>,
+}
+
+/// Everything the doctor found, worst findings first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosisReport {
+    pub findings: Vec<DiagnosisFinding>,
+    pub errors: u32,
+    pub warnings: u32,
+    pub checked_at: String, // RFC 3339 timestamp
+}
+
+/// How the game was started by launch_game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchMethod {
+    Steam,  // steam://rungameid/<appid>, so Steam applies launch options and Proton
+    Direct, // the game executable, for non-Steam installs
+}
+
+/// What happened in a game session, emitted as "game-exited" when FM26 closes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameSessionSummary {
+    pub install_id: String,
+    pub method: LaunchMethod,
+    pub started_at: String, // RFC 3339 timestamp
+    pub ended_at: String,
+    pub game_detected: bool,     // false when the game never showed up after a Steam launch
+    pub exit_code: Option<i32>,  // only known for direct launches
+    pub log_updated: bool,       // false when BepInEx did not write LogOutput.log this session
+    pub plugins_loaded: Vec<String>,
+    pub error_count: u32,
+    pub errors: Vec<String>, // the newest error lines
+    pub stadium_injections: u32,
 }
@@ -0,0 +1,144 @@
+use crate::models::{InvalidName, NameError, NameField, NameProblem};
+
+/// Device names Windows reserves, with or without an extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1",
+    "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Checks a name used as a single path component: an audio folder or bundle file
+pub fn check_component(name: &str) -> Result<(), NameProblem> {
+    if name.trim().is_empty() {
+        return Err(NameProblem::Empty);
+    }
+    if name.chars().any(char::is_control) {
+        return Err(NameProblem::ControlCharacter);
+    }
+    if name.starts_with(['/', '\\']) || name.as_bytes().get(1) == Some(&b':') {
+        return Err(NameProblem::Absolute);
+    }
+    if name.contains(['/', '\\']) {
+        return Err(NameProblem::Separator);
+    }
+    if name == "." || name == ".." {
+        return Err(NameProblem::RelativeSegment);
+    }
+    if name.contains(':') {
+        return Err(NameProblem::StreamSeparator);
+    }
+    if name.ends_with(['.', ' ']) {
+        return Err(NameProblem::TrailingDotOrSpace);
+    }
+
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        return Err(NameProblem::Reserved);
+    }
+    Ok(())
+}
+
+/// Checks a team key of AudioMappings.txt, e.g. "680" or "*"
+/// Keys aren't paths, but the same rules keep them from breaking the file.
+pub fn check_team_key(key: &str) -> Result<(), NameProblem> {
+    if key.contains(['=', ':', '#']) {
+        return Err(NameProblem::MappingSyntax);
+    }
+    check_component(key)
+}
+
+fn describe(field: NameField, value: &str, problem: NameProblem) -> String {
+    let field = match field {
+        NameField::FolderName => "Folder name",
+        NameField::BundleFile => "Bundle file",
+        NameField::TeamKey => "Team key",
+    };
+    let reason = match problem {
+        NameProblem::Empty => "is empty",
+        NameProblem::Separator => "contains a path separator",
+        NameProblem::RelativeSegment => "is a relative path segment",
+        NameProblem::Absolute => "is an absolute path",
+        NameProblem::Reserved => "is a reserved Windows name",
+        NameProblem::ControlCharacter => "contains control characters",
+        NameProblem::MappingSyntax => "contains '=', ':' or '#'",
+        NameProblem::StreamSeparator => "contains ':'",
+        NameProblem::TrailingDotOrSpace => "ends with a dot or space",
+    };
+    format!("{} \"{}\" {}", field, value.escape_debug(), reason)
+}
+
+/// Collects rejected names across the rows of a submitted list
+#[derive(Default)]
+pub struct NameChecks {
+    invalid: Vec<InvalidName>,
+}
+
+impl NameChecks {
+    /// Records a name that failed a check
+    pub fn check(&mut self, field: NameField, row: Option<u32>, value: &str, result: Result<(), NameProblem>) {
+        if let Err(problem) = result {
+            self.invalid.push(InvalidName {
+                field,
+                row,
+                value: value.to_string(),
+                problem,
+                message: describe(field, value, problem),
+            });
+        }
+    }
+
+    /// Fails with every rejected name, if there are any
+    pub fn finish(self) -> Result<(), NameError> {
+        if self.invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(NameError::Invalid { invalid: self.invalid })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_plain_names() {
+        assert_eq!(check_component("wembley.bundle"), Ok(()));
+        assert_eq!(check_component("Arsenal Chants"), Ok(()));
+        assert_eq!(check_component(".hidden"), Ok(()));
+        assert_eq!(check_component("CONCACAF.bundle"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_names_that_leave_the_folder() {
+        assert_eq!(check_component("a/b.bundle"), Err(NameProblem::Separator));
+        assert_eq!(check_component("a\\b.bundle"), Err(NameProblem::Separator));
+        assert_eq!(check_component("/etc"), Err(NameProblem::Absolute));
+        assert_eq!(check_component("C:"), Err(NameProblem::Absolute));
+        assert_eq!(check_component("C:evil.bundle"), Err(NameProblem::Absolute));
+        assert_eq!(check_component(".."), Err(NameProblem::RelativeSegment));
+        assert_eq!(check_component("."), Err(NameProblem::RelativeSegment));
+    }
+
+    #[test]
+    fn rejects_names_windows_would_change() {
+        assert_eq!(check_component(""), Err(NameProblem::Empty));
+        assert_eq!(check_component("  "), Err(NameProblem::Empty));
+        assert_eq!(check_component("a\tb"), Err(NameProblem::ControlCharacter));
+        assert_eq!(check_component("CON.txt"), Err(NameProblem::Reserved));
+        assert_eq!(check_component("lpt1"), Err(NameProblem::Reserved));
+        assert_eq!(check_component("wembley.bundle:evil"), Err(NameProblem::StreamSeparator));
+        assert_eq!(check_component("wembley.bundle."), Err(NameProblem::TrailingDotOrSpace));
+        assert_eq!(check_component("wembley "), Err(NameProblem::TrailingDotOrSpace));
+    }
+
+    #[test]
+    fn checks_team_key_syntax() {
+        assert_eq!(check_team_key("680"), Ok(()));
+        assert_eq!(check_team_key("*"), Ok(()));
+        assert_eq!(check_team_key("6=80"), Err(NameProblem::MappingSyntax));
+        assert_eq!(check_team_key("a:b"), Err(NameProblem::MappingSyntax));
+        assert_eq!(check_team_key("#680"), Err(NameProblem::MappingSyntax));
+        assert_eq!(check_team_key(""), Err(NameProblem::Empty));
+        assert_eq!(check_team_key("NUL"), Err(NameProblem::Reserved));
+    }
+}
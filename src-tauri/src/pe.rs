@@ -0,0 +1,341 @@
+use std::fs;
+use std::path::Path;
+
+/// Data directory index of the resource table
+const RESOURCE_DIRECTORY: usize = 2;
+
+/// Resource type id of VS_VERSIONINFO
+const RT_VERSION: u32 = 16;
+
+/// Signature that starts VS_FIXEDFILEINFO
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+
+/// A section header from a PE image
+#[derive(Debug, Clone)]
+struct Section {
+    virtual_address: u32,
+    virtual_size: u32,
+    raw_offset: u32,
+    raw_size: u32,
+}
+
+/// A Windows PE image (DLL or EXE) loaded into memory
+pub struct PeFile {
+    data: Vec<u8>,
+    sections: Vec<Section>,
+    data_directories: Vec<(u32, u32)>, // (rva, size)
+}
+
+/// Version details from a PE file's VS_VERSIONINFO resource
+#[derive(Debug, Clone)]
+pub struct PeVersionInfo {
+    pub file_version: String,            // from VS_FIXEDFILEINFO, e.g. "6.0.0.0"
+    pub product_version: Option<String>, // from StringFileInfo, e.g. "6.0.0-be.735+5fef357"
+}
+
+impl PeFile {
+    /// Reads and parses the headers of a PE file
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(data).ok_or_else(|| format!("Not a valid PE file: {}", path.display()))
+    }
+
+    fn parse(data: Vec<u8>) -> Option<Self> {
+        if data.get(0..2)? != b"MZ" {
+            return None;
+        }
+
+        let pe_offset = read_u32(&data, 0x3C)? as usize;
+        if data.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+            return None;
+        }
+
+        let coff = pe_offset + 4;
+        let section_count = read_u16(&data, coff + 2)? as usize;
+        let optional_size = read_u16(&data, coff + 16)? as usize;
+        let optional = coff + 20;
+
+        // PE32 and PE32+ differ in where the data directories start
+        let (dir_count_offset, dirs_offset) = match read_u16(&data, optional)? {
+            0x10B => (optional + 92, optional + 96),
+            0x20B => (optional + 108, optional + 112),
+            _ => return None,
+        };
+
+        let dir_count = read_u32(&data, dir_count_offset)?.min(16) as usize;
+        let data_directories = (0..dir_count)
+            .map(|i| {
+                let offset = dirs_offset + i * 8;
+                Some((read_u32(&data, offset)?, read_u32(&data, offset + 4)?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let sections_offset = optional + optional_size;
+        let sections = (0..section_count)
+            .map(|i| {
+                let offset = sections_offset + i * 40;
+                Some(Section {
+                    virtual_size: read_u32(&data, offset + 8)?,
+                    virtual_address: read_u32(&data, offset + 12)?,
+                    raw_size: read_u32(&data, offset + 16)?,
+                    raw_offset: read_u32(&data, offset + 20)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            data,
+            sections,
+            data_directories,
+        })
+    }
+
+    /// Returns the (rva, size) of a data directory, if present
+    pub fn data_directory(&self, index: usize) -> Option<(u32, u32)> {
+        self.data_directories
+            .get(index)
+            .copied()
+            .filter(|(rva, size)| *rva != 0 && *size != 0)
+    }
+
+    /// Converts a relative virtual address into a file offset
+    /// Section headers come from the file, so values that overflow give None.
+    pub fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        let section = self.sections.iter().find(|s| {
+            let end = s.virtual_address.checked_add(s.virtual_size.max(s.raw_size));
+            rva >= s.virtual_address && end.is_some_and(|end| rva < end)
+        })?;
+
+        (rva - section.virtual_address)
+            .checked_add(section.raw_offset)
+            .map(|offset| offset as usize)
+    }
+
+    /// Returns the bytes at an rva, bounded by size
+    pub fn slice_at_rva(&self, rva: u32, size: u32) -> Option<&[u8]> {
+        let offset = self.rva_to_offset(rva)?;
+        self.data.get(offset..offset.checked_add(size as usize)?)
+    }
+
+    /// Reads the version resource (VS_VERSIONINFO)
+    pub fn version_info(&self) -> Option<PeVersionInfo> {
+        let blob = self.version_resource()?;
+
+        // VS_FIXEDFILEINFO follows the "VS_VERSION_INFO" key, 4-byte aligned
+        let fixed = (0..blob.len().saturating_sub(4))
+            .step_by(4)
+            .find(|&i| read_u32(blob, i) == Some(FIXED_FILE_INFO_SIGNATURE))?;
+
+        let ms = read_u32(blob, fixed + 8)?;
+        let ls = read_u32(blob, fixed + 12)?;
+        let file_version = format!("{}.{}.{}.{}", ms >> 16, ms & 0xFFFF, ls >> 16, ls & 0xFFFF);
+
+        Some(PeVersionInfo {
+            file_version,
+            product_version: find_version_string(blob, "ProductVersion"),
+        })
+    }
+
+    /// Walks the resource tree to the first RT_VERSION entry
+    fn version_resource(&self) -> Option<&[u8]> {
+        let (rsrc_rva, rsrc_size) = self.data_directory(RESOURCE_DIRECTORY)?;
+        let rsrc = self.slice_at_rva(rsrc_rva, rsrc_size)?;
+
+        // Level 1: type, level 2: name, level 3: language
+        let type_entry = find_resource_entry(rsrc, 0, Some(RT_VERSION))?;
+        let name_dir = subdirectory_offset(type_entry)?;
+        let name_entry = find_resource_entry(rsrc, name_dir, None)?;
+        let lang_dir = subdirectory_offset(name_entry)?;
+        let lang_entry = find_resource_entry(rsrc, lang_dir, None)?;
+
+        // Leaf entries point at IMAGE_RESOURCE_DATA_ENTRY
+        if lang_entry & 0x8000_0000 != 0 {
+            return None;
+        }
+        let data_entry = lang_entry as usize;
+        let data_rva = read_u32(rsrc, data_entry)?;
+        let data_size = read_u32(rsrc, data_entry + 4)?;
+
+        self.slice_at_rva(data_rva, data_size)
+    }
+}
+
+/// Finds a resource directory entry by id, or the first entry if id is None.
+/// Returns the entry's OffsetToData field.
+fn find_resource_entry(rsrc: &[u8], dir_offset: usize, id: Option<u32>) -> Option<u32> {
+    let named = read_u16(rsrc, dir_offset + 12)? as usize;
+    let ids = read_u16(rsrc, dir_offset + 14)? as usize;
+
+    (0..named + ids).find_map(|i| {
+        let entry = dir_offset + 16 + i * 8;
+        let name = read_u32(rsrc, entry)?;
+        let offset = read_u32(rsrc, entry + 4)?;
+        match id {
+            Some(id) if name != id => None,
+            _ => Some(offset),
+        }
+    })
+}
+
+fn subdirectory_offset(entry: u32) -> Option<usize> {
+    (entry & 0x8000_0000 != 0).then_some((entry & 0x7FFF_FFFF) as usize)
+}
+
+/// Finds a value in the StringFileInfo table of a VS_VERSIONINFO blob
+fn find_version_string(blob: &[u8], key: &str) -> Option<String> {
+    let needle: Vec<u8> = key
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(|c| c.to_le_bytes())
+        .collect();
+
+    let key_start = blob.windows(needle.len()).position(|w| w == needle.as_slice())?;
+
+    // The value starts at the next 4-byte boundary after the key's terminator
+    let mut value_start = key_start + needle.len();
+    value_start = (value_start + 3) & !3;
+
+    let units: Vec<u16> = blob
+        .get(value_start..)?
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&c| c != 0)
+        .collect();
+
+    let value = String::from_utf16(&units).ok()?;
+    let value = value.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+pub fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+pub fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn utf16z(text: &str) -> Vec<u8> {
+        text.encode_utf16().chain(std::iter::once(0)).flat_map(|c| c.to_le_bytes()).collect()
+    }
+
+    fn pad4(data: &mut Vec<u8>) {
+        while !data.len().is_multiple_of(4) {
+            data.push(0);
+        }
+    }
+
+    /// VS_VERSIONINFO for file version 6.1.2.735 and product version 6.1.2-be.735
+    fn version_blob() -> Vec<u8> {
+        let mut blob = vec![0; 6]; // wLength, wValueLength, wType
+        blob.extend(utf16z("VS_VERSION_INFO"));
+        pad4(&mut blob);
+        blob.extend(FIXED_FILE_INFO_SIGNATURE.to_le_bytes());
+        blob.extend(0x0001_0000u32.to_le_bytes());
+        blob.extend(((6u32 << 16) | 1).to_le_bytes());
+        blob.extend(((2u32 << 16) | 735).to_le_bytes());
+        blob.extend([0; 36]);
+        blob.extend([0; 6]);
+        blob.extend(utf16z("ProductVersion"));
+        pad4(&mut blob);
+        blob.extend(utf16z("6.1.2-be.735"));
+        blob
+    }
+
+    /// A PE32 image with one section holding a resource tree: RT_VERSION / 1 / 0x409
+    fn pe_image(blob: &[u8]) -> Vec<u8> {
+        let mut rsrc = vec![0u8; 0x58];
+        rsrc[14] = 1;
+        put_u32(&mut rsrc, 0x10, RT_VERSION);
+        put_u32(&mut rsrc, 0x14, 0x8000_0018);
+        rsrc[0x18 + 14] = 1;
+        put_u32(&mut rsrc, 0x28, 1);
+        put_u32(&mut rsrc, 0x2C, 0x8000_0030);
+        rsrc[0x30 + 14] = 1;
+        put_u32(&mut rsrc, 0x40, 0x409);
+        put_u32(&mut rsrc, 0x44, 0x48);
+        put_u32(&mut rsrc, 0x48, 0x1000 + 0x58);
+        put_u32(&mut rsrc, 0x4C, blob.len() as u32);
+        rsrc.extend(blob);
+
+        let mut image = vec![0u8; 0x200];
+        image[0..2].copy_from_slice(b"MZ");
+        put_u32(&mut image, 0x3C, 0x40);
+        image[0x40..0x44].copy_from_slice(b"PE\0\0");
+        image[0x46] = 1; // one section
+        image[0x54] = 0xE0; // optional header size
+        image[0x58..0x5A].copy_from_slice(&0x10Bu16.to_le_bytes());
+        put_u32(&mut image, 0x58 + 92, 16);
+        put_u32(&mut image, 0x58 + 96 + RESOURCE_DIRECTORY * 8, 0x1000);
+        put_u32(&mut image, 0x58 + 96 + RESOURCE_DIRECTORY * 8 + 4, rsrc.len() as u32);
+
+        let section = 0x58 + 0xE0;
+        put_u32(&mut image, section + 8, rsrc.len() as u32);
+        put_u32(&mut image, section + 12, 0x1000);
+        put_u32(&mut image, section + 16, rsrc.len() as u32);
+        put_u32(&mut image, section + 20, 0x200);
+
+        image.extend(rsrc);
+        image
+    }
+
+    #[test]
+    fn reads_version_resources() {
+        let pe = PeFile::parse(pe_image(&version_blob())).unwrap();
+        assert_eq!(pe.rva_to_offset(0x1000), Some(0x200));
+        assert_eq!(pe.rva_to_offset(0x0FFF), None);
+
+        let version = pe.version_info().unwrap();
+        assert_eq!(version.file_version, "6.1.2.735");
+        assert_eq!(version.product_version.as_deref(), Some("6.1.2-be.735"));
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_pe_images() {
+        assert!(PeFile::parse(b"not a PE file".to_vec()).is_none());
+
+        let mut image = pe_image(&version_blob());
+        image[0x40] = b'X';
+        assert!(PeFile::parse(image).is_none());
+
+        // Headers cut short
+        assert!(PeFile::parse(pe_image(&version_blob())[..0x100].to_vec()).is_none());
+    }
+
+    #[test]
+    fn finds_no_version_without_a_resource_table() {
+        let mut image = pe_image(&version_blob());
+        put_u32(&mut image, 0x58 + 96 + RESOURCE_DIRECTORY * 8, 0);
+        assert!(PeFile::parse(image).unwrap().version_info().is_none());
+    }
+
+    #[test]
+    fn ignores_section_headers_that_overflow() {
+        let section = 0x58 + 0xE0;
+
+        // The section's end is past u32::MAX
+        let mut image = pe_image(&version_blob());
+        put_u32(&mut image, section + 12, 0xFFFF_F000);
+        put_u32(&mut image, section + 8, 0x2000);
+        let pe = PeFile::parse(image).unwrap();
+        assert_eq!(pe.rva_to_offset(0xFFFF_F800), None);
+        assert!(pe.version_info().is_none());
+
+        // The file offset is past u32::MAX
+        let mut image = pe_image(&version_blob());
+        put_u32(&mut image, section + 20, 0xFFFF_FFF0);
+        let pe = PeFile::parse(image).unwrap();
+        assert_eq!(pe.rva_to_offset(0x1020), None);
+        assert!(pe.version_info().is_none());
+    }
+}
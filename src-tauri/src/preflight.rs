@@ -0,0 +1,189 @@
+use crate::backups;
+use crate::archive::Archive;
+use crate::install_plan::{open_pack, Placement};
+use crate::models::{ArchiveLayout, Fm26Installation, PackKind, PreflightCheck, PreflightIssue, PreflightReport};
+use crate::process;
+use crate::registry;
+use crate::staging;
+use std::fs;
+use std::path::Path;
+
+/// File created and removed to test write access
+const WRITE_TEST_FILE: &str = ".bst_write_test";
+
+fn issue(check: PreflightCheck, message: String, path: Option<&Path>) -> PreflightIssue {
+    PreflightIssue {
+        check,
+        message,
+        path: path.map(|p| p.to_string_lossy().to_string()),
+    }
+}
+
+/// Checks that files can be created in a folder
+fn check_writable(dir: &Path, report: &mut PreflightReport) {
+    let test_path = dir.join(WRITE_TEST_FILE);
+    let result = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&test_path);
+
+    match result {
+        Ok(_) => {
+            let _ = fs::remove_file(&test_path);
+        }
+        Err(e) => report.blockers.push(issue(
+            PreflightCheck::WritePermission,
+            format!("Cannot write to {}: {}", dir.display(), e),
+            Some(dir),
+        )),
+    }
+}
+
+/// Runs the checks shared by every operation: space, permissions and the game process
+fn check_common(install: &Fm26Installation, required_bytes: u64) -> PreflightReport {
+    let root = Path::new(&install.root_path);
+    let bepinex_path = Path::new(&install.bep_in_ex_path);
+
+    let mut report = PreflightReport {
+        blockers: Vec::new(),
+        warnings: Vec::new(),
+        required_bytes,
+        available_bytes: fs4::available_space(root).ok(),
+    };
+
+    if let Some(available) = report.available_bytes {
+        if available < required_bytes {
+            report.blockers.push(issue(
+                PreflightCheck::DiskSpace,
+                format!(
+                    "Not enough free space: {} MB needed, {} MB available",
+                    required_bytes.div_ceil(1024 * 1024),
+                    available / (1024 * 1024)
+                ),
+                Some(root),
+            ));
+        }
+    }
+
+    check_writable(root, &mut report);
+    if bepinex_path.is_dir() {
+        check_writable(bepinex_path, &mut report);
+    }
+
+    for workspace in staging::leftover_workspaces(root) {
+        report.warnings.push(issue(
+            PreflightCheck::LeftoverStaging,
+            format!(
+                "{} is left from an interrupted install and may hold files it replaced",
+                workspace.display()
+            ),
+            Some(&workspace),
+        ));
+    }
+
+    if process::is_game_running() {
+        report.blockers.push(issue(
+            PreflightCheck::GameRunning,
+            "FM26 is running. Close the game first, its files are locked while it runs.".to_string(),
+            None,
+        ));
+    }
+
+    report
+}
+
+/// Checks an install of a BepInEx or stadium pack before anything is written
+/// Takes the archive the installer already opened, so it is only listed once.
+pub fn check_install(
+    archive: &Archive,
+    kind: PackKind,
+    layout: &ArchiveLayout,
+    install: &Fm26Installation,
+) -> Result<PreflightReport, String> {
+    let placement = Placement::new(kind, layout, install)?;
+
+    // Staging holds a full uncompressed copy before the swap
+    let mut required_bytes = 0;
+    let mut read_only = Vec::new();
+
+    for entry in archive.entries() {
+        required_bytes += entry.size;
+
+        let Some((dest, _)) = placement.destination(entry) else {
+            continue;
+        };
+        if fs::metadata(&dest).is_ok_and(|m| m.permissions().readonly()) {
+            read_only.push(dest);
+        }
+    }
+
+    let mut report = check_common(install, required_bytes);
+
+    for violation in placement.violations(archive) {
+        report.blockers.push(issue(PreflightCheck::UnsafeArchive, violation.message, None));
+    }
+
+    for path in read_only {
+        report.warnings.push(issue(
+            PreflightCheck::ReadOnlyFile,
+            format!("{} is read-only; it will be replaced but may not be removable later", path.display()),
+            Some(&path),
+        ));
+    }
+
+    Ok(report)
+}
+
+/// Checks a backup restore; backups stored outside the game folder are copied back
+pub fn check_restore(install: &Fm26Installation, backup_path: &Path) -> PreflightReport {
+    let root = Path::new(&install.root_path);
+    let required_bytes = if backup_path.parent() == Some(root) {
+        0
+    } else {
+        backups::dir_size(backup_path)
+    };
+
+    check_common(install, required_bytes)
+}
+
+/// Checks an uninstall: write access and the game process, which locks the files
+pub fn check_uninstall(install: &Fm26Installation) -> PreflightReport {
+    check_common(install, 0)
+}
+
+/// Turns blockers into an error, so an operation can refuse to start
+pub fn ensure_ready(report: &PreflightReport) -> Result<(), String> {
+    if report.blockers.is_empty() {
+        return Ok(());
+    }
+
+    let messages: Vec<&str> = report.blockers.iter().map(|b| b.message.as_str()).collect();
+    Err(messages.join("\n"))
+}
+
+/// Checks disk space, permissions, read-only files, archive safety and the game process before an install
+#[tauri::command]
+pub fn preflight_install(
+    zip_path: Option<String>,
+    kind: PackKind,
+    layout_prefix: Option<String>,
+    app_handle: tauri::AppHandle,
+    install_id: String,
+) -> Result<PreflightReport, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let (archive, layout) = open_pack(zip_path, kind, layout_prefix, &app_handle)?;
+    check_install(&archive, kind, &layout, &install)
+}
+
+/// Checks disk space, permissions and the game process before restoring a backup
+#[tauri::command]
+pub fn preflight_restore(
+    app_handle: tauri::AppHandle,
+    install_id: String,
+    name: String,
+) -> Result<PreflightReport, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let backup_path = backups::find_backup(&app_handle, Path::new(&install.root_path), &name)?;
+    Ok(check_restore(&install, &backup_path))
+}
@@ -0,0 +1,162 @@
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+/// Executable names of the FM26 game process
+/// Linux runs the Windows build through Proton, so it shares the Windows names.
+#[cfg(not(target_os = "macos"))]
+pub const GAME_EXECUTABLES: [&str; 2] = ["fm.exe", "Football Manager 2026.exe"];
+
+/// Executable names of the FM26 game process, the binary inside fm.app/Contents/MacOS
+#[cfg(target_os = "macos")]
+pub const GAME_EXECUTABLES: [&str; 2] = ["fm", "Football Manager 2026"];
+
+/// Longest process name Linux keeps in /proc/<pid>/comm
+#[cfg(target_os = "linux")]
+const COMM_MAX_LEN: usize = 15;
+
+/// Executable names of the Steam client
+pub const STEAM_EXECUTABLES: [&str; 3] = ["steam", "steam.exe", "steam_osx"];
+
+/// Checks whether a path or command names one of the given executables
+fn is_executable(command: &str, names: &[&str]) -> bool {
+    let name = command.rsplit(['/', '\\']).next().unwrap_or(command);
+    names.iter().any(|exe| name.eq_ignore_ascii_case(exe))
+}
+
+/// Checks a /proc/<pid>/comm name, which the kernel cuts to 15 bytes
+/// A cut name can't tell "Football Manager 2026.exe" from other versions, so
+/// only names that fit whole are matched here.
+#[cfg(target_os = "linux")]
+fn is_comm_of(comm: &str, names: &[&str]) -> bool {
+    names
+        .iter()
+        .any(|exe| exe.len() <= COMM_MAX_LEN && comm.eq_ignore_ascii_case(exe))
+}
+
+/// Checks a process by its comm, its exe link and its NUL separated command line
+/// Wine passes the Windows path of the exe among the first arguments.
+#[cfg(target_os = "linux")]
+fn is_process_of(comm: &str, exe: Option<&Path>, cmdline: &[u8], names: &[&str]) -> bool {
+    is_comm_of(comm, names)
+        || exe.is_some_and(|exe| is_executable(&exe.to_string_lossy(), names))
+        || cmdline
+            .split(|b| *b == 0)
+            .take(3)
+            .any(|arg| is_executable(&String::from_utf8_lossy(arg), names))
+}
+
+/// Finds running processes with one of the given executable names, returning their pids
+/// Under Proton a game shows up as a Wine process whose command line holds the .exe
+/// among its first arguments (after wine64-preloader or the wine loader), and whose
+/// comm is the .exe name, cut to 15 bytes.
+#[cfg(target_os = "linux")]
+pub fn find_processes(names: &[&str]) -> Vec<u32> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|pid| {
+            let Ok(comm) = std::fs::read_to_string(format!("/proc/{}/comm", pid)) else {
+                return false;
+            };
+            let exe = std::fs::read_link(format!("/proc/{}/exe", pid)).ok();
+            let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).unwrap_or_default();
+            is_process_of(comm.trim_end(), exe.as_deref(), &cmdline, names)
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+pub fn find_processes(names: &[&str]) -> Vec<u32> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let Ok(output) = std::process::Command::new("tasklist")
+        .args(["/FO", "CSV", "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+    else {
+        return Vec::new();
+    };
+
+    // Lines look like: "fm.exe","1234","Console","1","1,234,567 K"
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split("\",\"").map(|f| f.trim_matches('"'));
+            let name = fields.next()?;
+            let pid = fields.next()?.parse::<u32>().ok()?;
+            is_executable(name, names).then_some(pid)
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub fn find_processes(names: &[&str]) -> Vec<u32> {
+    let Ok(output) = std::process::Command::new("ps").args(["-axo", "pid=,comm="]).output() else {
+        return Vec::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (pid, command) = line.trim().split_once(' ')?;
+            let pid = pid.parse::<u32>().ok()?;
+            is_executable(command.trim(), names).then_some(pid)
+        })
+        .collect()
+}
+
+/// Finds running FM26 processes, returning their pids
+pub fn find_game_processes() -> Vec<u32> {
+    find_processes(&GAME_EXECUTABLES)
+}
+
+/// Checks whether the Steam client is running
+/// Steam rewrites localconfig.vdf on exit, undoing any edit made while it runs.
+pub fn is_steam_running() -> bool {
+    !find_processes(&STEAM_EXECUTABLES).is_empty()
+}
+
+/// Checks whether FM26 is running
+pub fn is_game_running() -> bool {
+    !find_game_processes().is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_executable_paths_from_any_platform() {
+        let names = ["fm.exe", "Football Manager 2026.exe"];
+        assert!(is_executable("Z:\\games\\FM26\\fm.exe", &names));
+        assert!(is_executable("/home/me/FM26/FM.EXE", &names));
+        assert!(is_executable("Football Manager 2026.exe", &names));
+        assert!(!is_executable("/usr/bin/fm", &names));
+        assert!(!is_executable("steam://rungameid/3551340", &STEAM_EXECUTABLES));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn confirms_truncated_comm_names() {
+        let names = ["fm.exe", "Football Manager 2026.exe"];
+        assert!(is_comm_of("fm.exe", &names));
+        assert!(!is_comm_of("Football Manage", &names));
+
+        // Another FM version has the same cut comm
+        let fm24 = b"Z:\\games\\FM24\\Football Manager 2024.exe\0--skip-launcher\0";
+        let fm26 = b"Z:\\games\\FM26\\Football Manager 2026.exe\0--skip-launcher\0";
+        assert!(!is_process_of("Football Manage", None, fm24, &names));
+        assert!(is_process_of("Football Manage", None, fm26, &names));
+        assert!(is_process_of(
+            "Football Manage",
+            Some(Path::new("/games/FM26/Football Manager 2026.exe")),
+            b"",
+            &names
+        ));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::models::{ProtonLaunchOptions, ProtonStatus};
+use crate::steam::{self, FM26_STEAM_APP_ID};
+use crate::vdf::{self, Vdf};
+use crate::{process, registry};
+use chrono::Local;
+use std::fs;
+use std::path::Path;
+
+/// Launch options that make Wine load BepInEx's doorstop winhttp.dll
+pub const WINHTTP_LAUNCH_OPTIONS: &str = "WINEDLLOVERRIDES=\"winhttp=n,b\" %command%";
+
+/// Checks whether launch options override winhttp as native-first and run %command%
+pub fn has_winhttp_override(launch_options: &str) -> bool {
+    if !launch_options.contains("%command%") {
+        return false;
+    }
+
+    let Some(start) = launch_options.find("WINEDLLOVERRIDES=") else {
+        return false;
+    };
+
+    // The value runs to the next unquoted space
+    let mut value = String::new();
+    let mut in_quotes = false;
+    for c in launch_options[start + "WINEDLLOVERRIDES=".len()..].chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => break,
+            c => value.push(c),
+        }
+    }
+
+    // Entries look like "winhttp=n,b" or "winhttp,version=n,b", separated by ';'
+    value.split(';').any(|entry| {
+        let Some((dlls, mode)) = entry.split_once('=') else {
+            return false;
+        };
+        dlls.split(',').any(|dll| dll.trim().eq_ignore_ascii_case("winhttp"))
+            && mode.trim().starts_with('n')
+    })
+}
+
+/// Adds the winhttp override to existing launch options, keeping what the user had
+fn merge_launch_options(existing: &str) -> String {
+    let existing = existing.trim();
+
+    if existing.is_empty() {
+        return WINHTTP_LAUNCH_OPTIONS.to_string();
+    }
+
+    if let Some(start) = existing.find("WINEDLLOVERRIDES=") {
+        // Prepend winhttp to the existing override list
+        let insert_at = start + "WINEDLLOVERRIDES=".len();
+        let (head, tail) = existing.split_at(insert_at);
+        return if let Some(rest) = tail.strip_prefix('"') {
+            format!("{}\"winhttp=n,b;{}", head, rest)
+        } else {
+            format!("{}\"winhttp=n,b;\"{}", head, tail)
+        };
+    }
+
+    if existing.contains("%command%") {
+        format!("WINEDLLOVERRIDES=\"winhttp=n,b\" {}", existing)
+    } else {
+        // Plain options are game arguments and belong after %command%
+        format!("{} {}", WINHTTP_LAUNCH_OPTIONS, existing)
+    }
+}
+
+/// Reads FM26's launch options from a Steam user's localconfig.vdf
+fn read_launch_options(localconfig: &Path) -> Result<Option<String>, String> {
+    let doc = vdf::parse_file(localconfig)?;
+
+    Ok(doc
+        .get_path(&steam::launch_options_key_path(FM26_STEAM_APP_ID))
+        .and_then(Vdf::as_str)
+        .map(|s| s.to_string()))
+}
+
+/// Checks whether an installation is run through Proton from Steam
+fn uses_proton(root: &Path) -> bool {
+    cfg!(target_os = "linux") && steam::fm26_app_id_for_install(root).is_some()
+}
+
+/// Inspects the Proton prefix and Steam launch options for an FM26 install
+pub fn proton_status(root: &Path) -> ProtonStatus {
+    if !uses_proton(root) {
+        return ProtonStatus {
+            uses_proton: false,
+            prefix_path: None,
+            launch_options: Vec::new(),
+            override_configured: false,
+        };
+    }
+
+    let prefix_path = steam::find_compat_prefix(FM26_STEAM_APP_ID)
+        .map(|p| p.to_string_lossy().to_string());
+
+    let launch_options: Vec<ProtonLaunchOptions> = steam::find_localconfig_files()
+        .into_iter()
+        .map(|localconfig| {
+            let options = read_launch_options(&localconfig).ok().flatten();
+            ProtonLaunchOptions {
+                localconfig_path: localconfig.to_string_lossy().to_string(),
+                has_winhttp_override: options.as_deref().is_some_and(has_winhttp_override),
+                launch_options: options,
+                backup_path: None,
+            }
+        })
+        .collect();
+
+    let override_configured = launch_options.iter().any(|l| l.has_winhttp_override);
+
+    ProtonStatus {
+        uses_proton: true,
+        prefix_path,
+        launch_options,
+        override_configured,
+    }
+}
+
+/// Checks whether BepInEx would be ignored because Proton loads its builtin winhttp
+pub fn blocks_bepinex(root: &Path) -> bool {
+    let status = proton_status(root);
+    status.uses_proton && !status.override_configured
+}
+
+/// Gets the Proton/Wine status of an FM26 installation
+#[tauri::command]
+pub fn get_proton_status(app_handle: tauri::AppHandle, install_id: String) -> Result<ProtonStatus, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    Ok(proton_status(Path::new(&install.root_path)))
+}
+
+/// Writes the winhttp override into FM26's Steam launch options
+/// Each localconfig.vdf is backed up first and only the LaunchOptions value is
+/// changed. Steam rewrites this file on exit, so this refuses to run while it is open.
+#[tauri::command]
+pub fn apply_proton_launch_options(app_handle: tauri::AppHandle, install_id: String) -> Result<Vec<ProtonLaunchOptions>, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let root = Path::new(&install.root_path);
+
+    if !uses_proton(root) {
+        return Err("This installation is not run through Proton".to_string());
+    }
+
+    if process::is_steam_running() {
+        return Err("Close Steam first. It overwrites its launch options when it exits.".to_string());
+    }
+
+    let localconfigs = steam::find_localconfig_files();
+    if localconfigs.is_empty() {
+        return Err("No Steam localconfig.vdf found. Launch the game from Steam once first.".to_string());
+    }
+
+    let key_path = steam::launch_options_key_path(FM26_STEAM_APP_ID);
+    let mut updated = Vec::new();
+
+    for localconfig in localconfigs {
+        let content = fs::read_to_string(&localconfig)
+            .map_err(|e| format!("Failed to read {}: {}", localconfig.display(), e))?;
+        let doc = vdf::parse(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", localconfig.display(), e))?;
+
+        let existing = doc
+            .get_path(&key_path)
+            .and_then(Vdf::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        if has_winhttp_override(&existing) {
+            continue;
+        }
+
+        // Back up before touching Steam's config
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let backup_path = localconfig.with_file_name(format!("localconfig.vdf.backup_{}", timestamp));
+        fs::copy(&localconfig, &backup_path)
+            .map_err(|e| format!("Failed to back up {}: {}", localconfig.display(), e))?;
+
+        let launch_options = merge_launch_options(&existing);
+        let patched = vdf::set_value_in_place(&content, &key_path, &launch_options)
+            .map_err(|e| format!("Failed to update {}: {}", localconfig.display(), e))?;
+
+        fs::write(&localconfig, patched)
+            .map_err(|e| format!("Failed to write {}: {}", localconfig.display(), e))?;
+
+        updated.push(ProtonLaunchOptions {
+            localconfig_path: localconfig.to_string_lossy().to_string(),
+            launch_options: Some(launch_options),
+            has_winhttp_override: true,
+            backup_path: Some(backup_path.to_string_lossy().to_string()),
+        });
+    }
+
+    Ok(updated)
+}
@@ -0,0 +1,431 @@
+use crate::fm26::{installation_paths, probe_fm26_dir};
+use crate::hash::sha256_bytes;
+use crate::models::{Fm26Installation, GameStore, InstallRegistry, RegisteredInstall};
+use crate::{bepinex, install_manifest};
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const REGISTRY_FILE: &str = "installs.json";
+
+/// Below this a registered folder no longer looks like FM26
+const MIN_VALID_CONFIDENCE: u8 = 50;
+
+/// Serializes read-modify-write cycles on the registry file
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+fn registry_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(REGISTRY_FILE))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+/// Loads the registry, empty if none has been saved yet
+pub fn load(app: &AppHandle) -> Result<InstallRegistry, String> {
+    let path = registry_path(app)?;
+    if !path.exists() {
+        return Ok(InstallRegistry::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read install registry: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse install registry: {}", e))
+}
+
+fn save(app: &AppHandle, registry: &InstallRegistry) -> Result<(), String> {
+    let path = registry_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(registry)
+        .map_err(|e| format!("Failed to serialize install registry: {}", e))?;
+
+    // Write then rename, so a crash never leaves a half-written registry
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, json).map_err(|e| format!("Failed to write install registry: {}", e))?;
+    fs::rename(&temp, &path).map_err(|e| format!("Failed to write install registry: {}", e))
+}
+
+/// Loads the registry, applies a change and saves it
+fn update<T>(app: &AppHandle, change: impl FnOnce(&mut InstallRegistry) -> Result<T, String>) -> Result<T, String> {
+    let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut registry = load(app)?;
+    let result = change(&mut registry)?;
+    save(app, &registry)?;
+    Ok(result)
+}
+
+/// Stable id of an installation, from its root path
+fn install_id(root: &Path) -> String {
+    sha256_bytes(root.to_string_lossy().as_bytes())[..12].to_string()
+}
+
+fn find<'a>(registry: &'a mut InstallRegistry, install_id: &str) -> Result<&'a mut RegisteredInstall, String> {
+    registry
+        .installs
+        .iter_mut()
+        .find(|i| i.id == install_id)
+        .ok_or_else(|| format!("Unknown installation: {}", install_id))
+}
+
+/// Re-checks a registered folder and refreshes what is known about its pack
+fn verify(install: &mut RegisteredInstall) {
+    let root = Path::new(&install.root_path);
+    install.valid = root.is_dir() && probe_fm26_dir(root, install.store).confidence >= MIN_VALID_CONFIDENCE;
+    if !install.valid {
+        return;
+    }
+
+    install.last_verified = Some(Local::now().to_rfc3339());
+    install.bepinex_version = bepinex::inspect_loader(root).core_version;
+    let manifest = install_manifest::read_manifest(root);
+    install.pack_sha256 = manifest.as_ref().map(|m| m.pack_sha256.clone());
+    install.pack_installed_at = manifest.map(|m| m.installed_at);
+}
+
+/// Re-checks every registered installation
+fn revalidate(registry: &mut InstallRegistry) {
+    registry.installs.iter_mut().for_each(verify);
+}
+
+/// Checks that a path stays inside an installation's root, following symlinks
+/// The path need not exist yet; its deepest existing ancestor is resolved instead.
+/// Returns the resolved path.
+pub fn ensure_inside(root: &Path, path: &Path) -> Result<PathBuf, String> {
+    let canonical_root = root
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", root.display(), e))?;
+
+    // symlink_metadata, unlike exists(), sees a dangling link, which then fails to resolve
+    let mut existing = path;
+    let mut missing = Vec::new();
+    while fs::symlink_metadata(existing).is_err() {
+        // No file name means the path ends in `..`
+        let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+            return Err(format!("{} is outside the game folder", path.display()));
+        };
+        missing.push(name);
+        existing = parent;
+    }
+
+    let mut resolved = existing
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", existing.display(), e))?;
+    resolved.extend(missing.into_iter().rev());
+
+    if !resolved.starts_with(&canonical_root) {
+        return Err(format!("{} is outside the game folder", path.display()));
+    }
+    Ok(resolved)
+}
+
+/// Checks a file of an installation before it is written
+/// A symlinked file or folder could otherwise redirect the write outside the
+/// game folder. Returns the resolved path to write to.
+pub fn install_file(install: &Fm26Installation, path: &Path) -> Result<PathBuf, String> {
+    ensure_inside(Path::new(&install.root_path), path)
+}
+
+/// Resolves a registered installation id to the installation's paths
+/// Every command working on a game folder goes through this, so the webview
+/// never supplies a path; each one is derived from the root and checked to stay inside it.
+pub fn resolve(app: &AppHandle, install_id: &str) -> Result<Fm26Installation, String> {
+    let registry = load(app)?;
+    let install = registry
+        .installs
+        .iter()
+        .find(|i| i.id == install_id)
+        .ok_or_else(|| format!("Unknown installation: {}", install_id))?;
+
+    let root = Path::new(&install.root_path);
+    if !root.is_dir() {
+        return Err(format!("Installation folder no longer exists: {}", install.root_path));
+    }
+
+    // A symlinked BepInEx or plugins folder could point anywhere
+    let paths = installation_paths(&install.id, root);
+    for path in [
+        &paths.bep_in_ex_path,
+        &paths.plugins_path,
+        &paths.custom_stadium_path,
+        &paths.audio_inject_path,
+        &paths.config_path,
+        &paths.log_path,
+    ] {
+        ensure_inside(root, Path::new(path))?;
+    }
+    Ok(paths)
+}
+
+/// Refreshes one installation's metadata after its pack changed (non-fatal)
+pub fn refresh(app: &AppHandle, install_id: &str) {
+    let _ = update(app, |registry| {
+        verify(find(registry, install_id)?);
+        Ok(())
+    });
+}
+
+/// Lists the saved installations and which one is the default
+#[tauri::command]
+pub fn list_installs(app_handle: AppHandle) -> Result<InstallRegistry, String> {
+    load(&app_handle)
+}
+
+/// Store of a new installation: a Steam app id wins over whatever the caller assumed
+fn resolve_store(probed: GameStore, requested: Option<GameStore>) -> GameStore {
+    match (probed, requested) {
+        (GameStore::Steam, _) => GameStore::Steam,
+        (_, Some(store)) => store,
+        (probed, None) => probed,
+    }
+}
+
+/// Adds an installation, or returns the registered one if its folder already is
+/// The first installation added becomes the default.
+fn add(
+    registry: &mut InstallRegistry,
+    root_path: &str,
+    label: Option<String>,
+    store: Option<GameStore>,
+) -> Result<RegisteredInstall, String> {
+    let root = Path::new(root_path);
+    if !root.is_dir() {
+        return Err(format!("Path is not a directory: {}", root_path));
+    }
+
+    let id = install_id(root);
+    if let Some(existing) = registry.installs.iter().find(|i| i.id == id) {
+        return Ok(existing.clone());
+    }
+
+    let probe = probe_fm26_dir(root, store.unwrap_or(GameStore::Manual));
+    let store = resolve_store(probe.store, store);
+
+    let mut install = RegisteredInstall {
+        id: id.clone(),
+        label: label
+            .filter(|l| !l.trim().is_empty())
+            .unwrap_or_else(|| format!("FM26 ({:?})", store)),
+        root_path: root_path.to_string(),
+        store,
+        added_at: Local::now().to_rfc3339(),
+        last_verified: None,
+        valid: false,
+        bepinex_version: None,
+        pack_sha256: None,
+        pack_installed_at: None,
+    };
+    verify(&mut install);
+
+    registry.installs.push(install.clone());
+    if registry.default_id.is_none() {
+        registry.default_id = Some(id);
+    }
+    Ok(install)
+}
+
+/// Removes an installation; if it was the default, the next one takes over
+fn remove(registry: &mut InstallRegistry, install_id: &str) -> Result<(), String> {
+    let before = registry.installs.len();
+    registry.installs.retain(|i| i.id != install_id);
+    if registry.installs.len() == before {
+        return Err(format!("Unknown installation: {}", install_id));
+    }
+
+    if registry.default_id.as_deref() == Some(install_id) {
+        registry.default_id = registry.installs.first().map(|i| i.id.clone());
+    }
+    Ok(())
+}
+
+fn set_label(registry: &mut InstallRegistry, install_id: &str, label: &str) -> Result<(), String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("Label cannot be empty".to_string());
+    }
+    find(registry, install_id)?.label = label.to_string();
+    Ok(())
+}
+
+fn set_default(registry: &mut InstallRegistry, install_id: &str) -> Result<(), String> {
+    find(registry, install_id)?;
+    registry.default_id = Some(install_id.to_string());
+    Ok(())
+}
+
+/// Saves an installation, or returns the saved one if its folder is already registered
+/// The first installation saved becomes the default.
+#[tauri::command]
+pub fn add_install(
+    app_handle: AppHandle,
+    root_path: String,
+    label: Option<String>,
+    store: Option<GameStore>,
+) -> Result<RegisteredInstall, String> {
+    update(&app_handle, |registry| add(registry, &root_path, label, store))
+}
+
+/// Forgets an installation; the game folder itself is left alone
+#[tauri::command]
+pub fn remove_install(app_handle: AppHandle, install_id: String) -> Result<(), String> {
+    update(&app_handle, |registry| remove(registry, &install_id))
+}
+
+/// Renames an installation
+#[tauri::command]
+pub fn label_install(app_handle: AppHandle, install_id: String, label: String) -> Result<(), String> {
+    update(&app_handle, |registry| set_label(registry, &install_id, &label))
+}
+
+/// Makes an installation the one the tabs open with
+#[tauri::command]
+pub fn set_default_install(app_handle: AppHandle, install_id: String) -> Result<(), String> {
+    update(&app_handle, |registry| set_default(registry, &install_id))
+}
+
+/// Gets the paths of the default installation, if one is saved
+#[tauri::command]
+pub fn get_default_install(app_handle: AppHandle) -> Result<Option<Fm26Installation>, String> {
+    let Some(default_id) = load(&app_handle)?.default_id else {
+        return Ok(None);
+    };
+    resolve(&app_handle, &default_id).map(Some)
+}
+
+/// Gets the paths of a saved installation
+#[tauri::command]
+pub fn get_install(app_handle: AppHandle, install_id: String) -> Result<Fm26Installation, String> {
+    resolve(&app_handle, &install_id)
+}
+
+/// Re-checks every saved installation; also run on startup
+#[tauri::command]
+pub fn revalidate_installs(app_handle: AppHandle) -> Result<InstallRegistry, String> {
+    update(&app_handle, |registry| {
+        revalidate(registry);
+        Ok(registry.clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn resolves_missing_paths_inside_the_root() {
+        let root = TempDir::new("registry_missing");
+
+        let resolved = ensure_inside(&root, &root.join("BepInEx").join("config").join("a.cfg")).unwrap();
+        assert!(resolved.ends_with("BepInEx/config/a.cfg"));
+        assert!(ensure_inside(&root, &root.join("..").join("elsewhere")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_leading_outside() {
+        let base = TempDir::new("registry_links");
+        let root = base.join("game");
+        let outside = base.join("outside.txt");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(&outside, "").unwrap();
+
+        // A symlinked file redirects writes to its target
+        std::os::unix::fs::symlink(&outside, root.join("team_mappings.txt")).unwrap();
+        assert!(ensure_inside(&root, &root.join("team_mappings.txt")).is_err());
+
+        // A dangling link exists even though its target doesn't
+        std::os::unix::fs::symlink(base.join("missing"), root.join("dangling.cfg")).unwrap();
+        assert!(ensure_inside(&root, &root.join("dangling.cfg")).is_err());
+    }
+
+    /// Two empty folders, which register as invalid installs
+    fn two_folders(name: &str) -> (TempDir, String, String) {
+        let base = TempDir::new(name);
+        let (a, b) = (base.join("a"), base.join("b"));
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+        (base, a.to_string_lossy().to_string(), b.to_string_lossy().to_string())
+    }
+
+    #[test]
+    fn makes_the_first_install_the_default() {
+        let (_base, a, b) = two_folders("registry_default");
+        let mut registry = InstallRegistry::default();
+
+        let first = add(&mut registry, &a, None, None).unwrap();
+        let second = add(&mut registry, &b, Some("Laptop".to_string()), Some(GameStore::Epic)).unwrap();
+        assert_eq!(registry.default_id.as_deref(), Some(first.id.as_str()));
+        assert_eq!(first.label, "FM26 (Manual)");
+        assert_eq!((second.label.as_str(), second.store), ("Laptop", GameStore::Epic));
+        assert!(!first.valid);
+
+        set_default(&mut registry, &second.id).unwrap();
+        assert_eq!(registry.default_id.as_deref(), Some(second.id.as_str()));
+        assert!(set_default(&mut registry, "missing").is_err());
+        assert!(add(&mut registry, &format!("{}/nope", a), None, None).is_err());
+    }
+
+    #[test]
+    fn returns_the_registered_install_for_a_known_folder() {
+        let (_base, a, _) = two_folders("registry_duplicate");
+        let mut registry = InstallRegistry::default();
+
+        let first = add(&mut registry, &a, Some("Desktop".to_string()), None).unwrap();
+        let again = add(&mut registry, &a, Some("Other".to_string()), Some(GameStore::Epic)).unwrap();
+        assert_eq!(again.id, first.id);
+        assert_eq!(again.label, "Desktop");
+        assert_eq!(registry.installs.len(), 1);
+    }
+
+    #[test]
+    fn promotes_the_next_install_when_the_default_is_removed() {
+        let (_base, a, b) = two_folders("registry_remove");
+        let mut registry = InstallRegistry::default();
+        let first = add(&mut registry, &a, None, None).unwrap();
+        let second = add(&mut registry, &b, None, None).unwrap();
+
+        remove(&mut registry, &first.id).unwrap();
+        assert_eq!(registry.default_id.as_deref(), Some(second.id.as_str()));
+        assert!(remove(&mut registry, &first.id).is_err());
+
+        remove(&mut registry, &second.id).unwrap();
+        assert_eq!(registry.default_id, None);
+    }
+
+    #[test]
+    fn trims_labels_and_rejects_empty_ones() {
+        let (_base, a, _) = two_folders("registry_label");
+        let mut registry = InstallRegistry::default();
+        let install = add(&mut registry, &a, None, None).unwrap();
+
+        set_label(&mut registry, &install.id, "  Steam Deck ").unwrap();
+        assert_eq!(registry.installs[0].label, "Steam Deck");
+        assert!(set_label(&mut registry, &install.id, "   ").is_err());
+        assert!(set_label(&mut registry, "missing", "x").is_err());
+    }
+
+    #[test]
+    fn lets_a_steam_app_id_override_the_store() {
+        assert_eq!(resolve_store(GameStore::Steam, Some(GameStore::Epic)), GameStore::Steam);
+        assert_eq!(resolve_store(GameStore::Manual, Some(GameStore::Epic)), GameStore::Epic);
+        assert_eq!(resolve_store(GameStore::Epic, None), GameStore::Epic);
+    }
+
+    #[test]
+    fn marks_vanished_folders_invalid_on_revalidation() {
+        let (_base, a, _) = two_folders("registry_revalidate");
+        let mut registry = InstallRegistry::default();
+        add(&mut registry, &a, None, None).unwrap();
+        registry.installs[0].valid = true;
+
+        fs::remove_dir_all(&a).unwrap();
+        revalidate(&mut registry);
+        assert!(!registry.installs[0].valid);
+    }
+}
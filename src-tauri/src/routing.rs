@@ -0,0 +1,299 @@
+use crate::archive::{Archive, ArchiveEntry};
+use crate::models::{ArchiveViolation, ArchiveViolationKind, Fm26Installation, RouteDestination, RoutedDestination};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Mapping files merged into the user's copy instead of replacing it
+pub const TEAM_MAPPINGS: &str = "team_mappings.txt";
+pub const AUDIO_MAPPINGS: &str = "AudioMappings.txt";
+
+/// The loader's own config, which a stadium pack never replaces
+const LOADER_CONFIG: &str = "BepInEx.cfg";
+
+/// Extensions the AudioInject plugin plays
+const AUDIO_EXTENSIONS: [&str; 3] = ["wav", "ogg", "mp3"];
+
+/// Where one archive entry goes, relative to the game root
+pub struct Route {
+    pub destination: RouteDestination,
+    pub relative: PathBuf,
+    /// A mapping or config file, merged into the user's copy rather than overwritten
+    pub merge: bool,
+}
+
+/// Sorts the contents of a mixed stadium pack into plugin folders
+pub struct Router {
+    strip_prefix: String,
+    dirs: Vec<(RouteDestination, PathBuf)>,
+}
+
+fn has_extension(name: &str, extensions: &[&str]) -> bool {
+    Path::new(name)
+        .extension()
+        .is_some_and(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+/// Components after the last folder with the given name, if there is one
+fn after_folder<'a>(components: &[&'a str], folder: &str) -> Option<Vec<&'a str>> {
+    let dirs = &components[..components.len() - 1];
+    let position = dirs.iter().rposition(|c| c.eq_ignore_ascii_case(folder))?;
+    Some(components[position + 1..].to_vec())
+}
+
+impl Router {
+    /// Resolves the destination folders of an install, relative to its root
+    pub fn new(install: &Fm26Installation, strip_prefix: &str) -> Result<Self, String> {
+        let root = Path::new(&install.root_path);
+        let folders = [
+            (RouteDestination::CustomStadium, PathBuf::from(&install.custom_stadium_path)),
+            (RouteDestination::StadiumInjection, Path::new(&install.plugins_path).join("StadiumInjection")),
+            (RouteDestination::AudioInject, PathBuf::from(&install.audio_inject_path)),
+            (RouteDestination::Config, PathBuf::from(&install.config_path)),
+        ];
+
+        let dirs = folders
+            .into_iter()
+            .map(|(destination, dir)| {
+                dir.strip_prefix(root)
+                    .map(|relative| (destination, relative.to_path_buf()))
+                    .map_err(|_| format!("{} is not inside the game folder", dir.display()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            strip_prefix: strip_prefix.to_string(),
+            dirs,
+        })
+    }
+
+    /// Destination folder, relative to the game root
+    pub fn dir(&self, destination: RouteDestination) -> &Path {
+        self.dirs
+            .iter()
+            .find(|(d, _)| *d == destination)
+            .map(|(_, dir)| dir.as_path())
+            .unwrap_or(Path::new(""))
+    }
+
+    fn route_to(&self, destination: RouteDestination, parts: &[&str], merge: bool) -> Option<Route> {
+        let mut relative = self.dir(destination).to_path_buf();
+        relative.extend(parts);
+        Some(Route {
+            destination,
+            relative,
+            merge,
+        })
+    }
+
+    /// Classifies an entry by name and type; None for content no plugin uses
+    pub fn route(&self, entry: &ArchiveEntry) -> Option<Route> {
+        if entry.is_dir {
+            return None;
+        }
+        let path = entry.path.as_ref()?.to_string_lossy().replace('\\', "/");
+        let components: Vec<&str> = path.split('/').collect();
+        let file_name = *components.last()?;
+
+        if file_name.eq_ignore_ascii_case(TEAM_MAPPINGS) {
+            return self.route_to(RouteDestination::StadiumInjection, &[TEAM_MAPPINGS], true);
+        }
+        if file_name.eq_ignore_ascii_case(AUDIO_MAPPINGS) {
+            return self.route_to(RouteDestination::AudioInject, &[AUDIO_MAPPINGS], true);
+        }
+        if file_name.eq_ignore_ascii_case(LOADER_CONFIG) {
+            return None;
+        }
+        if has_extension(file_name, &["cfg"]) {
+            return self.route_to(RouteDestination::Config, &[file_name], true);
+        }
+
+        // StadiumInjection only looks directly in CustomStadium/, so bundles are
+        // flattened; those outside the pack root (the layout prefix) are skipped
+        if after_folder(&components, "CustomStadium").is_some() || has_extension(file_name, &["bundle"]) {
+            if !path.starts_with(&self.strip_prefix) {
+                return None;
+            }
+            return self.route_to(RouteDestination::CustomStadium, &[file_name], false);
+        }
+
+        // Audio sets are folders of sounds, one per team
+        if let Some(rest) = after_folder(&components, "AudioInject") {
+            return self.route_to(RouteDestination::AudioInject, &rest, false);
+        }
+        if has_extension(file_name, &AUDIO_EXTENSIONS) && components.len() >= 2 {
+            let folder = components[components.len() - 2];
+            return self.route_to(RouteDestination::AudioInject, &[folder, file_name], false);
+        }
+
+        None
+    }
+
+    /// Entries routed to the same file, which would overwrite each other
+    /// Mapping and config files are merged, so only other files count; paths compare case-insensitively.
+    pub fn collisions(&self, archive: &Archive) -> Vec<ArchiveViolation> {
+        let mut violations = Vec::new();
+        // Lowercased destination -> first entry routed there
+        let mut seen: HashMap<String, String> = HashMap::new();
+
+        for entry in archive.entries() {
+            let Some(route) = self.route(entry).filter(|route| !route.merge) else {
+                continue;
+            };
+            let destination = route.relative.to_string_lossy().replace('\\', "/");
+
+            match seen.get(&destination.to_lowercase()) {
+                // The same name twice is already reported by the archive checks
+                Some(previous) if previous.eq_ignore_ascii_case(&entry.name) => {}
+                Some(previous) => violations.push(ArchiveViolation {
+                    kind: ArchiveViolationKind::RouteCollision,
+                    entry: entry.name.clone(),
+                    message: format!("{} and {} would both be installed as {}", previous, entry.name, destination),
+                }),
+                None => {
+                    seen.insert(destination.to_lowercase(), entry.name.clone());
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// Summary rows for every destination, in a fixed order
+    pub fn summaries(&self, root: &Path) -> Vec<RoutedDestination> {
+        self.dirs
+            .iter()
+            .map(|(destination, dir)| RoutedDestination {
+                destination: *destination,
+                path: root.join(dir).to_string_lossy().to_string(),
+                files: 0,
+                mappings_added: 0,
+                mappings_kept: 0,
+            })
+            .collect()
+    }
+}
+
+/// Key of a team_mappings.txt line: the team id before ':' or '='
+pub fn team_mapping_key(line: &str) -> Option<String> {
+    let (key, _) = line.split_once('=').or_else(|| line.split_once(':'))?;
+    key.trim().parse::<i32>().ok().map(|id| id.to_string())
+}
+
+/// Key of an AudioMappings.txt line: the team key before '='
+pub fn audio_mapping_key(line: &str) -> Option<String> {
+    line.split_once('=').map(|(key, _)| key.trim().to_string())
+}
+
+/// Adds a pack's mappings to the user's file, keeping the user's line for any key both have
+/// Returns the merged text and the number of mappings added and kept.
+pub fn merge_mappings(existing: &str, incoming: &str, key_of: fn(&str) -> Option<String>) -> (String, u32, u32) {
+    let is_mapping = |line: &&str| {
+        let line = line.trim();
+        !line.is_empty() && !line.starts_with('#') && !line.starts_with("//")
+    };
+
+    let mut keys: HashSet<String> = existing.lines().filter(is_mapping).filter_map(key_of).collect();
+    let mut merged = existing.to_string();
+    let (mut added, mut kept) = (0, 0);
+
+    if !merged.is_empty() && !merged.ends_with('\n') {
+        merged.push('\n');
+    }
+
+    for line in incoming.lines().filter(is_mapping) {
+        let Some(key) = key_of(line) else {
+            continue;
+        };
+        if !keys.insert(key) {
+            kept += 1;
+            continue;
+        }
+        merged.push_str(line.trim());
+        merged.push('\n');
+        added += 1;
+    }
+
+    (merged, added, kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use crate::fm26::installation_paths;
+    use std::fs;
+    use std::io::Write;
+
+    /// Writes a zip holding a small file under each name
+    fn write_zip(path: &Path, names: &[&str]) {
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        for name in names {
+            writer.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(name.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn open(name: &str, names: &[&str]) -> (Archive, TempDir) {
+        let dir = TempDir::new(&format!("routing_{}", name));
+        let path = dir.join("pack.zip");
+        write_zip(&path, names);
+        (Archive::open(&path).unwrap(), dir)
+    }
+
+    fn routed(router: &Router, archive: &Archive) -> Vec<String> {
+        archive
+            .entries()
+            .iter()
+            .filter_map(|entry| router.route(entry))
+            .map(|route| route.relative.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn flattens_bundles_under_the_pack_root() {
+        let install = installation_paths("test", Path::new("/games/fm26"));
+        let (archive, _dir) = open(
+            "flatten",
+            &["Pack/CustomStadium/Wembley/wembley.bundle", "Pack/CustomStadium/anfield.bundle", "Extras/old.bundle"],
+        );
+
+        let router = Router::new(&install, "Pack/CustomStadium/").unwrap();
+        assert_eq!(
+            routed(&router, &archive),
+            ["BepInEx/plugins/CustomStadium/wembley.bundle", "BepInEx/plugins/CustomStadium/anfield.bundle"]
+        );
+    }
+
+    #[test]
+    fn reports_entries_routed_to_the_same_file() {
+        let install = installation_paths("test", Path::new("/games/fm26"));
+        let (archive, _dir) = open(
+            "collisions",
+            &[
+                "PackA/x.bundle",
+                "PackB/X.bundle",
+                "PackA/x.cfg",
+                "PackB/x.cfg",
+                "PackA/team_mappings.txt",
+                "PackB/team_mappings.txt",
+            ],
+        );
+
+        let collisions = Router::new(&install, "").unwrap().collisions(&archive);
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].entry, "PackB/X.bundle");
+        assert!(collisions[0].message.contains("PackA/x.bundle"), "{}", collisions[0].message);
+    }
+
+    #[test]
+    fn merges_configs_and_leaves_the_loader_config_alone() {
+        let install = installation_paths("test", Path::new("/games/fm26"));
+        let (archive, _dir) = open("configs", &["Pack/com.bassy.test.cfg", "Pack/BepInEx/config/BepInEx.cfg"]);
+
+        let router = Router::new(&install, "").unwrap();
+        let routes: Vec<_> = archive.entries().iter().map(|entry| router.route(entry)).collect();
+        assert!(routes[0].as_ref().is_some_and(|route| route.merge && route.destination == RouteDestination::Config));
+        assert!(routes[1].is_none());
+    }
+}
@@ -1,4 +1,6 @@
-use crate::models::{BundleInfo, Fm26Installation, TeamMapping};
+use crate::models::{BundleInfo, NameError, NameField, TeamMapping};
+use crate::names::{self, NameChecks};
+use crate::registry;
 use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
@@ -6,7 +8,8 @@ use chrono::{DateTime, Utc};
 
 /// Lists all .bundle files in the CustomStadium folder
 #[tauri::command]
-pub fn list_bundles(install: Fm26Installation) -> Result<Vec<BundleInfo>, String> {
+pub fn list_bundles(app_handle: tauri::AppHandle, install_id: String) -> Result<Vec<BundleInfo>, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let custom_stadium_path = Path::new(&install.custom_stadium_path);
 
     if !custom_stadium_path.exists() {
@@ -56,7 +59,8 @@ pub fn list_bundles(install: Fm26Installation) -> Result<Vec<BundleInfo>, String
 
 /// Reads team mappings from team_mappings.txt in the StadiumInjection plugin folder
 #[tauri::command]
-pub fn read_team_mappings(install: Fm26Installation) -> Result<Vec<TeamMapping>, String> {
+pub fn read_team_mappings(app_handle: tauri::AppHandle, install_id: String) -> Result<Vec<TeamMapping>, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
     let stadium_injection_path = Path::new(&install.plugins_path).join("StadiumInjection");
     let mappings_path = stadium_injection_path.join("team_mappings.txt");
 
@@ -67,6 +71,11 @@ pub fn read_team_mappings(install: Fm26Installation) -> Result<Vec<TeamMapping>,
     let content = fs::read_to_string(&mappings_path)
         .map_err(|e| format!("Failed to read team_mappings.txt: {}", e))?;
 
+    Ok(parse_team_mappings(&content))
+}
+
+/// Parses team_mappings.txt, skipping comments and malformed lines
+pub fn parse_team_mappings(content: &str) -> Vec<TeamMapping> {
     let mut mappings = Vec::new();
 
     for line in content.lines() {
@@ -93,20 +102,35 @@ pub fn read_team_mappings(install: Fm26Installation) -> Result<Vec<TeamMapping>,
         }
     }
 
-    Ok(mappings)
+    mappings
 }
 
 /// Writes team mappings to team_mappings.txt in the StadiumInjection plugin folder with validation
 #[tauri::command]
 pub fn write_team_mappings(
-    install: Fm26Installation,
+    app_handle: tauri::AppHandle,
+    install_id: String,
     mappings: Vec<TeamMapping>,
-) -> Result<(), String> {
+) -> Result<(), NameError> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+
+    // Validate: bundle files must be plain names inside CustomStadium
+    let mut checks = NameChecks::default();
+    for (row, mapping) in mappings.iter().enumerate() {
+        checks.check(
+            NameField::BundleFile,
+            Some(row as u32),
+            &mapping.bundle_file,
+            names::check_component(&mapping.bundle_file),
+        );
+    }
+    checks.finish()?;
+
     // Validate: check for duplicate team IDs
     let mut seen_ids = HashSet::new();
     for mapping in &mappings {
         if !seen_ids.insert(mapping.team_id) {
-            return Err(format!("Duplicate team ID: {}", mapping.team_id));
+            return Err(format!("Duplicate team ID: {}", mapping.team_id).into());
         }
     }
 
@@ -118,7 +142,8 @@ pub fn write_team_mappings(
             return Err(format!(
                 "Bundle file does not exist: {} (for team {})",
                 mapping.bundle_file, mapping.team_id
-            ));
+            )
+            .into());
         }
     }
 
@@ -141,7 +166,7 @@ pub fn write_team_mappings(
             .map_err(|e| format!("Failed to create StadiumInjection directory: {}", e))?;
     }
 
-    let mappings_path = stadium_injection_path.join("team_mappings.txt");
+    let mappings_path = registry::install_file(&install, &stadium_injection_path.join("team_mappings.txt"))?;
     fs::write(&mappings_path, content)
         .map_err(|e| format!("Failed to write team_mappings.txt: {}", e))?;
 
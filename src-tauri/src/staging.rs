@@ -0,0 +1,281 @@
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+/// Prefix of the temporary install folders created in the game root
+pub const STAGING_PREFIX: &str = ".bst_install_";
+
+/// (root, workspace) of every transaction in progress, so two installs never
+/// move aside the same folders
+static ACTIVE: Mutex<Vec<(PathBuf, PathBuf)>> = Mutex::new(Vec::new());
+
+/// Distinguishes workspaces created in the same second
+static NEXT_WORKSPACE: AtomicU32 = AtomicU32::new(0);
+
+fn active() -> std::sync::MutexGuard<'static, Vec<(PathBuf, PathBuf)>> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Holds a root for one transaction and releases it when dropped
+struct RootLock {
+    root: PathBuf,
+}
+
+impl RootLock {
+    fn acquire(root: &Path, workspace: &Path) -> Result<Self, String> {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut active = active();
+        if active.iter().any(|(locked, _)| *locked == root) {
+            return Err(format!("Another install is already running in {}", root.display()));
+        }
+
+        active.push((root.clone(), workspace.to_path_buf()));
+        Ok(Self { root })
+    }
+}
+
+impl Drop for RootLock {
+    fn drop(&mut self) {
+        active().retain(|(locked, _)| *locked != self.root);
+    }
+}
+
+/// Workspaces in a root left by installs that were interrupted or couldn't roll back
+/// They may hold the only copy of files an install moved aside.
+pub fn leftover_workspaces(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let in_use: Vec<_> = active()
+        .iter()
+        .filter_map(|(_, workspace)| workspace.file_name().map(|name| name.to_os_string()))
+        .collect();
+
+    let mut leftovers: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(STAGING_PREFIX))
+        .filter(|entry| !in_use.contains(&entry.file_name()))
+        .map(|entry| entry.path())
+        .collect();
+    leftovers.sort();
+    leftovers
+}
+
+/// A change made to the target folder, undone in reverse on rollback
+#[derive(Debug)]
+enum Op {
+    CreatedDir(PathBuf),
+    MovedAside { original: PathBuf, saved: PathBuf },
+    Placed(PathBuf),
+}
+
+/// An install that extracts into a staging folder and is then swapped into place
+///
+/// Layout: `<workspace>/files` holds the staged tree, `<workspace>/rollback`
+/// holds whatever the swap replaced. The workspace lives in the game root so
+/// every move is a same-volume rename.
+pub struct Transaction {
+    workspace: PathBuf,
+    target: PathBuf,
+    ops: Vec<Op>,
+    _lock: RootLock,
+}
+
+impl Transaction {
+    /// Creates a fresh workspace in `root` for an install into `target`
+    /// Fails if another transaction is running in the same root; existing
+    /// workspaces are never reused or removed.
+    pub fn begin(root: &Path, target: &Path) -> Result<Self, String> {
+        let workspace = root.join(format!(
+            "{}{}_{}_{}",
+            STAGING_PREFIX,
+            Local::now().format("%Y%m%d_%H%M%S"),
+            std::process::id(),
+            NEXT_WORKSPACE.fetch_add(1, Ordering::Relaxed)
+        ));
+        let lock = RootLock::acquire(root, &workspace)?;
+
+        fs::create_dir(&workspace)
+            .and_then(|_| fs::create_dir(workspace.join("files")))
+            .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+        Ok(Self {
+            workspace,
+            target: target.to_path_buf(),
+            ops: Vec::new(),
+            _lock: lock,
+        })
+    }
+
+    /// Folder to extract into before the swap
+    pub fn staging_dir(&self) -> PathBuf {
+        self.workspace.join("files")
+    }
+
+    fn rollback_dir(&self) -> PathBuf {
+        self.workspace.join("rollback")
+    }
+
+    /// Where an entry moved aside by this transaction is kept until commit
+    pub fn replaced_path(&self, relative: &Path) -> PathBuf {
+        self.rollback_dir().join(relative)
+    }
+
+    /// Moves an existing entry of the target out of the way, keeping it for rollback
+    pub fn move_aside(&mut self, relative: &Path) -> Result<(), String> {
+        let original = self.target.join(relative);
+        if !original.exists() {
+            return Ok(());
+        }
+
+        let saved = self.rollback_dir().join(relative);
+        if let Some(parent) = saved.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create rollback directory: {}", e))?;
+        }
+
+        fs::rename(&original, &saved)
+            .map_err(|e| format!("Failed to move {} aside: {}", original.display(), e))?;
+        self.ops.push(Op::MovedAside { original, saved });
+
+        Ok(())
+    }
+
+    /// Creates a folder in the target, and any missing parents
+    fn ensure_dir(&mut self, dir: &Path) -> Result<(), String> {
+        if dir.is_dir() {
+            return Ok(());
+        }
+        if let Some(parent) = dir.parent() {
+            self.ensure_dir(parent)?;
+        }
+
+        fs::create_dir(dir)
+            .map_err(|e| format!("Failed to create directory {}: {}", dir.display(), e))?;
+        self.ops.push(Op::CreatedDir(dir.to_path_buf()));
+
+        Ok(())
+    }
+
+    fn place_dir(&mut self, staged_dir: &Path, relative: &Path, placed: &mut Vec<PathBuf>) -> Result<(), String> {
+        let mut entries: Vec<_> = fs::read_dir(staged_dir)
+            .map_err(|e| format!("Failed to read staging directory: {}", e))?
+            .flatten()
+            .map(|entry| entry.path())
+            .collect();
+        entries.sort();
+
+        for staged in entries {
+            let Some(name) = staged.file_name() else {
+                continue;
+            };
+            let relative = relative.join(name);
+            let dest = self.target.join(&relative);
+
+            if staged.is_dir() {
+                if dest.exists() && !dest.is_dir() {
+                    return Err(format!("{} is a file but the pack has a folder there", dest.display()));
+                }
+                self.ensure_dir(&dest)?;
+                self.place_dir(&staged, &relative, placed)?;
+                continue;
+            }
+
+            if dest.is_dir() {
+                return Err(format!("{} is a folder but the pack has a file there", dest.display()));
+            }
+            if let Some(parent) = dest.parent() {
+                self.ensure_dir(parent)?;
+            }
+            self.move_aside(&relative)?;
+
+            fs::rename(&staged, &dest)
+                .map_err(|e| format!("Failed to place {}: {}", dest.display(), e))?;
+            self.ops.push(Op::Placed(dest));
+            placed.push(relative);
+        }
+
+        Ok(())
+    }
+
+    /// Moves every staged file into the target, replacing what was there
+    /// Returns the placed files, relative to the target.
+    pub fn place_all(&mut self) -> Result<Vec<PathBuf>, String> {
+        let mut placed = Vec::new();
+        let staging = self.staging_dir();
+        self.place_dir(&staging, Path::new(""), &mut placed)?;
+        Ok(placed)
+    }
+
+    /// Undoes every change to the target and removes the workspace
+    /// If something can't be put back, the workspace is left so nothing is lost.
+    pub fn rollback(self) {
+        let mut restored = true;
+
+        for op in self.ops.iter().rev() {
+            match op {
+                Op::Placed(path) => {
+                    let _ = fs::remove_file(path);
+                }
+                Op::CreatedDir(path) => {
+                    // Only this install created it, so anything inside is ours
+                    let _ = fs::remove_dir_all(path);
+                }
+                Op::MovedAside { original, saved } => {
+                    restored &= fs::rename(saved, original).is_ok();
+                }
+            }
+        }
+
+        if restored {
+            let _ = fs::remove_dir_all(&self.workspace);
+        }
+    }
+
+    /// Keeps the new files and removes the workspace
+    /// `keep` can take what was moved aside first; if it fails the install is rolled back.
+    pub fn commit(self, keep: impl FnOnce(&Path) -> Result<(), String>) -> Result<(), String> {
+        if let Err(e) = keep(&self.rollback_dir()) {
+            self.rollback();
+            return Err(e);
+        }
+
+        let _ = fs::remove_dir_all(&self.workspace);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn allows_one_transaction_per_root() {
+        let root = TempDir::new("staging_lock");
+        let first = Transaction::begin(&root, &root).unwrap();
+        assert!(Transaction::begin(&root, &root).is_err());
+
+        first.rollback();
+        let second = Transaction::begin(&root, &root).unwrap();
+        second.commit(|_| Ok(())).unwrap();
+    }
+
+    #[test]
+    fn reports_workspaces_left_behind() {
+        let root = TempDir::new("staging_leftovers");
+        let kept = root.join(format!("{}20260101_120000", STAGING_PREFIX));
+        fs::create_dir_all(kept.join("rollback")).unwrap();
+
+        let transaction = Transaction::begin(&root, &root).unwrap();
+        assert_ne!(transaction.workspace, kept);
+        assert_eq!(leftover_workspaces(&root), std::slice::from_ref(&kept));
+
+        transaction.rollback();
+        assert!(kept.is_dir());
+        assert_eq!(leftover_workspaces(&root), [kept]);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::vdf::{self, Vdf};
+use std::path::{Path, PathBuf};
+
+/// Steam app id for Football Manager 2026
+pub const FM26_STEAM_APP_ID: &str = "3551340";
+
+/// An app install resolved through a Steam library's appmanifest
+#[derive(Debug, Clone)]
+pub struct SteamAppInstall {
+    pub steam_root: PathBuf,
+    pub library_path: PathBuf,
+    pub manifest_path: PathBuf,
+    pub install_path: PathBuf,
+}
+
+/// Lists the locations a Steam client may live in for the current platform
+fn steam_root_candidates() -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(path) = read_steam_path_from_registry() {
+            candidates.push(path);
+        }
+        for var in ["ProgramFiles(x86)", "ProgramFiles"] {
+            if let Ok(dir) = std::env::var(var) {
+                candidates.push(PathBuf::from(dir).join("Steam"));
+            }
+        }
+        candidates.push(PathBuf::from(r"C:\Program Files (x86)\Steam"));
+        candidates.push(PathBuf::from(r"C:\Program Files\Steam"));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(home) = dirs::home_dir() {
+            candidates.push(home.join("Library/Application Support/Steam"));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(home) = dirs::home_dir() {
+            // Native packages
+            candidates.push(home.join(".steam/steam"));
+            candidates.push(home.join(".steam/root"));
+            candidates.push(home.join(".local/share/Steam"));
+            // Flatpak
+            candidates.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+            candidates.push(home.join(".var/app/com.valvesoftware.Steam/.steam/steam"));
+            // Snap
+            candidates.push(home.join("snap/steam/common/.local/share/Steam"));
+            candidates.push(home.join("snap/steam/common/.steam/steam"));
+        }
+    }
+
+    candidates
+}
+
+/// Reads SteamPath from HKCU\Software\Valve\Steam
+#[cfg(target_os = "windows")]
+fn read_steam_path_from_registry() -> Option<PathBuf> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let output = std::process::Command::new("reg")
+        .args(["query", r"HKCU\Software\Valve\Steam", "/v", "SteamPath"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .lines()
+        .find_map(|line| line.split_once("REG_SZ"))
+        .map(|(_, value)| PathBuf::from(value.trim().replace('/', "\\")))
+}
+
+/// Finds every Steam client directory present on this machine
+pub fn find_steam_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+
+    for candidate in steam_root_candidates() {
+        if candidate.join("steamapps").is_dir() {
+            push_unique(&mut roots, candidate);
+        }
+    }
+
+    roots
+}
+
+/// Lists all library folders registered with a Steam client, including the client itself
+pub fn find_library_folders(steam_root: &Path) -> Vec<PathBuf> {
+    let mut folders = vec![steam_root.to_path_buf()];
+
+    let vdf_paths = [
+        steam_root.join("steamapps").join("libraryfolders.vdf"),
+        steam_root.join("config").join("libraryfolders.vdf"),
+    ];
+
+    for vdf_path in vdf_paths {
+        let Ok(doc) = vdf::parse_file(&vdf_path) else {
+            continue;
+        };
+
+        let Some(root) = doc.get("libraryfolders") else {
+            continue;
+        };
+
+        for (key, value) in root.entries() {
+            // Only numbered entries are libraries; skip "contentstatsid" and friends
+            if key.parse::<u32>().is_err() {
+                continue;
+            }
+
+            // Newer format nests the path in an object, older format stores it directly
+            let path = match value {
+                Vdf::Object(_) => value.get("path").and_then(Vdf::as_str),
+                Vdf::Value(path) => Some(path.as_str()),
+            };
+
+            if let Some(path) = path {
+                push_unique(&mut folders, PathBuf::from(path));
+            }
+        }
+    }
+
+    folders
+}
+
+/// Lists every library folder across all Steam clients on this machine
+pub fn find_all_library_folders() -> Vec<(PathBuf, PathBuf)> {
+    let mut libraries: Vec<(PathBuf, PathBuf)> = Vec::new();
+
+    for steam_root in find_steam_roots() {
+        for library in find_library_folders(&steam_root) {
+            if !libraries.iter().any(|(_, existing)| same_path(existing, &library)) {
+                libraries.push((steam_root.clone(), library));
+            }
+        }
+    }
+
+    libraries
+}
+
+/// Finds installs of a Steam app by reading appmanifest_<appid>.acf in every library
+pub fn find_app_installs(app_id: &str) -> Vec<SteamAppInstall> {
+    find_all_library_folders()
+        .into_iter()
+        .filter_map(|(steam_root, library)| read_app_manifest(&steam_root, &library, app_id))
+        .collect()
+}
+
+/// Resolves an app's install directory from its appmanifest in one library
+fn read_app_manifest(steam_root: &Path, library: &Path, app_id: &str) -> Option<SteamAppInstall> {
+    let manifest_path = library
+        .join("steamapps")
+        .join(format!("appmanifest_{}.acf", app_id));
+
+    let doc = vdf::parse_file(&manifest_path).ok()?;
+    let install_dir = doc.get_path(&["AppState", "installdir"])?.as_str()?;
+
+    let install_path = library.join("steamapps").join("common").join(install_dir);
+    if !install_path.is_dir() {
+        return None;
+    }
+
+    Some(SteamAppInstall {
+        steam_root: steam_root.to_path_buf(),
+        library_path: library.to_path_buf(),
+        manifest_path,
+        install_path,
+    })
+}
+
+/// Works out FM26's Steam app id for a game folder, if Steam owns it
+///
+/// Checks `steam_appid.txt` first, then whether the folder sits in a library's
+/// `steamapps/common` and is the `installdir` of FM26's appmanifest there.
+pub fn fm26_app_id_for_install(install_path: &Path) -> Option<String> {
+    if let Ok(content) = std::fs::read_to_string(install_path.join("steam_appid.txt")) {
+        let app_id = content.trim();
+        if app_id == FM26_STEAM_APP_ID {
+            return Some(app_id.to_string());
+        }
+    }
+
+    fm26_manifest_for_install(install_path).map(|_| FM26_STEAM_APP_ID.to_string())
+}
+
+/// Reads the Steam build id FM26's appmanifest records for a game folder
+pub fn fm26_build_id_for_install(install_path: &Path) -> Option<String> {
+    let doc = fm26_manifest_for_install(install_path)?;
+    doc.get_path(&["AppState", "buildid"])?
+        .as_str()
+        .map(|s| s.to_string())
+}
+
+/// Loads FM26's appmanifest from the library containing a game folder, if it
+/// sits in `steamapps/common` and is the manifest's `installdir`
+fn fm26_manifest_for_install(install_path: &Path) -> Option<Vdf> {
+    let common = install_path.parent()?;
+    let steamapps = common.parent()?;
+    if !common.file_name()?.eq_ignore_ascii_case("common")
+        || !steamapps.file_name()?.eq_ignore_ascii_case("steamapps")
+    {
+        return None;
+    }
+
+    let manifest_path = steamapps.join(format!("appmanifest_{}.acf", FM26_STEAM_APP_ID));
+    let doc = vdf::parse_file(&manifest_path).ok()?;
+    let install_dir = doc.get_path(&["AppState", "installdir"])?.as_str()?;
+
+    if install_path.file_name()?.to_string_lossy().eq_ignore_ascii_case(install_dir) {
+        Some(doc)
+    } else {
+        None
+    }
+}
+
+/// Finds the Proton prefix (`compatdata/<appid>/pfx`) for an app in any library
+pub fn find_compat_prefix(app_id: &str) -> Option<PathBuf> {
+    find_all_library_folders()
+        .into_iter()
+        .map(|(_, library)| {
+            library
+                .join("steamapps")
+                .join("compatdata")
+                .join(app_id)
+                .join("pfx")
+        })
+        .find(|prefix| prefix.is_dir())
+}
+
+/// Lists every Steam user's `userdata/<id>/config/localconfig.vdf`
+pub fn find_localconfig_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    for steam_root in find_steam_roots() {
+        let Ok(entries) = std::fs::read_dir(steam_root.join("userdata")) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let localconfig = entry.path().join("config").join("localconfig.vdf");
+            if localconfig.is_file() {
+                files.push(localconfig);
+            }
+        }
+    }
+
+    files
+}
+
+/// Key path of an app's launch options inside localconfig.vdf
+pub fn launch_options_key_path(app_id: &str) -> [&str; 7] {
+    [
+        "UserLocalConfigStore",
+        "Software",
+        "Valve",
+        "Steam",
+        "apps",
+        app_id,
+        "LaunchOptions",
+    ]
+}
+
+/// Compares two paths, resolving symlinks like ~/.steam/steam where possible
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn push_unique(paths: &mut Vec<PathBuf>, path: PathBuf) {
+    if !paths.iter().any(|existing| same_path(existing, &path)) {
+        paths.push(path);
+    }
+}
@@ -0,0 +1,47 @@
+//! Fixtures shared by the unit tests
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Distinguishes scratch folders created by the same test process
+static NEXT_TEMP_DIR: AtomicU32 = AtomicU32::new(0);
+
+/// A scratch folder under the system temp dir, removed when dropped so a
+/// failing assertion doesn't leave it behind
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates an empty folder named after the test
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "bst_test_{}_{}_{}",
+            name,
+            std::process::id(),
+            NEXT_TEMP_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::bepinex::DOORSTOP_ROOT_FILES;
+use crate::{game_version, install_manifest, preflight};
+use crate::models::{Fm26Installation, UninstallReport};
+use crate::registry;
+use chrono::Local;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Folder BepInEx 6 IL2CPP puts its .NET runtime in, next to the game exe
+const DOTNET_RUNTIME_DIR: &str = "dotnet";
+
+/// Collects every file under a directory, recursively
+/// Symlinks are listed as files of their own and never followed.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files);
+        } else {
+            files.push(entry.path());
+        }
+    }
+}
+
+/// Finds the user-made files worth keeping: configs, team mappings, stadium bundles and audio
+fn find_user_data(install: &Fm26Installation) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    collect_files(Path::new(&install.config_path), &mut files);
+    collect_files(Path::new(&install.custom_stadium_path), &mut files);
+    collect_files(Path::new(&install.audio_inject_path), &mut files);
+
+    let mappings = Path::new(&install.plugins_path)
+        .join("StadiumInjection")
+        .join("team_mappings.txt");
+    if mappings.is_file() {
+        files.push(mappings);
+    }
+
+    files
+}
+
+/// Writes files into a zip archive, keeping their paths relative to the game root
+fn export_user_data(root: &Path, files: &[PathBuf], dest: &Path) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+
+    let file = fs::File::create(dest)
+        .map_err(|e| format!("Failed to create export archive: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+
+    for path in files {
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        // A link could pull files from outside the game folder into the export
+        if fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink()) {
+            continue;
+        }
+
+        zip.start_file(install_manifest::to_manifest_path(relative), SimpleFileOptions::default())
+            .map_err(|e| format!("Failed to add {} to export: {}", relative.display(), e))?;
+
+        let mut source = fs::File::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        io::copy(&mut source, &mut zip)
+            .map_err(|e| format!("Failed to export {}: {}", path.display(), e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish export archive: {}", e))?;
+
+    Ok(())
+}
+
+/// Folders BepInEx generates at runtime; removed even though no manifest lists them
+const GENERATED_DIRS: [&str; 3] = ["cache", "interop", "unity-libs"];
+
+/// Files under BepInEx/ written by BepInEx or this app rather than by a pack
+const GENERATED_FILES: [&str; 2] = ["LogOutput.log", game_version::RECORDED_VERSION_FILE];
+
+/// Files to remove: everything the manifest lists, or only the known loader files without one
+fn files_to_remove(root: &Path) -> (Vec<PathBuf>, bool) {
+    let mut files: Vec<PathBuf> = DOORSTOP_ROOT_FILES.iter().map(|name| root.join(name)).collect();
+
+    let Some(manifest) = install_manifest::read_manifest(root) else {
+        // Without a manifest, only take the .NET runtime if it is clearly BepInEx's
+        let dotnet = root.join(DOTNET_RUNTIME_DIR);
+        if dotnet.join("coreclr.dll").is_file() || dotnet.join("libcoreclr.so").is_file() {
+            collect_files(&dotnet, &mut files);
+        }
+        return (files, false);
+    };
+
+    let bepinex_path = root.join("BepInEx");
+    // Files migrated from an earlier install are the user's, even where the pack shipped one
+    let listed = manifest
+        .files
+        .iter()
+        .filter(|entry| !manifest.user_data.contains(&entry.path))
+        .map(|entry| root.join(&entry.path))
+        .chain(GENERATED_FILES.iter().map(|name| bepinex_path.join(name)))
+        .chain(std::iter::once(root.join(install_manifest::MANIFEST_PATH)));
+    for path in listed {
+        if !files.contains(&path) {
+            files.push(path);
+        }
+    }
+
+    (files, true)
+}
+
+/// Removes now-empty parent folders of a removed file, stopping at the game root
+fn remove_empty_parents(root: &Path, path: &Path, removed_dirs: &mut Vec<String>) {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == root || !current.starts_with(root) {
+            break;
+        }
+        if fs::remove_dir(current).is_err() {
+            break; // not empty
+        }
+        removed_dirs.push(current.to_string_lossy().to_string());
+        dir = current.parent();
+    }
+}
+
+/// Uninstalls BepInEx and the stadium mods from an FM26 installation
+/// With an install manifest, only the pack files it lists (not those it marks
+/// as user data) and the folders BepInEx generates are removed; anything else under BepInEx/ is reported as left behind.
+/// Without one, the BepInEx folder and the known loader files are removed.
+/// With keep_user_data, configs, team mappings, stadium bundles and audio are
+/// first exported to a zip in the app data folder.
+#[tauri::command]
+pub fn uninstall_bepinex(
+    app_handle: tauri::AppHandle,
+    install_id: String,
+    keep_user_data: bool,
+) -> Result<UninstallReport, String> {
+    let install = registry::resolve(&app_handle, &install_id)?;
+    let root = Path::new(&install.root_path);
+    let bepinex_path = root.join("BepInEx");
+
+    preflight::ensure_ready(&preflight::check_uninstall(&install))?;
+
+    // Export before anything is deleted, so a failed export leaves the install intact
+    let export_path = if keep_user_data {
+        let files = find_user_data(&install);
+        if files.is_empty() {
+            None
+        } else {
+            let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+            let dest = app_handle
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+                .join("exports")
+                .join(format!("bepinex_user_data_{}.zip", timestamp));
+            export_user_data(root, &files, &dest)?;
+            Some(dest.to_string_lossy().to_string())
+        }
+    } else {
+        None
+    };
+
+    // Read the manifest before BepInEx/ (where it lives) is removed
+    let (files, used_manifest) = files_to_remove(root);
+
+    let mut removed_files = Vec::new();
+    let mut removed_dirs = Vec::new();
+    let mut failed = Vec::new();
+
+    for path in files {
+        // A tampered manifest must not reach outside the game folder; the file itself
+        // is not resolved, so a symlink is removed rather than its target
+        let inside = path.parent().map(|parent| registry::ensure_inside(root, parent));
+        if !matches!(inside, Some(Ok(_))) {
+            failed.push(format!("{}: outside the game folder", path.display()));
+            continue;
+        }
+        if !path.is_file() {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                removed_files.push(path.to_string_lossy().to_string());
+                remove_empty_parents(root, &path, &mut removed_dirs);
+            }
+            Err(e) => failed.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    let generated_dirs: Vec<PathBuf> = if used_manifest {
+        GENERATED_DIRS.iter().map(|name| bepinex_path.join(name)).collect()
+    } else {
+        vec![bepinex_path.clone()]
+    };
+    for dir in generated_dirs.iter().filter(|dir| dir.is_dir()) {
+        match fs::remove_dir_all(dir) {
+            Ok(()) => {
+                removed_dirs.push(dir.to_string_lossy().to_string());
+                remove_empty_parents(root, dir, &mut removed_dirs);
+            }
+            Err(e) => failed.push(format!("{}: {}", dir.display(), e)),
+        }
+    }
+
+    // Whatever is still under BepInEx/ was not installed by a pack, e.g. third-party plugins
+    let mut remaining = Vec::new();
+    collect_files(&bepinex_path, &mut remaining);
+    let left_behind = remaining
+        .iter()
+        .filter_map(|path| path.strip_prefix(root).ok())
+        .map(install_manifest::to_manifest_path)
+        .collect();
+
+    registry::refresh(&app_handle, &install.id);
+
+    Ok(UninstallReport {
+        removed_files,
+        removed_dirs,
+        failed,
+        used_manifest,
+        export_path,
+        left_behind,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{InstallManifest, ManifestFile, PackSource};
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn keeps_files_migrated_from_the_previous_install() {
+        let root = TempDir::new("uninstall_user_data");
+        fs::create_dir_all(root.join("BepInEx")).unwrap();
+        let file = |path: &str| ManifestFile {
+            path: path.to_string(),
+            size: 0,
+            sha256: String::new(),
+        };
+        let mut manifest = InstallManifest {
+            source: PackSource::Bundled,
+            pack_sha256: String::new(),
+            installed_at: String::new(),
+            files: vec![file("BepInEx/core/BepInEx.Core.dll"), file("BepInEx/config/com.bassy.test.cfg")],
+            user_data: Vec::new(),
+        };
+        install_manifest::mark_user_data(&mut manifest, &["config/com.bassy.test.cfg".to_string()]);
+        install_manifest::write_manifest(&root, &manifest).unwrap();
+
+        let (files, used_manifest) = files_to_remove(&root);
+        assert!(used_manifest);
+        assert!(files.contains(&root.join("BepInEx/core/BepInEx.Core.dll")));
+        assert!(!files.contains(&root.join("BepInEx/config/com.bassy.test.cfg")));
+    }
+}
@@ -0,0 +1,382 @@
+use std::fs;
+use std::path::Path;
+
+/// A node in a Valve KeyValues (VDF/ACF) document
+#[derive(Debug, Clone, PartialEq)]
+pub enum Vdf {
+    Value(String),
+    Object(Vec<(String, Vdf)>),
+}
+
+impl Vdf {
+    /// Looks up a direct child by key (KeyValues keys are case-insensitive)
+    pub fn get(&self, key: &str) -> Option<&Vdf> {
+        match self {
+            Vdf::Object(entries) => entries
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            Vdf::Value(_) => None,
+        }
+    }
+
+    /// Follows a chain of keys, e.g. `["AppState", "installdir"]`
+    pub fn get_path(&self, keys: &[&str]) -> Option<&Vdf> {
+        keys.iter().try_fold(self, |node, key| node.get(key))
+    }
+
+    /// Returns the string value of a leaf node
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Vdf::Value(value) => Some(value),
+            Vdf::Object(_) => None,
+        }
+    }
+
+    /// Returns the child entries of an object node
+    pub fn entries(&self) -> &[(String, Vdf)] {
+        match self {
+            Vdf::Object(entries) => entries,
+            Vdf::Value(_) => &[],
+        }
+    }
+
+}
+
+/// Reads and parses a VDF/ACF file from disk
+pub fn parse_file(path: &Path) -> Result<Vdf, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    parse(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Parses a KeyValues document into a root object node
+pub fn parse(input: &str) -> Result<Vdf, String> {
+    let mut tokenizer = Tokenizer::new(input);
+    let entries = parse_entries(&mut tokenizer, false)?;
+    Ok(Vdf::Object(entries))
+}
+
+/// Inverse of the unescaping done by the tokenizer
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Str(String),
+    Open,
+    Close,
+}
+
+struct Tokenizer<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    len: usize,
+    line: usize,
+    token_start: usize, // byte offset where the last returned token began
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.char_indices().peekable(),
+            len: input.len(),
+            line: 1,
+            token_start: 0,
+        }
+    }
+
+    /// Byte offset of the next unread character
+    fn offset(&mut self) -> usize {
+        self.chars.peek().map_or(self.len, |&(i, _)| i)
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, String> {
+        loop {
+            self.token_start = self.offset();
+            match self.peek() {
+                None => return Ok(None),
+                Some('\n') => {
+                    self.line += 1;
+                    self.chars.next();
+                }
+                Some(c) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some('/') => {
+                    // Line comment
+                    self.chars.next();
+                    if self.peek() == Some('/') {
+                        while let Some(c) = self.peek() {
+                            if c == '\n' {
+                                break;
+                            }
+                            self.chars.next();
+                        }
+                    } else {
+                        return Ok(Some(Token::Str(self.read_bare("/".to_string()))));
+                    }
+                }
+                Some('[') => {
+                    // Conditional like [$WIN32] - not used by Steam's files, ignore
+                    for (_, c) in self.chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                Some('{') => {
+                    self.chars.next();
+                    return Ok(Some(Token::Open));
+                }
+                Some('}') => {
+                    self.chars.next();
+                    return Ok(Some(Token::Close));
+                }
+                Some('"') => {
+                    self.chars.next();
+                    return self.read_quoted().map(|s| Some(Token::Str(s)));
+                }
+                Some(_) => return Ok(Some(Token::Str(self.read_bare(String::new())))),
+            }
+        }
+    }
+
+    fn read_quoted(&mut self) -> Result<String, String> {
+        let start_line = self.line;
+        let mut value = String::new();
+
+        while let Some((_, c)) = self.chars.next() {
+            match c {
+                '"' => return Ok(value),
+                '\\' => match self.chars.next().map(|(_, c)| c) {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(other) => value.push(other),
+                    None => break,
+                },
+                '\n' => {
+                    self.line += 1;
+                    value.push(c);
+                }
+                _ => value.push(c),
+            }
+        }
+
+        Err(format!("Unterminated string starting on line {}", start_line))
+    }
+
+    fn read_bare(&mut self, mut value: String) -> String {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == '"' || c == '{' || c == '}' {
+                break;
+            }
+            value.push(c);
+            self.chars.next();
+        }
+        value
+    }
+}
+
+fn parse_entries(tokenizer: &mut Tokenizer, nested: bool) -> Result<Vec<(String, Vdf)>, String> {
+    let mut entries = Vec::new();
+
+    loop {
+        let key = match tokenizer.next_token()? {
+            Some(Token::Str(key)) => key,
+            Some(Token::Close) if nested => return Ok(entries),
+            Some(Token::Close) => {
+                return Err(format!("Unexpected '}}' on line {}", tokenizer.line));
+            }
+            Some(Token::Open) => {
+                return Err(format!("Unexpected '{{' on line {}", tokenizer.line));
+            }
+            None if nested => return Err("Unexpected end of file, missing '}'".to_string()),
+            None => return Ok(entries),
+        };
+
+        let value = match tokenizer.next_token()? {
+            Some(Token::Str(value)) => Vdf::Value(value),
+            Some(Token::Open) => Vdf::Object(parse_entries(tokenizer, true)?),
+            Some(Token::Close) | None => {
+                return Err(format!("Missing value for key \"{}\" on line {}", key, tokenizer.line));
+            }
+        };
+
+        entries.push((key, value));
+    }
+}
+
+/// Where a key's value sits in the source text
+struct Span {
+    key: String,
+    value: SpanValue,
+}
+
+enum SpanValue {
+    Leaf { start: usize, end: usize }, // the quoted or bare value token
+    Object { children: Vec<Span>, close: usize }, // close is the offset of '}'
+}
+
+/// Like parse_entries, but records byte offsets instead of values
+/// Returns the entries and where the enclosing object closes (the input length at the root).
+fn parse_spans(tokenizer: &mut Tokenizer, nested: bool) -> Result<(Vec<Span>, usize), String> {
+    let mut entries = Vec::new();
+
+    loop {
+        let key = match tokenizer.next_token()? {
+            Some(Token::Str(key)) => key,
+            Some(Token::Close) if nested => return Ok((entries, tokenizer.token_start)),
+            None if !nested => return Ok((entries, tokenizer.token_start)),
+            Some(Token::Close) | Some(Token::Open) | None => {
+                return Err(format!("Malformed document on line {}", tokenizer.line));
+            }
+        };
+
+        let value = match tokenizer.next_token()? {
+            Some(Token::Str(_)) => SpanValue::Leaf {
+                start: tokenizer.token_start,
+                end: tokenizer.offset(),
+            },
+            Some(Token::Open) => {
+                let (children, close) = parse_spans(tokenizer, true)?;
+                SpanValue::Object { children, close }
+            }
+            Some(Token::Close) | None => {
+                return Err(format!("Missing value for key \"{}\" on line {}", key, tokenizer.line));
+            }
+        };
+
+        entries.push(Span { key, value });
+    }
+}
+
+/// Sets one leaf value in KeyValues text, leaving every other byte untouched
+/// Missing objects along the path are added before the closing brace of the
+/// deepest one that exists. Comments and [$conditionals] survive, unlike a
+/// parse and to_string round trip.
+pub fn set_value_in_place(input: &str, keys: &[&str], value: &str) -> Result<String, String> {
+    let Some((last, parents)) = keys.split_last() else {
+        return Ok(input.to_string());
+    };
+
+    let (mut entries, mut close) = parse_spans(&mut Tokenizer::new(input), false)?;
+    let mut depth = 0;
+    let mut missing = parents;
+
+    while let Some((key, rest)) = missing.split_first() {
+        let object = entries
+            .into_iter()
+            .find(|span| span.key.eq_ignore_ascii_case(key) && matches!(span.value, SpanValue::Object { .. }));
+        let Some(Span { value: SpanValue::Object { children, close: object_close }, .. }) = object else {
+            entries = Vec::new();
+            break;
+        };
+        entries = children;
+        close = object_close;
+        depth += 1;
+        missing = rest;
+    }
+
+    let quoted = format!("\"{}\"", escape(value));
+    if missing.is_empty() {
+        if let Some(span) = entries.iter().find(|span| span.key.eq_ignore_ascii_case(last)) {
+            return match span.value {
+                SpanValue::Leaf { start, end } => Ok(format!("{}{}{}", &input[..start], quoted, &input[end..])),
+                SpanValue::Object { .. } => Err(format!("\"{}\" is an object, not a value", last)),
+            };
+        }
+    }
+
+    // Build the missing objects and the value, indented like Steam writes them
+    let mut text = String::new();
+    for (i, key) in missing.iter().enumerate() {
+        let indent = "\t".repeat(depth + i);
+        text.push_str(&format!("{}\"{}\"\n{}{{\n", indent, escape(key), indent));
+    }
+    text.push_str(&format!("{}\"{}\"\t\t{}\n", "\t".repeat(depth + missing.len()), escape(last), quoted));
+    for i in (0..missing.len()).rev() {
+        text.push_str(&format!("{}}}\n", "\t".repeat(depth + i)));
+    }
+
+    // Insert at the start of the closing brace's line when it sits alone there
+    let line_start = input[..close].rfind('\n').map_or(0, |i| i + 1);
+    let at = if input[line_start..close].trim().is_empty() { line_start } else { close };
+    let separator = if at > 0 && !input[..at].ends_with('\n') { "\n" } else { "" };
+
+    Ok(format!("{}{}{}{}", &input[..at], separator, text, &input[at..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCALCONFIG: &str = "\"UserLocalConfigStore\"\n{\n\t\"Software\"\n\t{\n\t\t// comment\n\t\t\"apps\"\n\t\t{\n\t\t\t\"3551340\"\n\t\t\t{\n\t\t\t\t\"LaunchOptions\"\t\t\"-windowed\"\n\t\t\t\t\"cloud\" [$WIN32]\t\t\"1\"\n\t\t\t}\n\t\t}\n\t}\n}\n";
+
+    #[test]
+    fn round_trips_escaped_values() {
+        let value = "quote \" slash \\ line\nbreak\ttab";
+        let patched = set_value_in_place("", &["a", "b"], value).unwrap();
+        assert_eq!(parse(&patched).unwrap().get_path(&["a", "b"]).and_then(Vdf::as_str), Some(value));
+    }
+
+    #[test]
+    fn parses_nested_objects_case_insensitively() {
+        let doc = parse(LOCALCONFIG).unwrap();
+        let value = doc.get_path(&["userlocalconfigstore", "software", "apps", "3551340", "launchoptions"]);
+        assert_eq!(value.and_then(Vdf::as_str), Some("-windowed"));
+    }
+
+    #[test]
+    fn replaces_value_in_place() {
+        let keys = ["UserLocalConfigStore", "Software", "apps", "3551340", "LaunchOptions"];
+        let patched = set_value_in_place(LOCALCONFIG, &keys, "WINEDLLOVERRIDES=\"winhttp=n,b\" %command%").unwrap();
+
+        assert_eq!(
+            patched,
+            LOCALCONFIG.replace("\"-windowed\"", "\"WINEDLLOVERRIDES=\\\"winhttp=n,b\\\" %command%\"")
+        );
+        assert!(patched.contains("// comment") && patched.contains("[$WIN32]"));
+        let doc = parse(&patched).unwrap();
+        assert_eq!(
+            doc.get_path(&keys).and_then(Vdf::as_str),
+            Some("WINEDLLOVERRIDES=\"winhttp=n,b\" %command%")
+        );
+    }
+
+    #[test]
+    fn inserts_missing_objects() {
+        let keys = ["UserLocalConfigStore", "Software", "apps", "999", "LaunchOptions"];
+        let patched = set_value_in_place(LOCALCONFIG, &keys, "-novid").unwrap();
+
+        assert!(patched.starts_with(&LOCALCONFIG[..LOCALCONFIG.find("\t\t}").unwrap()]));
+        let doc = parse(&patched).unwrap();
+        assert_eq!(doc.get_path(&keys).and_then(Vdf::as_str), Some("-novid"));
+        assert_eq!(
+            doc.get_path(&["UserLocalConfigStore", "Software", "apps", "3551340", "LaunchOptions"])
+                .and_then(Vdf::as_str),
+            Some("-windowed")
+        );
+    }
+
+    #[test]
+    fn rejects_replacing_an_object() {
+        let keys = ["UserLocalConfigStore", "Software", "apps"];
+        assert!(set_value_in_place(LOCALCONFIG, &keys, "x").is_err());
+    }
+}
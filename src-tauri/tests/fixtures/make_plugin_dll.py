@@ -0,0 +1,189 @@
+#!/usr/bin/env python3
+"""Writes FixturePlugin.dll, a metadata-only .NET assembly for the dotnet.rs tests.
+
+It holds what the C# compiler emits for the attributes of this class (no IL):
+
+    [BepInPlugin("com.bassy.fixture", "Fixture Plugin", "1.2.3")]
+    [BepInDependency("com.bassy.fm26.stadiuminjection", BepInDependency.DependencyFlags.HardDependency)]
+    [BepInDependency("com.bassy.fm26.audioinject", BepInDependency.DependencyFlags.SoftDependency)]
+    [BepInDependency("com.bassy.core", "1.0.0")]
+    public class FixturePlugin : BaseUnityPlugin {}
+
+Run it from this folder: python3 make_plugin_dll.py
+"""
+
+import struct
+
+TEXT_RVA = 0x2000
+TEXT_OFFSET = 0x200
+ALIGN = 0x200
+
+
+class Heap:
+    def __init__(self, initial):
+        self.data = bytearray(initial)
+        self.index = {}
+
+    def add(self, key, encoded):
+        if key not in self.index:
+            self.index[key] = len(self.data)
+            self.data += encoded
+        return self.index[key]
+
+
+def compressed(n):
+    if n < 0x80:
+        return bytes([n])
+    if n < 0x4000:
+        return struct.pack(">H", n | 0x8000)
+    return struct.pack(">I", n | 0xC000_0000)
+
+
+def ser_string(text):
+    raw = text.encode()
+    return compressed(len(raw)) + raw
+
+
+def pad4(data):
+    return bytes(data) + b"\0" * (-len(data) % 4)
+
+
+strings = Heap(b"\0")
+blobs = Heap(b"\0")
+
+
+def s(text):
+    return strings.add(text, text.encode() + b"\0") if text else 0
+
+
+def b(data):
+    return blobs.add(bytes(data), compressed(len(data)) + bytes(data)) if data else 0
+
+
+def u16(*values):
+    return struct.pack("<%dH" % len(values), *values)
+
+
+# Coded indexes (ECMA-335 II.24.2.6), all small
+def resolution_scope_assembly_ref(row):
+    return (row << 2) | 2
+
+
+def resolution_scope_type_ref(row):
+    return (row << 2) | 3
+
+
+def type_def_or_ref_type_ref(row):
+    return (row << 2) | 1
+
+
+def member_ref_parent_type_ref(row):
+    return (row << 3) | 1
+
+
+def has_custom_attribute_type_def(row):
+    return (row << 5) | 3
+
+
+def custom_attribute_type_member_ref(row):
+    return (row << 3) | 3
+
+
+# AssemblyRef rows: 1 BepInEx, 2 netstandard
+TYPE_REFS = [
+    (resolution_scope_assembly_ref(1), "BaseUnityPlugin", "BepInEx"),
+    (resolution_scope_assembly_ref(1), "BepInPlugin", "BepInEx"),
+    (resolution_scope_assembly_ref(1), "BepInDependency", "BepInEx"),
+    (resolution_scope_type_ref(3), "DependencyFlags", ""),
+    (resolution_scope_assembly_ref(2), "Object", "System"),
+]
+
+tables = {}
+tables[0x00] = [u16(0, s("FixturePlugin.dll"), 1, 0, 0)]
+tables[0x01] = [u16(scope, s(name), s(namespace)) for scope, name, namespace in TYPE_REFS]
+tables[0x02] = [
+    struct.pack("<I", 0) + u16(s("<Module>"), 0, 0, 1, 1),
+    struct.pack("<I", 0x0010_0001) + u16(s("FixturePlugin"), s("Bassy.Fixture"), type_def_or_ref_type_ref(1), 1, 1),
+]
+
+STRING, VALUE_TYPE = 0x0E, 0x11
+ctor = lambda *params: bytes([0x20, len(params), 0x01]) + b"".join(params)
+tables[0x0A] = [
+    u16(member_ref_parent_type_ref(2), s(".ctor"), b(ctor(bytes([STRING]), bytes([STRING]), bytes([STRING])))),
+    u16(member_ref_parent_type_ref(3), s(".ctor"), b(ctor(bytes([STRING]), bytes([VALUE_TYPE, type_def_or_ref_type_ref(4)])))),
+    u16(member_ref_parent_type_ref(3), s(".ctor"), b(ctor(bytes([STRING]), bytes([STRING])))),
+]
+
+
+def attribute(member_ref, *args):
+    value = b"\x01\x00"
+    for arg in args:
+        value += ser_string(arg) if isinstance(arg, str) else struct.pack("<i", arg)
+    value += b"\x00\x00"
+    return u16(has_custom_attribute_type_def(2), custom_attribute_type_member_ref(member_ref), b(value))
+
+
+tables[0x0C] = [
+    attribute(1, "com.bassy.fixture", "Fixture Plugin", "1.2.3"),
+    attribute(2, "com.bassy.fm26.stadiuminjection", 1),
+    attribute(2, "com.bassy.fm26.audioinject", 2),
+    attribute(3, "com.bassy.core", "1.0.0"),
+]
+tables[0x20] = [struct.pack("<I", 0x8004) + u16(1, 2, 3, 0) + struct.pack("<I", 0) + u16(0, s("FixturePlugin"), 0)]
+tables[0x23] = [
+    u16(5, 4, 0, 0) + struct.pack("<I", 0) + u16(0, s("BepInEx"), 0, 0),
+    u16(2, 0, 0, 0) + struct.pack("<I", 0) + u16(0, s("netstandard"), 0, 0),
+]
+
+valid = sum(1 << t for t in tables)
+sorted_tables = (1 << 0x0C)
+tilde = struct.pack("<IBBBBQQ", 0, 2, 0, 0, 1, valid, sorted_tables)
+tilde += b"".join(struct.pack("<I", len(tables[t])) for t in sorted(tables))
+tilde += b"".join(b"".join(tables[t]) for t in sorted(tables))
+
+guid = bytes(range(1, 17))
+streams = [
+    ("#~", pad4(tilde)),
+    ("#Strings", pad4(strings.data)),
+    ("#US", pad4(b"\0")),
+    ("#GUID", guid),
+    ("#Blob", pad4(blobs.data)),
+]
+
+version = pad4(b"v4.0.30319\0")
+header_size = 16 + len(version) + 4 + sum(8 + len(pad4(name.encode() + b"\0")) for name, _ in streams)
+root = struct.pack("<IHHII", 0x424A_5342, 1, 1, 0, len(version)) + version + u16(0, len(streams))
+offset = header_size
+for name, data in streams:
+    root += struct.pack("<II", offset, len(data)) + pad4(name.encode() + b"\0")
+    offset += len(data)
+metadata = root + b"".join(data for _, data in streams)
+
+# .text holds the CLI header followed by the metadata
+CLI_SIZE = 72
+cli = struct.pack("<IHH", CLI_SIZE, 2, 5) + struct.pack("<II", TEXT_RVA + CLI_SIZE, len(metadata))
+cli += struct.pack("<II", 1, 0) + b"\0" * (CLI_SIZE - 24)
+text = cli + metadata
+text_raw = text + b"\0" * (-len(text) % ALIGN)
+
+dos = b"MZ" + b"\0" * 0x3A + struct.pack("<I", 0x80)
+dos += b"\0" * (0x80 - len(dos))
+coff = b"PE\0\0" + struct.pack("<HHIIIHH", 0x14C, 1, 0, 0, 0, 0xE0, 0x2102)
+
+optional = struct.pack("<HBBIII", 0x10B, 8, 0, len(text_raw), 0, 0)
+optional += struct.pack("<III", 0, TEXT_RVA, 0)  # entry point, base of code, base of data
+optional += struct.pack("<III", 0x1000_0000, 0x2000, ALIGN)
+optional += u16(4, 0, 0, 0, 4, 0) + struct.pack("<I", 0)
+optional += struct.pack("<III", TEXT_RVA + 0x2000, TEXT_OFFSET, 0)
+optional += u16(3, 0x8540) + struct.pack("<IIIIII", 0x10_0000, 0x1000, 0x10_0000, 0x1000, 0, 16)
+directories = [(0, 0)] * 16
+directories[14] = (TEXT_RVA, CLI_SIZE)
+optional += b"".join(struct.pack("<II", rva, size) for rva, size in directories)
+
+section = b".text\0\0\0" + struct.pack("<IIIIIIHHI", len(text), TEXT_RVA, len(text_raw), TEXT_OFFSET, 0, 0, 0, 0, 0x6000_0020)
+
+headers = dos + coff + optional + section
+image = headers + b"\0" * (TEXT_OFFSET - len(headers)) + text_raw
+
+with open("FixturePlugin.dll", "wb") as f:
+    f.write(image)
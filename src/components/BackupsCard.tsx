@@ -0,0 +1,202 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { confirm, open } from "@tauri-apps/plugin-dialog";
+import { toast } from "sonner";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Badge } from "@/components/ui/badge";
+import { Table, TableBody, TableCell, TableHead, TableHeader, TableRow } from "@/components/ui/table";
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
+import { RotateCcw, Trash2 } from "lucide-react";
+import type { BackupInfo, BackupLocation, BackupSettings, Fm26Installation } from "@/types";
+
+interface BackupsCardProps {
+  installation: Fm26Installation;
+  onRestoreComplete: () => void;
+}
+
+function formatSize(bytes: number): string {
+  if (bytes >= 1024 * 1024 * 1024) return `${(bytes / 1024 / 1024 / 1024).toFixed(1)} GB`;
+  if (bytes >= 1024 * 1024) return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+  return `${Math.ceil(bytes / 1024)} KB`;
+}
+
+export function BackupsCard({ installation, onRestoreComplete }: BackupsCardProps) {
+  const [backups, setBackups] = useState<BackupInfo[]>([]);
+  const [settings, setSettings] = useState<BackupSettings | null>(null);
+  const [busy, setBusy] = useState<string | null>(null);
+
+  const loadBackups = async () => {
+    try {
+      const list = await invoke<BackupInfo[]>("list_backups", { installId: installation.id });
+      setBackups(list);
+    } catch (err) {
+      toast.error("Failed to list backups", { description: String(err) });
+    }
+  };
+
+  useEffect(() => {
+    loadBackups();
+    invoke<BackupSettings>("get_backup_settings").then(setSettings).catch(() => {});
+  }, [installation]);
+
+  const saveSettings = async (next: BackupSettings) => {
+    try {
+      await invoke("set_backup_settings", { settings: next });
+      setSettings(next);
+      await loadBackups();
+    } catch (err) {
+      toast.error("Failed to save backup settings", { description: String(err) });
+    }
+  };
+
+  const handleLocationChange = async (kind: string) => {
+    if (!settings) return;
+
+    let location: BackupLocation;
+    if (kind === "custom") {
+      const selected = await open({ directory: true, title: "Select Backup Folder" });
+      if (!selected || typeof selected !== "string") return;
+      location = { kind: "custom", path: selected };
+    } else {
+      location = { kind: kind as "game_folder" | "app_data" };
+    }
+
+    await saveSettings({ ...settings, location });
+  };
+
+  const handleRestore = async (backup: BackupInfo) => {
+    const confirmed = await confirm(
+      `Restore ${backup.name}? The current BepInEx folder will be kept as a new backup.`,
+      { title: "Restore Backup", kind: "warning" }
+    );
+    if (!confirmed) return;
+
+    setBusy(backup.name);
+    try {
+      await invoke("restore_backup", { installId: installation.id, name: backup.name });
+      toast.success("Backup restored");
+      await loadBackups();
+      onRestoreComplete();
+    } catch (err) {
+      toast.error("Failed to restore backup", { description: String(err) });
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleDelete = async (backup: BackupInfo) => {
+    const confirmed = await confirm(`Delete ${backup.name}? This cannot be undone.`, {
+      title: "Delete Backup",
+      kind: "warning",
+    });
+    if (!confirmed) return;
+
+    setBusy(backup.name);
+    try {
+      await invoke("delete_backup", { installId: installation.id, name: backup.name });
+      await loadBackups();
+    } catch (err) {
+      toast.error("Failed to delete backup", { description: String(err) });
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>Backups</CardTitle>
+        <CardDescription>
+          Previous BepInEx folders, saved before each install
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {settings && (
+          <div className="grid gap-4 md:grid-cols-2">
+            <div className="space-y-2">
+              <Label>Keep</Label>
+              <Input
+                type="number"
+                min={1}
+                value={settings.retention}
+                onChange={(e) => {
+                  const retention = parseInt(e.target.value, 10);
+                  if (retention >= 1) saveSettings({ ...settings, retention });
+                }}
+              />
+            </div>
+            <div className="space-y-2">
+              <Label>Location</Label>
+              <Select value={settings.location.kind} onValueChange={handleLocationChange}>
+                <SelectTrigger>
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value="game_folder">Game folder</SelectItem>
+                  <SelectItem value="app_data">App data folder</SelectItem>
+                  <SelectItem value="custom">
+                    {settings.location.kind === "custom" ? settings.location.path : "Custom folder..."}
+                  </SelectItem>
+                </SelectContent>
+              </Select>
+            </div>
+          </div>
+        )}
+        <Table>
+          <TableHeader>
+            <TableRow>
+              <TableHead>Date</TableHead>
+              <TableHead>Size</TableHead>
+              <TableHead className="hidden md:table-cell">Plugins</TableHead>
+              <TableHead />
+            </TableRow>
+          </TableHeader>
+          <TableBody>
+            {backups.map((backup) => (
+              <TableRow key={backup.path}>
+                <TableCell className="font-medium">
+                  {backup.created_at ? new Date(backup.created_at).toLocaleString() : backup.name}
+                  {backup.external && (
+                    <Badge variant="secondary" className="ml-2">External</Badge>
+                  )}
+                </TableCell>
+                <TableCell>{formatSize(backup.size_bytes)}</TableCell>
+                <TableCell className="hidden md:table-cell text-muted-foreground text-xs">
+                  {backup.plugins.join(", ") || "None"}
+                </TableCell>
+                <TableCell className="text-right space-x-2">
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={() => handleRestore(backup)}
+                    disabled={busy !== null}
+                  >
+                    <RotateCcw className="h-4 w-4" />
+                  </Button>
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={() => handleDelete(backup)}
+                    disabled={busy !== null}
+                  >
+                    <Trash2 className="h-4 w-4" />
+                  </Button>
+                </TableCell>
+              </TableRow>
+            ))}
+            {backups.length === 0 && (
+              <TableRow>
+                <TableCell colSpan={4} className="text-center text-muted-foreground">
+                  No backups yet
+                </TableCell>
+              </TableRow>
+            )}
+          </TableBody>
+        </Table>
+      </CardContent>
+    </Card>
+  );
+}
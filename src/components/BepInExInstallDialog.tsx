@@ -17,11 +17,27 @@ import { Label } from "@/components/ui/label";
 import { Progress } from "@/components/ui/progress";
 import { Alert, AlertDescription } from "@/components/ui/alert";
 import { useBepInExDownload } from "@/hooks/useBepInExDownload";
+import { INSTALL_CANCELLED, useInstallProgress } from "@/hooks/useInstallProgress";
+import { describePlan, formatBytes } from "@/lib/utils";
 import { Download, FileArchive, Link, AlertTriangle, X } from "lucide-react";
-import type { Fm26Installation, BepInExStatus } from "@/types";
+import type {
+  ArchiveLayout,
+  Fm26Installation,
+  BepInExStatus,
+  InstallPlan,
+  MigrationReport,
+  PackSource,
+  PreflightReport,
+} from "@/types";
 
 type InstallSource = "r2" | "local" | "url";
 
+/** An archive ready to install, waiting for the user to confirm its layout */
+interface ReadyPack {
+  path: string;
+  source: PackSource;
+}
+
 interface BepInExInstallDialogProps {
   open: boolean;
   onOpenChange: (open: boolean) => void;
@@ -42,7 +58,10 @@ export function BepInExInstallDialog({
     null
   );
   const [installing, setInstalling] = useState(false);
-  const [showConfirm, setShowConfirm] = useState(false);
+  const [pack, setPack] = useState<ReadyPack | null>(null);
+  const [plan, setPlan] = useState<InstallPlan | null>(null);
+  const [layout, setLayout] = useState<ArchiveLayout | null>(null);
+  const [layoutPrefix, setLayoutPrefix] = useState<string | null>(null);
 
   const {
     downloading,
@@ -58,24 +77,62 @@ export function BepInExInstallDialog({
     clearError,
   } = useBepInExDownload();
 
+  const {
+    progress: installProgress,
+    resetProgress,
+    cancelInstall,
+  } = useInstallProgress();
+
   // Check existing BepInEx status when dialog opens
   useEffect(() => {
     if (open && installation) {
       invoke<BepInExStatus>("check_bepinex_installed", {
-        install: installation,
+        installId: installation.id,
       })
         .then(setBepInExStatus)
         .catch(console.error);
     }
   }, [open, installation]);
 
+  // Find where the pack's install tree starts, so the user can confirm it
+  useEffect(() => {
+    setLayout(null);
+    setLayoutPrefix(null);
+    if (!pack) return;
+    invoke<ArchiveLayout>("detect_archive_layout", {
+      zipPath: pack.path,
+      kind: "bepinex",
+      installId: installation.id,
+    })
+      .then(setLayout)
+      .catch((err) => {
+        toast.error("Could not find BepInEx in this archive", {
+          description: String(err),
+        });
+      });
+  }, [pack, installation]);
+
+  // Dry run the pack so the confirmation can say exactly what changes
+  useEffect(() => {
+    setPlan(null);
+    if (!pack) return;
+    invoke<InstallPlan>("plan_install", {
+      zipPath: pack.path,
+      kind: "bepinex",
+      layoutPrefix,
+      installId: installation.id,
+    })
+      .then(setPlan)
+      .catch(console.error);
+  }, [pack, layoutPrefix, installation]);
+
   // Reset state when dialog closes
   useEffect(() => {
     if (!open) {
       setSource("r2");
       setCustomUrl("");
       setLocalPath("");
-      setShowConfirm(false);
+      setPack(null);
       clearError();
     }
   }, [open, clearError]);
@@ -84,8 +141,13 @@ export function BepInExInstallDialog({
     try {
       const selected = await openFileDialog({
         multiple: false,
-        title: "Select BepInEx Pack Zip File",
-        filters: [{ name: "Zip Archives", extensions: ["zip"] }],
+        title: "Select BepInEx Pack Archive",
+        filters: [
+          {
+            name: "Pack Archives",
+            extensions: ["zip", "7z", "gz", "tgz", "xz", "txz"],
+          },
+        ],
       });
 
       if (selected && typeof selected === "string") {
@@ -96,58 +158,99 @@ export function BepInExInstallDialog({
     }
   };
 
-  const handleInstall = async () => {
-    // Show confirmation if BepInEx already installed
-    if (bepInExStatus?.installed && !showConfirm) {
-      setShowConfirm(true);
-      return;
-    }
-
-    setInstalling(true);
+  // Downloads or picks the archive, then waits for the user to confirm the plan
+  const preparePack = async () => {
     clearError();
-
     try {
-      let zipPath: string | null = null;
-
-      // Get zip file based on source
       if (source === "r2") {
-        zipPath = await downloadFromR2();
+        setPack({ path: await downloadFromR2(), source: { kind: "r2" } });
       } else if (source === "url") {
         if (!customUrl) {
           throw new Error("Please enter a URL");
         }
-        zipPath = await downloadFromUrl(customUrl);
-      } else if (source === "local") {
+        const path = await downloadFromUrl(customUrl);
+        setPack({ path, source: { kind: "url", url: customUrl } });
+      } else {
         if (!localPath) {
           throw new Error("Please select a local file");
         }
-        zipPath = localPath;
+        setPack({ path: localPath, source: { kind: "local_file", path: localPath } });
+      }
+    } catch (err) {
+      const message = err instanceof Error ? err.message : String(err);
+      if (message !== "Download cancelled") {
+        toast.error("Could not get the pack", { description: message });
+      }
+    }
+  };
+
+  const handleInstall = async () => {
+    if (!pack) {
+      await preparePack();
+      return;
+    }
+
+    setInstalling(true);
+    clearError();
+    resetProgress();
+
+    try {
+      // Check space, permissions and the game process before touching anything
+      const preflight = await invoke<PreflightReport>("preflight_install", {
+        zipPath: pack.path,
+        kind: "bepinex",
+        layoutPrefix,
+        installId: installation.id,
+      });
+      if (preflight.blockers.length > 0) {
+        throw new Error(preflight.blockers.map((b) => b.message).join("\n"));
+      }
+      if (preflight.warnings.length > 0) {
+        toast.warning("Installing with warnings", {
+          description: preflight.warnings.map((w) => w.message).join("\n"),
+        });
       }
 
       // Install the pack
-      await invoke("install_bepinex_pack", {
-        zipPath,
+      const migration = await invoke<MigrationReport>("install_bepinex_pack", {
+        zipPath: pack.path,
+        source: pack.source,
+        layoutPrefix,
         appHandle: null, // Tauri handles this
-        install: installation,
+        installId: installation.id,
       });
 
-      toast.success("BepInEx Stadium Pack installed!");
+      toast.success("BepInEx Stadium Pack installed!", {
+        description: migration.migrated.length > 0
+          ? `Kept ${migration.migrated.length} of your configs, mappings and stadiums`
+          : undefined,
+      });
+      if (migration.not_migrated.length > 0) {
+        toast.warning("Some settings could not be carried over", {
+          description: migration.not_migrated.join("\n"),
+        });
+      }
       onInstallComplete();
       onOpenChange(false);
     } catch (err) {
       const message = err instanceof Error ? err.message : String(err);
-      if (message !== "Download cancelled") {
+      if (message !== INSTALL_CANCELLED) {
         toast.error("Installation failed", { description: message });
       }
     } finally {
       setInstalling(false);
-      setShowConfirm(false);
+      setPack(null);
     }
   };
 
+  // Extraction has started once the install reports progress
+  const extracting = installing && !downloading && installProgress !== null;
+
   const handleCancel = () => {
     if (downloading && canCancel) {
       cancelDownload();
+    } else if (extracting) {
+      cancelInstall();
     } else {
       onOpenChange(false);
     }
@@ -155,6 +258,7 @@ export function BepInExInstallDialog({
 
   const isButtonDisabled = () => {
     if (installing || downloading) return true;
+    if (pack) return !layout;
     if (source === "url" && !customUrl) return true;
     if (source === "local" && !localPath) return true;
     return false;
@@ -166,7 +270,7 @@ export function BepInExInstallDialog({
     installing,
     progress,
     total,
-    showConfirm,
+    pack,
   });
 
   // Prevent closing dialog during download/install
@@ -197,30 +301,101 @@ export function BepInExInstallDialog({
         </AlertDialogHeader>
 
         {/* Overwrite Warning */}
-        {bepInExStatus?.installed && !showConfirm && (
+        {bepInExStatus?.installed && !pack && (
           <Alert variant="destructive" className="my-2">
             <AlertTriangle className="h-4 w-4" />
             <AlertDescription>
-              BepInEx is already installed with {bepInExStatus.plugin_count}{" "}
+              BepInEx{bepInExStatus.core_version ? ` ${bepInExStatus.core_version}` : ""} is
+              already installed with {bepInExStatus.plugin_count}{" "}
               plugin(s). Installing will replace existing files.
             </AlertDescription>
           </Alert>
         )}
 
-        {/* Confirmation Dialog - hide when downloading */}
-        {showConfirm && !downloading && !installing && (
-          <Alert variant="destructive" className="my-2">
+        {/* Loader mismatch warnings */}
+        {bepInExStatus && bepInExStatus.loader_warnings.length > 0 && !pack && (
+          <Alert className="my-2">
             <AlertTriangle className="h-4 w-4" />
             <AlertDescription>
-              <strong>Are you sure?</strong> This will overwrite your existing
-              BepInEx installation at{" "}
-              <code className="text-xs">{bepInExStatus?.path}</code>
+              <ul className="list-disc pl-4 text-sm">
+                {bepInExStatus.loader_warnings.map((warning) => (
+                  <li key={warning}>{warning}</li>
+                ))}
+              </ul>
             </AlertDescription>
           </Alert>
         )}
 
+        {/* Confirmation: where the pack goes and what it changes */}
+        {pack && !downloading && !installing && (
+          <div className="space-y-3 my-2">
+            {bepInExStatus?.installed && (
+              <Alert variant="destructive">
+                <AlertTriangle className="h-4 w-4" />
+                <AlertDescription>
+                  <strong>Are you sure?</strong> This will overwrite your existing
+                  BepInEx installation at{" "}
+                  <code className="text-xs">{bepInExStatus.path}</code>
+                </AlertDescription>
+              </Alert>
+            )}
+            {layout && (
+              <div className="space-y-1">
+                <Label htmlFor="layoutPrefix" className="text-xs">
+                  Game folder starts at
+                </Label>
+                <Input
+                  id="layoutPrefix"
+                  placeholder="(archive root)"
+                  value={layoutPrefix ?? layout.strip_prefix}
+                  onChange={(e) => setLayoutPrefix(e.target.value)}
+                  className="text-sm h-8"
+                />
+                <div className="text-xs text-muted-foreground">
+                  {layout.mapped_entries} file(s) installed
+                  {layout.skipped_entries.length > 0 &&
+                    `, ${layout.skipped_entries.length} outside this folder skipped`}
+                </div>
+              </div>
+            )}
+            {plan && (
+              <p className="text-xs text-muted-foreground">
+                {describePlan(plan)}
+              </p>
+            )}
+          </div>
+        )}
+
+        {/* Extraction Progress */}
+        {extracting && (
+          <div className="space-y-2 my-4">
+            <div className="flex justify-between text-sm">
+              <span>Extracting...</span>
+              <span>{Math.round(installProgress.percent)}%</span>
+            </div>
+            <Progress value={installProgress.percent} className="h-2" />
+            <div className="flex justify-between text-xs text-muted-foreground">
+              <span>
+                {installProgress.entries_done} / {installProgress.entries_total} files
+              </span>
+              <span>
+                {formatBytes(installProgress.bytes_done)} /{" "}
+                {formatBytes(installProgress.bytes_total)}
+              </span>
+            </div>
+            {installProgress.current_file && (
+              <div className="text-xs text-muted-foreground font-mono truncate">
+                {installProgress.current_file}
+              </div>
+            )}
+            <p className="text-xs text-muted-foreground text-center mt-2">
+              Cancelling puts your previous files back.
+            </p>
+          </div>
+        )}
+
         {/* Download Progress - show when downloading OR installing */}
-        {(downloading || installing) && (
+        {(downloading || installing) && !extracting && (
           <div className="space-y-2 my-4">
             <div className="flex justify-between text-sm">
               <span>
@@ -248,7 +423,7 @@ export function BepInExInstallDialog({
         )}
 
         {/* Source Selection */}
-        {!downloading && !installing && !showConfirm && (
+        {!downloading && !installing && !pack && (
           <div className="space-y-4 my-4">
             {/* R2 Download */}
             <div
@@ -279,7 +454,7 @@ export function BepInExInstallDialog({
             >
               <FileArchive className="h-5 w-5 mt-0.5 text-primary" />
               <div className="flex-1">
-                <div className="font-medium">Use local zip file</div>
+                <div className="font-medium">Use local archive</div>
                 <div className="text-sm text-muted-foreground">
                   Select a BepInEx pack you've already downloaded.
                 </div>
@@ -349,13 +524,18 @@ export function BepInExInstallDialog({
         <AlertDialogFooter>
           <AlertDialogCancel
             onClick={handleCancel}
-            disabled={installing && !canCancel}
+            disabled={installing && !canCancel && !extracting}
           >
             {downloading && canCancel ? (
               <>
                 <X className="mr-2 h-4 w-4" />
                 Cancel Download
               </>
+            ) : extracting ? (
+              <>
+                <X className="mr-2 h-4 w-4" />
+                Cancel Install
+              </>
             ) : (
               "Cancel"
             )}
@@ -369,7 +549,7 @@ export function BepInExInstallDialog({
           >
             {installing || downloading
               ? "Installing..."
-              : showConfirm
+              : pack
               ? "Confirm & Install"
               : "Install"}
           </Button>
@@ -0,0 +1,128 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { confirm } from "@tauri-apps/plugin-dialog";
+import { toast } from "sonner";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Badge } from "@/components/ui/badge";
+import { Stethoscope, Wrench } from "lucide-react";
+import type { DiagnosisFinding, DiagnosisReport, DiagnosisSeverity, Fm26Installation, FixAction } from "@/types";
+
+interface DiagnosisCardProps {
+  installation: Fm26Installation;
+  onInstallPack: () => void;
+  onFixed: () => void;
+}
+
+const SEVERITY_BADGES: Record<DiagnosisSeverity, { label: string; variant: "success" | "secondary" | "warning" | "destructive" }> = {
+  ok: { label: "OK", variant: "success" },
+  info: { label: "Info", variant: "secondary" },
+  warning: { label: "Warning", variant: "warning" },
+  error: { label: "Error", variant: "destructive" },
+};
+
+const FIX_LABELS: Record<FixAction["kind"], string> = {
+  install_pack: "Reinstall Pack",
+  apply_proton_launch_options: "Set Launch Options",
+  remove_team_mappings: "Remove Mappings",
+  disable_replace_all_stadiums: "Turn Off Replace All",
+  remove_audio_mappings: "Remove Mappings",
+  set_aside_config: "Reset Config",
+};
+
+export function DiagnosisCard({ installation, onInstallPack, onFixed }: DiagnosisCardProps) {
+  const [report, setReport] = useState<DiagnosisReport | null>(null);
+  const [isRunning, setIsRunning] = useState(false);
+  const [fixing, setFixing] = useState<number | null>(null);
+
+  const runDiagnosis = async () => {
+    setIsRunning(true);
+    try {
+      const result = await invoke<DiagnosisReport>("diagnose_install", { installId: installation.id });
+      setReport(result);
+    } catch (err) {
+      toast.error("Failed to diagnose installation", { description: String(err) });
+    } finally {
+      setIsRunning(false);
+    }
+  };
+
+  useEffect(() => {
+    runDiagnosis();
+  }, [installation]);
+
+  const handleFix = async (finding: DiagnosisFinding, index: number) => {
+    if (!finding.fix) return;
+    if (finding.fix.kind === "install_pack") {
+      onInstallPack();
+      return;
+    }
+
+    const confirmed = await confirm(`${FIX_LABELS[finding.fix.kind]}: ${finding.message}?`, {
+      title: "Apply Fix",
+      kind: "warning",
+    });
+    if (!confirmed) return;
+
+    setFixing(index);
+    try {
+      await invoke("apply_fix", { installId: installation.id, fix: finding.fix });
+      toast.success("Fix applied");
+      // Reloading the installation re-runs the checks
+      onFixed();
+    } catch (err) {
+      toast.error("Failed to apply fix", { description: String(err) });
+    } finally {
+      setFixing(null);
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center justify-between">
+          <span>Installation Doctor</span>
+          <Button variant="outline" size="sm" onClick={runDiagnosis} disabled={isRunning}>
+            <Stethoscope className="h-4 w-4 mr-2" />
+            {isRunning ? "Checking..." : "Run Checks"}
+          </Button>
+        </CardTitle>
+        <CardDescription>
+          {report
+            ? `${report.errors} errors, ${report.warnings} warnings`
+            : "Checks BepInEx, plugins, configs, mappings and the game log"}
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {report?.findings.map((finding, index) => (
+          <div key={index} className="flex items-start justify-between gap-4 border-b pb-3 last:border-b-0">
+            <div className="space-y-1 min-w-0">
+              <div className="flex items-center gap-2">
+                <Badge variant={SEVERITY_BADGES[finding.severity].variant}>
+                  {SEVERITY_BADGES[finding.severity].label}
+                </Badge>
+                <span className="text-sm font-medium">{finding.message}</span>
+              </div>
+              {finding.details.map((detail, i) => (
+                <p key={i} className="text-xs text-muted-foreground font-mono truncate">
+                  {detail}
+                </p>
+              ))}
+            </div>
+            {finding.fix && (
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={() => handleFix(finding, index)}
+                disabled={fixing !== null}
+              >
+                <Wrench className="h-4 w-4 mr-2" />
+                {fixing === index ? "Fixing..." : FIX_LABELS[finding.fix.kind]}
+              </Button>
+            )}
+          </div>
+        ))}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,78 @@
+import { toast } from "sonner";
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Badge } from "@/components/ui/badge";
+import { Play } from "lucide-react";
+import { useGameSession } from "@/hooks/useGameSession";
+import type { Fm26Installation, GameSessionSummary } from "@/types";
+
+interface GameSessionCardProps {
+  installation: Fm26Installation;
+  onSessionEnd: () => void;
+}
+
+function describeSession(session: GameSessionSummary): string {
+  if (!session.game_detected) return "FM26 did not start";
+  if (!session.log_updated) return "BepInEx did not run this session";
+  return `${session.plugins_loaded.length} plugins, ${session.stadium_injections} stadium injections, ${session.error_count} errors`;
+}
+
+export function GameSessionCard({ installation, onSessionEnd }: GameSessionCardProps) {
+  const { isRunning, lastSession, launch, stopWatching } = useGameSession((summary) => {
+    const description = describeSession(summary);
+    if (summary.error_count > 0 || !summary.log_updated) {
+      toast.warning("FM26 session ended", { description });
+    } else {
+      toast.success("FM26 session ended", { description });
+    }
+    onSessionEnd();
+  });
+
+  const handleLaunch = async () => {
+    try {
+      const method = await launch(installation.id);
+      toast.info(method === "steam" ? "Starting FM26 through Steam" : "Starting FM26");
+    } catch (err) {
+      toast.error("Failed to launch FM26", { description: String(err) });
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center justify-between">
+          <span>Play</span>
+          {isRunning && <Badge variant="success">Running</Badge>}
+        </CardTitle>
+        <CardDescription>
+          Launch FM26 and get a summary of the session's log when it closes
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <Button onClick={handleLaunch} disabled={isRunning} className="w-full">
+          <Play className="mr-2 h-4 w-4" />
+          {isRunning ? "FM26 is running..." : "Launch FM26"}
+        </Button>
+        {isRunning && (
+          <Button variant="outline" size="sm" onClick={stopWatching} className="w-full">
+            Stop watching
+          </Button>
+        )}
+
+        {lastSession && (
+          <div className="space-y-2 text-sm">
+            <p className="font-medium">Last session: {describeSession(lastSession)}</p>
+            {lastSession.plugins_loaded.length > 0 && (
+              <p className="text-muted-foreground">Loaded {lastSession.plugins_loaded.join(", ")}</p>
+            )}
+            {lastSession.errors.map((line, i) => (
+              <p key={i} className="text-xs text-muted-foreground font-mono truncate">
+                {line}
+              </p>
+            ))}
+          </div>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,152 @@
+import { useState, useEffect } from "react";
+import {
+  AlertDialog,
+  AlertDialogAction,
+  AlertDialogCancel,
+  AlertDialogContent,
+  AlertDialogDescription,
+  AlertDialogFooter,
+  AlertDialogHeader,
+  AlertDialogTitle,
+} from "@/components/ui/alert-dialog";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { describePlan } from "@/lib/utils";
+import type { StadiumPackReview } from "@/hooks/useStadiumPackInstall";
+import type { ConflictAction, ConflictResolution } from "@/types";
+
+interface StadiumPackDialogProps {
+  review: StadiumPackReview | null;
+  onLayoutPrefixChange: (prefix: string) => void;
+  onConfirm: (resolutions: ConflictResolution[]) => void;
+  onCancel: () => void;
+}
+
+function formatSize(bytes: number): string {
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+export function StadiumPackDialog({
+  review,
+  onLayoutPrefixChange,
+  onConfirm,
+  onCancel,
+}: StadiumPackDialogProps) {
+  const [actions, setActions] = useState<Record<string, ConflictAction>>({});
+  const conflicts = review?.conflicts;
+
+  // Default every conflict to keeping the installed bundle
+  useEffect(() => {
+    setActions(
+      Object.fromEntries((conflicts ?? []).map((c) => [c.file, "keep"]))
+    );
+  }, [conflicts]);
+
+  const handleConfirm = () => {
+    onConfirm(
+      (conflicts ?? []).map((c) => ({
+        file: c.file,
+        action: actions[c.file] ?? "keep",
+      }))
+    );
+  };
+
+  return (
+    <AlertDialog
+      open={review !== null}
+      onOpenChange={(open) => !open && onCancel()}
+    >
+      <AlertDialogContent className="max-w-lg">
+        <AlertDialogHeader>
+          <AlertDialogTitle>Install Stadium Pack</AlertDialogTitle>
+          <AlertDialogDescription>
+            Bundles go to CustomStadium; mappings and configs are merged into
+            your own, keeping every setting you have already changed.
+          </AlertDialogDescription>
+        </AlertDialogHeader>
+
+        {review && (
+          <div className="space-y-1">
+            <Label htmlFor="stadiumLayoutPrefix" className="text-xs">
+              Bundles are taken from
+            </Label>
+            <Input
+              id="stadiumLayoutPrefix"
+              placeholder="(archive root)"
+              value={review.layoutPrefix ?? review.layout.strip_prefix}
+              onChange={(e) => onLayoutPrefixChange(e.target.value)}
+              className="text-sm h-8"
+            />
+            <div className="text-xs text-muted-foreground">
+              {review.error ?? (review.plan ? describePlan(review.plan) : "Planning...")}
+            </div>
+          </div>
+        )}
+
+        {(conflicts ?? []).length > 0 && (
+          <div className="space-y-2">
+            <div className="text-sm">
+              These bundles differ from the ones you have. Choose what to do
+              with each; identical bundles are skipped.
+            </div>
+            <div className="space-y-2 max-h-64 overflow-y-auto">
+              {(conflicts ?? []).map((conflict) => (
+                <div
+                  key={conflict.file}
+                  className="flex items-center justify-between gap-2"
+                >
+                  <div className="min-w-0">
+                    <div className="font-mono text-sm truncate">
+                      {conflict.file}
+                    </div>
+                    <div className="text-xs text-muted-foreground">
+                      {formatSize(conflict.existing_size)} installed,{" "}
+                      {formatSize(conflict.incoming_size)} in pack
+                    </div>
+                  </div>
+                  <Select
+                    value={actions[conflict.file] ?? "keep"}
+                    onValueChange={(value) =>
+                      setActions((prev) => ({
+                        ...prev,
+                        [conflict.file]: value as ConflictAction,
+                      }))
+                    }
+                  >
+                    <SelectTrigger className="w-44">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="keep">Keep mine</SelectItem>
+                      <SelectItem value="overwrite">Overwrite</SelectItem>
+                      <SelectItem value="rename">
+                        Rename to {conflict.rename_to}
+                      </SelectItem>
+                    </SelectContent>
+                  </Select>
+                </div>
+              ))}
+            </div>
+          </div>
+        )}
+
+        <AlertDialogFooter>
+          <AlertDialogCancel onClick={onCancel}>Cancel</AlertDialogCancel>
+          <AlertDialogAction
+            onClick={handleConfirm}
+            disabled={!review?.plan || review.error !== null}
+          >
+            Install
+          </AlertDialogAction>
+        </AlertDialogFooter>
+      </AlertDialogContent>
+    </AlertDialog>
+  );
+}
@@ -1,6 +1,7 @@
 import { useState, useCallback, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { formatBytes } from "@/lib/utils";
 
 interface DownloadProgress {
   downloaded: number;
@@ -19,14 +20,6 @@ interface DownloadState {
   canCancel: boolean;
 }
 
-function formatBytes(bytes: number): string {
-  if (bytes === 0) return "0 B";
-  const k = 1024;
-  const sizes = ["B", "KB", "MB", "GB"];
-  const i = Math.floor(Math.log(bytes) / Math.log(k));
-  return parseFloat((bytes / Math.pow(k, i)).toFixed(1)) + " " + sizes[i];
-}
-
 function formatSpeed(bytesPerSecond: number): string {
   return formatBytes(bytesPerSecond) + "/s";
 }
@@ -0,0 +1,47 @@
+import { useState, useCallback, useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import type { GameSessionSummary, LaunchMethod } from "@/types";
+
+/** Launches FM26 and tracks the session until the game-exited event */
+export function useGameSession(onExit?: (summary: GameSessionSummary) => void) {
+  const [isRunning, setIsRunning] = useState(false);
+  const [lastSession, setLastSession] = useState<GameSessionSummary | null>(null);
+  const onExitRef = useRef(onExit);
+  onExitRef.current = onExit;
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+
+    const setupListener = async () => {
+      unlisten = await listen<GameSessionSummary>("game-exited", (event) => {
+        setIsRunning(false);
+        setLastSession(event.payload);
+        onExitRef.current?.(event.payload);
+      });
+    };
+
+    setupListener();
+    // A session started before this view mounted is still being watched
+    invoke<boolean>("is_game_session_active").then(setIsRunning).catch(() => {});
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  const launch = useCallback(async (installId: string) => {
+    const method = await invoke<LaunchMethod>("launch_game", { installId });
+    setIsRunning(true);
+    return method;
+  }, []);
+
+  // Ends the watch of a Steam session whose game the app can't see exit
+  const stopWatching = useCallback(async () => {
+    await invoke("stop_watching_game");
+  }, []);
+
+  return { isRunning, lastSession, launch, stopWatching };
+}
@@ -0,0 +1,43 @@
+import { useState, useCallback, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import type { InstallProgress } from "@/types";
+
+/** Error an install returns once the user cancelled it */
+export const INSTALL_CANCELLED = "Install cancelled";
+
+/** Tracks install-progress events from the running pack install */
+export function useInstallProgress() {
+  const [progress, setProgress] = useState<InstallProgress | null>(null);
+
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+
+    const setupListener = async () => {
+      unlisten = await listen<InstallProgress>("install-progress", (event) => {
+        setProgress(event.payload);
+      });
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  const resetProgress = useCallback(() => setProgress(null), []);
+
+  // The install rolls back and then fails with INSTALL_CANCELLED
+  const cancelInstall = useCallback(async () => {
+    try {
+      await invoke("cancel_install");
+    } catch (err) {
+      console.error("Failed to cancel install:", err);
+    }
+  }, []);
+
+  return { progress, resetProgress, cancelInstall };
+}
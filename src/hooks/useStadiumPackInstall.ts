@@ -0,0 +1,158 @@
+import { useState, useCallback, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { open as openDialog } from "@tauri-apps/plugin-dialog";
+import { toast } from "sonner";
+import { describeStadiumPack } from "@/lib/utils";
+import { INSTALL_CANCELLED, useInstallProgress } from "@/hooks/useInstallProgress";
+import type {
+  ArchiveLayout,
+  BundleConflict,
+  ConflictResolution,
+  Fm26Installation,
+  InstallPlan,
+  StadiumPackReport,
+} from "@/types";
+
+interface PendingInstall {
+  path: string;
+  layout: ArchiveLayout;
+}
+
+/** What the user confirms before a stadium pack installs */
+export interface StadiumPackReview {
+  layout: ArchiveLayout;
+  layoutPrefix: string | null; // user override of layout.strip_prefix
+  plan: InstallPlan | null;
+  conflicts: BundleConflict[];
+  error: string | null; // why the pack can't be planned with this prefix
+}
+
+/** Picks a stadium pack, shows its layout, plan and conflicting bundles, then installs it */
+export function useStadiumPackInstall(
+  installation: Fm26Installation | null,
+  onInstalled?: () => void | Promise<void>
+) {
+  const [isInstalling, setIsInstalling] = useState(false);
+  const [pending, setPending] = useState<PendingInstall | null>(null);
+  const [layoutPrefix, setLayoutPrefix] = useState<string | null>(null);
+  const [plan, setPlan] = useState<InstallPlan | null>(null);
+  const [conflicts, setConflicts] = useState<BundleConflict[]>([]);
+  const [reviewError, setReviewError] = useState<string | null>(null);
+  const { progress, resetProgress, cancelInstall } = useInstallProgress();
+
+  const install = useCallback(
+    async (
+      path: string,
+      prefix: string | null,
+      resolutions: ConflictResolution[]
+    ) => {
+      if (!installation) return;
+
+      resetProgress();
+      setIsInstalling(true);
+      try {
+        const report = await invoke<StadiumPackReport>(
+          "install_custom_stadiums_pack",
+          {
+            zipPath: path,
+            layoutPrefix: prefix,
+            resolutions,
+            installId: installation.id,
+          }
+        );
+
+        toast.success("Custom stadiums installed!", {
+          description: describeStadiumPack(report),
+        });
+        await onInstalled?.();
+      } catch (err) {
+        if (String(err) !== INSTALL_CANCELLED) {
+          toast.error("Failed to install stadiums", { description: String(err) });
+        }
+      } finally {
+        setIsInstalling(false);
+      }
+    },
+    [installation, onInstalled, resetProgress]
+  );
+
+  const pickAndInstall = useCallback(async () => {
+    if (!installation) return;
+
+    try {
+      const selected = await openDialog({
+        multiple: false,
+        title: "Select Custom Stadiums Archive",
+        filters: [
+          {
+            name: "Pack Archives",
+            extensions: ["zip", "7z", "gz", "tgz", "xz", "txz"],
+          },
+        ],
+      });
+      if (!selected || typeof selected !== "string") return;
+
+      const layout = await invoke<ArchiveLayout>("detect_archive_layout", {
+        zipPath: selected,
+        kind: "custom_stadiums",
+        installId: installation.id,
+      });
+      setLayoutPrefix(null);
+      setPending({ path: selected, layout });
+    } catch (err) {
+      toast.error("Failed to read stadium pack", { description: String(err) });
+    }
+  }, [installation]);
+
+  // The plan and conflicts follow the pack root the user confirms
+  useEffect(() => {
+    setPlan(null);
+    setConflicts([]);
+    setReviewError(null);
+    if (!pending || !installation) return;
+
+    let stale = false;
+    const args = {
+      zipPath: pending.path,
+      layoutPrefix,
+      installId: installation.id,
+    };
+    invoke<InstallPlan>("plan_install", { ...args, kind: "custom_stadiums" })
+      .then((result) => !stale && setPlan(result))
+      .catch((err) => !stale && setReviewError(String(err)));
+    invoke<BundleConflict[]>("check_stadium_conflicts", args)
+      .then((result) => !stale && setConflicts(result))
+      .catch((err) => !stale && setReviewError(String(err)));
+    return () => {
+      stale = true;
+    };
+  }, [pending, layoutPrefix, installation]);
+
+  const confirm = useCallback(
+    (resolutions: ConflictResolution[]) => {
+      const path = pending?.path;
+      setPending(null);
+      if (path) {
+        install(path, layoutPrefix, resolutions);
+      }
+    },
+    [pending, layoutPrefix, install]
+  );
+
+  const cancelReview = useCallback(() => setPending(null), []);
+
+  const review: StadiumPackReview | null = pending
+    ? { layout: pending.layout, layoutPrefix, plan, conflicts, error: reviewError }
+    : null;
+
+  return {
+    isInstalling,
+    percent: Math.round(progress?.percent ?? 0),
+    cancelInstall,
+    pickAndInstall,
+    review,
+    setLayoutPrefix,
+    confirm,
+    cancelReview,
+  };
+}
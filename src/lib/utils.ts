@@ -1,6 +1,87 @@
 import { type ClassValue, clsx } from "clsx";
 import { twMerge } from "tailwind-merge";
+import type {
+  InstallPlan,
+  InvalidName,
+  PlannedAction,
+  RouteDestination,
+  StadiumPackReport,
+} from "@/types";
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
 }
+
+export function formatBytes(bytes: number): string {
+  if (bytes === 0) return "0 B";
+  const k = 1024;
+  const sizes = ["B", "KB", "MB", "GB"];
+  const i = Math.floor(Math.log(bytes) / Math.log(k));
+  return parseFloat((bytes / Math.pow(k, i)).toFixed(1)) + " " + sizes[i];
+}
+
+const PLAN_LABELS: Record<PlannedAction, string> = {
+  new: "new",
+  overwrite_identical: "identical",
+  overwrite_different: "changed",
+  unchanged: "unchanged",
+  moved_to_backup: "moved to backup",
+  merged: "merged",
+};
+
+/** File counts per planned action, e.g. "12 new, 3 changed" */
+export function describePlan(plan: InstallPlan): string {
+  return plan.totals.map((t) => `${t.files} ${PLAN_LABELS[t.action]}`).join(", ");
+}
+
+const DESTINATION_LABELS: Record<RouteDestination, string> = {
+  custom_stadium: "CustomStadium",
+  stadium_injection: "StadiumInjection",
+  audio_inject: "AudioInject",
+  config: "BepInEx/config",
+};
+
+/** One line per destination a stadium pack install wrote to */
+export function describeStadiumPack(report: StadiumPackReport): string {
+  const lines = report.destinations
+    .filter((d) => d.files > 0)
+    .map((d) => {
+      const merged =
+        d.mappings_added + d.mappings_kept > 0
+          ? ` (${d.mappings_added} mappings added, ${d.mappings_kept} already set)`
+          : "";
+      return `${d.files} file(s) to ${DESTINATION_LABELS[d.destination]}${merged}`;
+    });
+
+  if (report.identical.length > 0) {
+    lines.push(`${report.identical.length} bundle(s) already installed`);
+  }
+  if (report.kept.length > 0) {
+    lines.push(`${report.kept.length} bundle(s) kept as they were`);
+  }
+  for (const rename of report.renamed) {
+    lines.push(`${rename.from} installed as ${rename.to}`);
+  }
+  if (report.skipped.length > 0) {
+    lines.push(`${report.skipped.length} file(s) skipped`);
+  }
+
+  return lines.join("\n");
+}
+
+/** Names a command rejected, or an empty list for any other error */
+export function invalidNames(err: unknown): InvalidName[] {
+  if (typeof err === "object" && err !== null && "invalid" in err) {
+    return (err as { invalid: InvalidName[] }).invalid;
+  }
+  return [];
+}
+
+/** Readable text for a command error, including rejected names */
+export function describeError(err: unknown): string {
+  const invalid = invalidNames(err);
+  if (invalid.length > 0) {
+    return invalid.map((i) => i.message).join("\n");
+  }
+  return String(err);
+}
@@ -11,10 +11,9 @@ import { toast } from "sonner";
 import { RefreshCw, AlertCircle, Plus, Trash2, Save, Music } from "lucide-react";
 import { Tooltip, TooltipContent, TooltipTrigger } from "@/components/ui/tooltip";
 import { ComingSoonOverlay } from "@/components/ComingSoonOverlay";
+import { describeError, invalidNames } from "@/lib/utils";
 import type { Fm26Installation, AudioMapping, AudioFolderStatus } from "@/types";
 
-const STORAGE_KEY = "fm26_install_path";
-
 export function AudioTab() {
   const [installation, setInstallation] = useState<Fm26Installation | null>(null);
   const [folders, setFolders] = useState<string[]>([]);
@@ -25,25 +24,23 @@ export function AudioTab() {
   const [error, setError] = useState<string | null>(null);
   const [isLoading, setIsLoading] = useState(false);
   const [isSaving, setIsSaving] = useState(false);
+  const [invalidRows, setInvalidRows] = useState<Set<number>>(new Set());
 
-  // Load installation on mount
+  // Load the default installation on mount
   useEffect(() => {
-    const savedPath = localStorage.getItem(STORAGE_KEY);
-    if (savedPath) {
-      loadInstallation(savedPath);
-    }
+    loadInstallation();
   }, []);
 
-  const loadInstallation = async (path: string) => {
+  const loadInstallation = async () => {
     setIsLoading(true);
     setError(null);
 
     try {
-      const install = await invoke<Fm26Installation>("inspect_fm26_install", {
-        rootPath: path,
-      });
+      const install = await invoke<Fm26Installation | null>("get_default_install");
       setInstallation(install);
-      await loadData(install);
+      if (install) {
+        await loadData(install);
+      }
     } catch (err) {
       setError(String(err));
       setInstallation(null);
@@ -55,8 +52,8 @@ export function AudioTab() {
   const loadData = async (install: Fm26Installation) => {
     try {
       const [folderList, mappingList] = await Promise.all([
-        invoke<string[]>("list_audio_folders", { install }),
-        invoke<AudioMapping[]>("read_audio_mappings", { install }),
+        invoke<string[]>("list_audio_folders", { installId: install.id }),
+        invoke<AudioMapping[]>("read_audio_mappings", { installId: install.id }),
       ]);
       setFolders(folderList);
       setMappings(mappingList);
@@ -83,12 +80,12 @@ export function AudioTab() {
 
     try {
       const status = await invoke<AudioFolderStatus>("inspect_audio_folder", {
-        install: installation,
+        installId: installation.id,
         folderName,
       });
       setFolderStatus(status);
     } catch (err) {
-      setError(String(err));
+      setError(describeError(err));
       setFolderStatus(null);
     }
   };
@@ -97,6 +94,7 @@ export function AudioTab() {
     const newMappings = [...mappings];
     newMappings[index] = { ...newMappings[index], [field]: value };
     setMappings(newMappings);
+    setInvalidRows(new Set());
   };
 
   const handleAddMapping = () => {
@@ -109,6 +107,7 @@ export function AudioTab() {
 
   const handleRemoveMapping = (index: number) => {
     setMappings(mappings.filter((_, i) => i !== index));
+    setInvalidRows(new Set());
   };
 
   const handleSave = async () => {
@@ -119,13 +118,18 @@ export function AudioTab() {
 
     try {
       await invoke("write_audio_mappings", {
-        install: installation,
+        installId: installation.id,
         mappings,
       });
       toast.success("Audio mappings saved!");
       setOriginalMappings(mappings);
+      setInvalidRows(new Set());
     } catch (err) {
-      toast.error("Failed to save audio mappings", { description: String(err) });
+      // Highlight the rows the backend rejected
+      setInvalidRows(
+        new Set(invalidNames(err).flatMap((i) => (i.row === null ? [] : [i.row])))
+      );
+      toast.error("Failed to save audio mappings", { description: describeError(err) });
     } finally {
       setIsSaving(false);
     }
@@ -133,6 +137,7 @@ export function AudioTab() {
 
   const handleRevert = () => {
     setMappings(originalMappings);
+    setInvalidRows(new Set());
     setError(null);
   };
 
@@ -189,7 +194,10 @@ export function AudioTab() {
                 </TableHeader>
                 <TableBody>
                   {mappings.map((mapping, index) => (
-                    <TableRow key={index}>
+                    <TableRow
+                      key={index}
+                      className={invalidRows.has(index) ? "bg-destructive/10" : undefined}
+                    >
                       <TableCell>
                         <Input
                           value={mapping.team_key}
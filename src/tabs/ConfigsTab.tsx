@@ -14,8 +14,6 @@ import { RefreshCw, AlertCircle, Save, Settings, Volume2, Users, LayoutGrid } fr
 import { ComingSoonOverlay } from "@/components/ComingSoonOverlay";
 import type { Fm26Installation, StadiumInjectionConfig, BundleInfo, AudioInjectConfig, CrowdInjectConfig, AdboardsConfig } from "@/types";
 
-const STORAGE_KEY = "fm26_install_path";
-
 export function ConfigsTab() {
   const [installation, setInstallation] = useState<Fm26Installation | null>(null);
   const [stadiumConfig, setStadiumConfig] = useState<StadiumInjectionConfig | null>(null);
@@ -31,24 +29,21 @@ export function ConfigsTab() {
   const [isLoading, setIsLoading] = useState(false);
   const [isSaving, setIsSaving] = useState(false);
 
-  // Load installation on mount
+  // Load the default installation on mount
   useEffect(() => {
-    const savedPath = localStorage.getItem(STORAGE_KEY);
-    if (savedPath) {
-      loadInstallation(savedPath);
-    }
+    loadInstallation();
   }, []);
 
-  const loadInstallation = async (path: string) => {
+  const loadInstallation = async () => {
     setIsLoading(true);
     setError(null);
 
     try {
-      const install = await invoke<Fm26Installation>("inspect_fm26_install", {
-        rootPath: path,
-      });
+      const install = await invoke<Fm26Installation | null>("get_default_install");
       setInstallation(install);
-      await loadData(install);
+      if (install) {
+        await loadData(install);
+      }
     } catch (err) {
       setError(String(err));
       setInstallation(null);
@@ -60,11 +55,11 @@ export function ConfigsTab() {
   const loadData = async (install: Fm26Installation) => {
     try {
       const [stadiumCfg, audioCfg, crowdCfg, adboardsCfg, bundleList] = await Promise.all([
-        invoke<StadiumInjectionConfig>("read_stadium_injection_config", { install }),
-        invoke<AudioInjectConfig>("read_audio_inject_config", { install }),
-        invoke<CrowdInjectConfig>("read_crowd_inject_config", { install }),
-        invoke<AdboardsConfig>("read_adboards_config", { install }),
-        invoke<BundleInfo[]>("list_bundles", { install }),
+        invoke<StadiumInjectionConfig>("read_stadium_injection_config", { installId: install.id }),
+        invoke<AudioInjectConfig>("read_audio_inject_config", { installId: install.id }),
+        invoke<CrowdInjectConfig>("read_crowd_inject_config", { installId: install.id }),
+        invoke<AdboardsConfig>("read_adboards_config", { installId: install.id }),
+        invoke<BundleInfo[]>("list_bundles", { installId: install.id }),
       ]);
       setStadiumConfig(stadiumCfg);
       setOriginalStadiumConfig(stadiumCfg);
@@ -98,16 +93,16 @@ export function ConfigsTab() {
       const promises = [];
 
       if (hasStadiumChanges && stadiumConfig) {
-        promises.push(invoke("write_stadium_injection_config", { install: installation, config: stadiumConfig }));
+        promises.push(invoke("write_stadium_injection_config", { installId: installation.id, config: stadiumConfig }));
       }
       if (hasAudioChanges && audioConfig) {
-        promises.push(invoke("write_audio_inject_config", { install: installation, config: audioConfig }));
+        promises.push(invoke("write_audio_inject_config", { installId: installation.id, config: audioConfig }));
       }
       if (hasCrowdChanges && crowdConfig) {
-        promises.push(invoke("write_crowd_inject_config", { install: installation, config: crowdConfig }));
+        promises.push(invoke("write_crowd_inject_config", { installId: installation.id, config: crowdConfig }));
       }
       if (hasAdboardsChanges && adboardsConfig) {
-        promises.push(invoke("write_adboards_config", { install: installation, config: adboardsConfig }));
+        promises.push(invoke("write_adboards_config", { installId: installation.id, config: adboardsConfig }));
       }
 
       await Promise.all(promises);
@@ -1,6 +1,6 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { open } from "@tauri-apps/plugin-dialog";
+import { ask, confirm, open } from "@tauri-apps/plugin-dialog";
 import { toast } from "sonner";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
@@ -9,48 +9,113 @@ import { Alert, AlertDescription, AlertTitle } from "@/components/ui/alert";
 import { Badge } from "@/components/ui/badge";
 import { Table, TableBody, TableCell, TableHead, TableHeader, TableRow } from "@/components/ui/table";
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
-import { FolderOpen, Download, RefreshCw, AlertCircle, CheckCircle2, FileArchive, Search } from "lucide-react";
+import { FolderOpen, Download, RefreshCw, AlertCircle, CheckCircle2, FileArchive, Search, Trash2, Pencil } from "lucide-react";
 import { Tooltip, TooltipContent, TooltipTrigger } from "@/components/ui/tooltip";
 import { BepInExInstallDialog } from "@/components/BepInExInstallDialog";
-import type { Fm26Installation, PluginStatus } from "@/types";
+import { BackupsCard } from "@/components/BackupsCard";
+import { DiagnosisCard } from "@/components/DiagnosisCard";
+import { GameSessionCard } from "@/components/GameSessionCard";
+import { StadiumPackDialog } from "@/components/StadiumPackDialog";
+import { useStadiumPackInstall } from "@/hooks/useStadiumPackInstall";
+import type {
+  DetectedInstall,
+  Fm26Installation,
+  GameStore,
+  GameVersionCheck,
+  InstallEvidence,
+  InstallRegistry,
+  PluginState,
+  PluginStatus,
+  ProtonLaunchOptions,
+  RegisteredInstall,
+  UninstallReport,
+} from "@/types";
 
-const STORAGE_KEY = "fm26_install_path";
+// Where earlier versions saved the one selected install path
+const LEGACY_STORAGE_KEY = "fm26_install_path";
+
+const STORE_LABELS: Record<GameStore, string> = {
+  steam: "Steam",
+  epic: "Epic",
+  manual: "Manual",
+};
+
+const EVIDENCE_LABELS: Record<InstallEvidence, string> = {
+  fm_exe: "fm.exe / fm.app",
+  unity_data_folder: "Unity data folder",
+  unity_player_dll: "UnityPlayer library",
+  game_assembly_dll: "GameAssembly library",
+  steam_app_id: "Steam app id",
+};
+
+const PLUGIN_STATE_BADGES: Record<PluginState, { label: string; variant: "success" | "destructive" | "warning" }> = {
+  installed: { label: "Installed", variant: "success" },
+  missing: { label: "Missing", variant: "destructive" },
+  proton_blocked: { label: "Won't load under Proton", variant: "warning" },
+  missing_dependency: { label: "Missing dependency", variant: "warning" },
+};
+
+// Below this the folder is probably not FM26
+const LOW_CONFIDENCE = 50;
 
 export function GameTab() {
   const [installPath, setInstallPath] = useState<string>("");
   const [installation, setInstallation] = useState<Fm26Installation | null>(null);
+  const [registry, setRegistry] = useState<InstallRegistry | null>(null);
+  const [labelDraft, setLabelDraft] = useState("");
   const [plugins, setPlugins] = useState<PluginStatus[]>([]);
-  const [detectedPaths, setDetectedPaths] = useState<string[]>([]);
+  const [detectedPaths, setDetectedPaths] = useState<DetectedInstall[]>([]);
   const [isDetecting, setIsDetecting] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [probe, setProbe] = useState<DetectedInstall | null>(null);
+  const [versionCheck, setVersionCheck] = useState<GameVersionCheck | null>(null);
   const [isLoading, setIsLoading] = useState(false);
   const [showInstallDialog, setShowInstallDialog] = useState(false);
-  const [isInstallingStadiums, setIsInstallingStadiums] = useState(false);
+  const [isApplyingProton, setIsApplyingProton] = useState(false);
+  const [isUninstalling, setIsUninstalling] = useState(false);
+  const stadiumPack = useStadiumPackInstall(installation);
 
-  // Load saved path on mount, or try auto-detect
+  // Load saved installs on mount, or try auto-detect
   useEffect(() => {
-    const savedPath = localStorage.getItem(STORAGE_KEY);
-    if (savedPath) {
-      setInstallPath(savedPath);
-      validateAndLoadInstallation(savedPath);
-    } else {
-      // No saved path, try auto-detection
-      handleAutoDetect();
-    }
+    loadRegistry(true);
   }, []);
 
+  const loadRegistry = async (initial: boolean = false) => {
+    try {
+      let saved = await invoke<InstallRegistry>("list_installs");
+
+      // Carry over the single path saved by earlier versions
+      const legacyPath = localStorage.getItem(LEGACY_STORAGE_KEY);
+      if (initial && legacyPath && saved.installs.length === 0) {
+        await invoke<RegisteredInstall>("add_install", { rootPath: legacyPath });
+        localStorage.removeItem(LEGACY_STORAGE_KEY);
+        saved = await invoke<InstallRegistry>("list_installs");
+      }
+      setRegistry(saved);
+
+      if (!initial) return;
+      if (saved.default_id) {
+        await loadInstallation(saved.default_id);
+      } else {
+        // Nothing saved yet, try auto-detection
+        await handleAutoDetect();
+      }
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
   const handleAutoDetect = async () => {
     setIsDetecting(true);
     setError(null);
 
     try {
-      const paths = await invoke<string[]>("detect_fm26_paths");
-      setDetectedPaths(paths);
+      const detected = await invoke<DetectedInstall[]>("detect_fm26_paths");
+      setDetectedPaths(detected);
 
       // If exactly one path found, auto-select it
-      if (paths.length === 1) {
-        setInstallPath(paths[0]);
-        await validateAndLoadInstallation(paths[0]);
+      if (detected.length === 1) {
+        await registerAndLoad(detected[0].path, detected[0].store);
       }
     } catch (err) {
       // Silent fail for auto-detect - user can browse manually
@@ -60,35 +125,104 @@ export function GameTab() {
     }
   };
 
-  const validateAndLoadInstallation = async (path: string) => {
+  // Saves a folder in the registry, makes it the default and opens it
+  const registerAndLoad = async (path: string, store?: GameStore) => {
     if (!path) return;
 
-    setIsLoading(true);
     setError(null);
-
     try {
-      const install = await invoke<Fm26Installation>("inspect_fm26_install", {
+      const added = await invoke<RegisteredInstall>("add_install", {
         rootPath: path,
+        store: store ?? null,
       });
+      await invoke("set_default_install", { installId: added.id });
+      await loadRegistry();
+      await loadInstallation(added.id);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const loadInstallation = async (installId: string) => {
+    setIsLoading(true);
+    setError(null);
+
+    try {
+      const install = await invoke<Fm26Installation>("get_install", { installId });
       setInstallation(install);
+      setInstallPath(install.root_path);
+
+      // Explain how well the folder matches FM26
+      setProbe(await invoke<DetectedInstall>("probe_fm26_install", { installId: install.id }));
 
       // Load plugin status
       const pluginStatus = await invoke<PluginStatus[]>("get_plugin_status", {
-        install,
+        installId: install.id,
       });
       setPlugins(pluginStatus);
 
-      // Save path to localStorage
-      localStorage.setItem(STORAGE_KEY, path);
+      // Warn if the game updated since BepInEx was installed
+      setVersionCheck(await invoke<GameVersionCheck>("check_game_version", { installId: install.id }));
     } catch (err) {
       setError(String(err));
       setInstallation(null);
+      setProbe(null);
+      setVersionCheck(null);
       setPlugins([]);
     } finally {
       setIsLoading(false);
     }
   };
 
+  const handleSelectInstall = async (installId: string) => {
+    try {
+      await invoke("set_default_install", { installId });
+      await loadRegistry();
+      await loadInstallation(installId);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleRenameInstall = async () => {
+    if (!installation || !labelDraft.trim()) return;
+
+    try {
+      await invoke("label_install", { installId: installation.id, label: labelDraft });
+      setLabelDraft("");
+      await loadRegistry();
+    } catch (err) {
+      toast.error("Failed to rename installation", { description: String(err) });
+    }
+  };
+
+  const handleRemoveInstall = async () => {
+    if (!installation) return;
+
+    const confirmed = await confirm(
+      "The installation is removed from this list only; nothing in the game folder is touched.",
+      { title: "Forget this installation?", kind: "warning" }
+    );
+    if (!confirmed) return;
+
+    try {
+      await invoke("remove_install", { installId: installation.id });
+      setInstallation(null);
+      setInstallPath("");
+      setProbe(null);
+      setVersionCheck(null);
+      setPlugins([]);
+
+      const saved = await invoke<InstallRegistry>("list_installs");
+      setRegistry(saved);
+      if (saved.default_id) {
+        await loadInstallation(saved.default_id);
+      }
+    } catch (err) {
+      toast.error("Failed to remove installation", { description: String(err) });
+    }
+  };
+
   const handleBrowse = async () => {
     try {
       const selected = await open({
@@ -98,8 +232,7 @@ export function GameTab() {
       });
 
       if (selected && typeof selected === "string") {
-        setInstallPath(selected);
-        await validateAndLoadInstallation(selected);
+        await registerAndLoad(selected);
       }
     } catch (err) {
       setError(String(err));
@@ -110,47 +243,86 @@ export function GameTab() {
     // Refresh plugin status after installation
     if (installation) {
       const pluginStatus = await invoke<PluginStatus[]>("get_plugin_status", {
-        install: installation,
+        installId: installation.id,
       });
       setPlugins(pluginStatus);
     }
+    // Pick up the new pack version
+    await loadRegistry();
   };
 
   const handleRefresh = async () => {
-    if (installPath) {
-      await validateAndLoadInstallation(installPath);
+    if (installPath && installPath !== installation?.root_path) {
+      await registerAndLoad(installPath);
+    } else if (installation) {
+      await invoke("revalidate_installs").catch(console.error);
+      await loadRegistry();
+      await loadInstallation(installation.id);
     }
   };
 
-  const handleInstallCustomStadiums = async () => {
+  const handleUninstall = async () => {
     if (!installation) return;
 
-    try {
-      const selected = await open({
-        multiple: false,
-        title: "Select Custom Stadiums Zip File",
-        filters: [{ name: "Zip Archives", extensions: ["zip"] }],
-      });
+    const confirmed = await confirm(
+      "This removes what the Stadium Pack installed and the loader files from the game folder.",
+      { title: "Uninstall Stadium Pack?", kind: "warning" }
+    );
+    if (!confirmed) return;
 
-      if (selected && typeof selected === "string") {
-        setIsInstallingStadiums(true);
-        setError(null);
+    const keepUserData = await ask(
+      "Export your configs, team mappings, custom stadiums and audio to a zip before removing them?",
+      { title: "Keep user data?" }
+    );
 
-        const filesExtracted = await invoke<number>("install_custom_stadiums_pack", {
-          zipPath: selected,
-          install: installation,
+    setIsUninstalling(true);
+    try {
+      const report = await invoke<UninstallReport>("uninstall_bepinex", {
+        installId: installation.id,
+        keepUserData,
+      });
+      if (report.failed.length > 0) {
+        toast.warning("Uninstalled with errors", { description: report.failed.join("\n") });
+      } else if (report.left_behind.length > 0) {
+        toast.warning("Stadium Pack uninstalled, other files kept", {
+          description: `${report.left_behind.length} files the pack did not install are still in BepInEx/, e.g. ${report.left_behind[0]}`,
+        });
+      } else {
+        toast.success("Stadium Pack uninstalled", {
+          description: report.export_path
+            ? `User data saved to ${report.export_path}`
+            : `${report.removed_files.length} loader files and the BepInEx folder removed`,
         });
-
-        toast.success("Custom stadiums installed!", { description: `${filesExtracted} files extracted` });
       }
+      await handleInstallComplete();
+    } catch (err) {
+      toast.error("Failed to uninstall", { description: String(err) });
+    } finally {
+      setIsUninstalling(false);
+    }
+  };
+
+  const handleApplyProtonLaunchOptions = async () => {
+    if (!installation) return;
+
+    setIsApplyingProton(true);
+    try {
+      const updated = await invoke<ProtonLaunchOptions[]>("apply_proton_launch_options", {
+        installId: installation.id,
+      });
+      toast.success("Steam launch options updated", {
+        description: `${updated.length} Steam user config${updated.length === 1 ? "" : "s"} updated (backups saved). Restart Steam if it was running.`,
+      });
+      await handleInstallComplete();
     } catch (err) {
-      toast.error("Failed to install stadiums", { description: String(err) });
+      toast.error("Failed to update launch options", { description: String(err) });
     } finally {
-      setIsInstallingStadiums(false);
+      setIsApplyingProton(false);
     }
   };
 
   const bepInExInstalled = plugins.length > 0 && plugins.some((p) => p.installed);
+  const protonBlocked = plugins.some((p) => p.state === "proton_blocked");
 
   return (
     <div className="space-y-4 pt-4">
@@ -163,6 +335,64 @@ export function GameTab() {
           </CardDescription>
         </CardHeader>
         <CardContent className="space-y-4">
+          {/* Saved installations */}
+          {registry && registry.installs.length > 0 && (
+            <div className="space-y-2">
+              <div className="flex gap-2">
+                <Select
+                  value={installation?.id ?? ""}
+                  onValueChange={handleSelectInstall}
+                >
+                  <SelectTrigger className="flex-1">
+                    <SelectValue placeholder="Select saved installation" />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {registry.installs.map((saved) => (
+                      <SelectItem key={saved.id} value={saved.id}>
+                        {saved.label} [{STORE_LABELS[saved.store]}]
+                        {saved.bepinex_version && ` - BepInEx ${saved.bepinex_version}`}
+                        {!saved.valid && " (folder missing)"}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+                <Tooltip>
+                  <TooltipTrigger asChild>
+                    <Button
+                      onClick={handleRemoveInstall}
+                      variant="outline"
+                      disabled={!installation}
+                    >
+                      <Trash2 className="h-4 w-4" />
+                    </Button>
+                  </TooltipTrigger>
+                  <TooltipContent>Forget this installation</TooltipContent>
+                </Tooltip>
+              </div>
+              {installation && (
+                <div className="flex gap-2">
+                  <Input
+                    value={labelDraft}
+                    onChange={(e) => setLabelDraft(e.target.value)}
+                    placeholder={
+                      registry.installs.find((i) => i.id === installation.id)?.label ??
+                      "Label"
+                    }
+                    className="flex-1"
+                  />
+                  <Button
+                    onClick={handleRenameInstall}
+                    variant="outline"
+                    disabled={!labelDraft.trim()}
+                  >
+                    <Pencil className="mr-2 h-4 w-4" />
+                    Rename
+                  </Button>
+                </div>
+              )}
+            </div>
+          )}
+
           {/* Auto-detected paths dropdown */}
           {detectedPaths.length > 0 && (
             <div className="space-y-2">
@@ -175,16 +405,19 @@ export function GameTab() {
                   value={installPath}
                   onValueChange={(value) => {
                     setInstallPath(value);
-                    validateAndLoadInstallation(value);
+                    registerAndLoad(
+                      value,
+                      detectedPaths.find((d) => d.path === value)?.store
+                    );
                   }}
                 >
                   <SelectTrigger>
                     <SelectValue placeholder="Select detected installation" />
                   </SelectTrigger>
                   <SelectContent>
-                    {detectedPaths.map((path) => (
-                      <SelectItem key={path} value={path}>
-                        {path}
+                    {detectedPaths.map((detected) => (
+                      <SelectItem key={detected.path} value={detected.path}>
+                        [{STORE_LABELS[detected.store]}] {detected.path} ({detected.confidence}%)
                       </SelectItem>
                     ))}
                   </SelectContent>
@@ -230,6 +463,26 @@ export function GameTab() {
             </Tooltip>
           </div>
 
+          {probe && probe.confidence < LOW_CONFIDENCE && (
+            <Alert>
+              <AlertCircle className="h-4 w-4" />
+              <AlertTitle>This may not be an FM26 folder ({probe.confidence}% match)</AlertTitle>
+              <AlertDescription>
+                Missing: {probe.missing.map((e) => EVIDENCE_LABELS[e]).join(", ")}
+              </AlertDescription>
+            </Alert>
+          )}
+
+          {versionCheck?.warning && (
+            <Alert>
+              <AlertCircle className="h-4 w-4" />
+              <AlertTitle>Game updated</AlertTitle>
+              <AlertDescription>
+                {versionCheck.warning}. Consider repairing the Stadium Pack.
+              </AlertDescription>
+            </Alert>
+          )}
+
           {error && (
             <Alert variant="destructive">
               <AlertCircle className="h-4 w-4" />
@@ -265,6 +518,17 @@ export function GameTab() {
             <Download className="mr-2 h-4 w-4" />
             {bepInExInstalled ? "Repair Stadium Pack" : "Install Stadium Pack"}
           </Button>
+          {bepInExInstalled && (
+            <Button
+              onClick={handleUninstall}
+              disabled={isUninstalling}
+              variant="outline"
+              className="mt-2 w-full"
+            >
+              <Trash2 className="mr-2 h-4 w-4" />
+              {isUninstalling ? "Uninstalling..." : "Uninstall Stadium Pack"}
+            </Button>
+          )}
           {!installation && (
             <p className="mt-2 text-sm text-muted-foreground">
               Select your FM26 installation directory first
@@ -283,27 +547,44 @@ export function GameTab() {
         />
       )}
 
+      <StadiumPackDialog
+        review={stadiumPack.review}
+        onLayoutPrefixChange={stadiumPack.setLayoutPrefix}
+        onConfirm={stadiumPack.confirm}
+        onCancel={stadiumPack.cancelReview}
+      />
+
       {/* Custom Stadiums Install */}
       {installation && bepInExInstalled && (
         <Card>
           <CardHeader>
             <CardTitle>Custom Stadiums</CardTitle>
             <CardDescription>
-              Install custom stadium bundles from a zip file
+              Install custom stadium bundles from a .zip, .7z or .tar archive
             </CardDescription>
           </CardHeader>
           <CardContent>
             <Button
-              onClick={handleInstallCustomStadiums}
-              disabled={isInstallingStadiums}
+              onClick={stadiumPack.pickAndInstall}
+              disabled={stadiumPack.isInstalling}
               variant="outline"
               className="w-full"
             >
               <FileArchive className="mr-2 h-4 w-4" />
-              {isInstallingStadiums
-                ? "Installing Stadiums..."
+              {stadiumPack.isInstalling
+                ? `Installing Stadiums... ${stadiumPack.percent}%`
                 : "Select & Install Custom Stadiums Zip"}
             </Button>
+            {stadiumPack.isInstalling && (
+              <Button
+                onClick={stadiumPack.cancelInstall}
+                variant="ghost"
+                size="sm"
+                className="mt-2 w-full"
+              >
+                Cancel Install
+              </Button>
+            )}
             <p className="mt-2 text-sm text-muted-foreground">
               Extracts .bundle files to BepInEx/plugins/CustomStadium/
             </p>
@@ -320,7 +601,27 @@ export function GameTab() {
               Status of installed BepInEx plugins
             </CardDescription>
           </CardHeader>
-          <CardContent>
+          <CardContent className="space-y-4">
+            {protonBlocked && (
+              <Alert>
+                <AlertCircle className="h-4 w-4" />
+                <AlertTitle>BepInEx won't load under Proton</AlertTitle>
+                <AlertDescription className="space-y-2">
+                  <p>
+                    Steam's launch options for FM26 need{" "}
+                    <code>WINEDLLOVERRIDES="winhttp=n,b" %command%</code>. Close Steam before applying.
+                  </p>
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={handleApplyProtonLaunchOptions}
+                    disabled={isApplyingProton}
+                  >
+                    {isApplyingProton ? "Applying..." : "Set Launch Options"}
+                  </Button>
+                </AlertDescription>
+              </Alert>
+            )}
             <Table>
               <TableHeader>
                 <TableRow>
@@ -331,12 +632,25 @@ export function GameTab() {
               </TableHeader>
               <TableBody>
                 {plugins.map((plugin) => (
-                  <TableRow key={plugin.name}>
-                    <TableCell className="font-medium">{plugin.name}</TableCell>
+                  <TableRow key={plugin.guid ?? plugin.path}>
+                    <TableCell className="font-medium">
+                      {plugin.name}
+                      {plugin.version && (
+                        <span className="ml-2 text-xs text-muted-foreground">v{plugin.version}</span>
+                      )}
+                    </TableCell>
                     <TableCell>
-                      <Badge variant={plugin.installed ? "success" : "destructive"}>
-                        {plugin.installed ? "Installed" : "Missing"}
+                      <Badge variant={PLUGIN_STATE_BADGES[plugin.state].variant}>
+                        {PLUGIN_STATE_BADGES[plugin.state].label}
                       </Badge>
+                      {plugin.dependencies
+                        .filter((d) => !d.soft && !d.satisfied)
+                        .map((d) => (
+                          <div key={d.guid} className="text-xs text-muted-foreground mt-1">
+                            Needs {d.guid}
+                            {d.min_version && ` ${d.min_version}+`}
+                          </div>
+                        ))}
                     </TableCell>
                     <TableCell className="hidden md:table-cell text-muted-foreground text-xs">
                       {plugin.path}
@@ -355,6 +669,25 @@ export function GameTab() {
           </CardContent>
         </Card>
       )}
+
+      {/* Play */}
+      {installation && (
+        <GameSessionCard installation={installation} onSessionEnd={handleRefresh} />
+      )}
+
+      {/* Doctor */}
+      {installation && (
+        <DiagnosisCard
+          installation={installation}
+          onInstallPack={() => setShowInstallDialog(true)}
+          onFixed={handleRefresh}
+        />
+      )}
+
+      {/* Backups */}
+      {installation && (
+        <BackupsCard installation={installation} onRestoreComplete={handleInstallComplete} />
+      )}
     </div>
   );
 }
@@ -13,8 +13,6 @@ import { RefreshCw, AlertCircle, Trash2, FileText, Search, Download, Play, Pause
 import { Tooltip, TooltipContent, TooltipTrigger } from "@/components/ui/tooltip";
 import type { Fm26Installation, LogInfo } from "@/types";
 
-const STORAGE_KEY = "fm26_install_path";
-
 export function LogsTab() {
   const [installation, setInstallation] = useState<Fm26Installation | null>(null);
   const [logContent, setLogContent] = useState<string>("");
@@ -28,12 +26,9 @@ export function LogsTab() {
   const scrollRef = useRef<HTMLDivElement>(null);
   const intervalRef = useRef<ReturnType<typeof setInterval> | null>(null);
 
-  // Load installation on mount
+  // Load the default installation on mount
   useEffect(() => {
-    const savedPath = localStorage.getItem(STORAGE_KEY);
-    if (savedPath) {
-      loadInstallation(savedPath);
-    }
+    loadInstallation();
   }, []);
 
   // Auto-refresh effect
@@ -66,16 +61,16 @@ export function LogsTab() {
     };
   }, []);
 
-  const loadInstallation = async (path: string) => {
+  const loadInstallation = async () => {
     setIsLoading(true);
     setError(null);
 
     try {
-      const install = await invoke<Fm26Installation>("inspect_fm26_install", {
-        rootPath: path,
-      });
+      const install = await invoke<Fm26Installation | null>("get_default_install");
       setInstallation(install);
-      await loadData(install, false);
+      if (install) {
+        await loadData(install, false);
+      }
     } catch (err) {
       setError(String(err));
       setInstallation(null);
@@ -87,8 +82,8 @@ export function LogsTab() {
   const loadData = async (install: Fm26Installation, silent: boolean = false) => {
     try {
       const [content, info] = await Promise.all([
-        invoke<string>("read_log", { install }),
-        invoke<LogInfo>("get_log_info", { install }),
+        invoke<string>("read_log", { installId: install.id }),
+        invoke<LogInfo>("get_log_info", { installId: install.id }),
       ]);
       setLogContent(content);
       setLogInfo(info);
@@ -114,7 +109,7 @@ export function LogsTab() {
     if (!installation) return;
 
     try {
-      await invoke("clear_log", { install: installation });
+      await invoke("clear_log", { installId: installation.id });
       setLogContent("");
       await loadData(installation);
     } catch (err) {
@@ -1,7 +1,6 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-shell";
-import { open as openDialog } from "@tauri-apps/plugin-dialog";
 import { toast } from "sonner";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
@@ -10,12 +9,13 @@ import { Alert, AlertDescription, AlertTitle } from "@/components/ui/alert";
 import { Badge } from "@/components/ui/badge";
 import { Table, TableBody, TableCell, TableHead, TableHeader, TableRow } from "@/components/ui/table";
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
-import { RefreshCw, AlertCircle, FolderOpen, Plus, Trash2, Save, FileArchive } from "lucide-react";
+import { RefreshCw, AlertCircle, FolderOpen, Plus, Trash2, Save, FileArchive, X } from "lucide-react";
 import { Tooltip, TooltipContent, TooltipTrigger } from "@/components/ui/tooltip";
+import { StadiumPackDialog } from "@/components/StadiumPackDialog";
+import { useStadiumPackInstall } from "@/hooks/useStadiumPackInstall";
+import { describeError, invalidNames } from "@/lib/utils";
 import type { Fm26Installation, BundleInfo, TeamMapping } from "@/types";
 
-const STORAGE_KEY = "fm26_install_path";
-
 export function StadiumsTab() {
   const [installation, setInstallation] = useState<Fm26Installation | null>(null);
   const [bundles, setBundles] = useState<BundleInfo[]>([]);
@@ -24,27 +24,27 @@ export function StadiumsTab() {
   const [error, setError] = useState<string | null>(null);
   const [isLoading, setIsLoading] = useState(false);
   const [isSaving, setIsSaving] = useState(false);
-  const [isInstallingStadiums, setIsInstallingStadiums] = useState(false);
   const [validationErrors, setValidationErrors] = useState<string[]>([]);
+  const [invalidRows, setInvalidRows] = useState<Set<number>>(new Set());
+  const stadiumPack = useStadiumPackInstall(installation, () =>
+    installation ? loadData(installation) : undefined
+  );
 
-  // Load installation on mount
+  // Load the default installation on mount
   useEffect(() => {
-    const savedPath = localStorage.getItem(STORAGE_KEY);
-    if (savedPath) {
-      loadInstallation(savedPath);
-    }
+    loadInstallation();
   }, []);
 
-  const loadInstallation = async (path: string) => {
+  const loadInstallation = async () => {
     setIsLoading(true);
     setError(null);
 
     try {
-      const install = await invoke<Fm26Installation>("inspect_fm26_install", {
-        rootPath: path,
-      });
+      const install = await invoke<Fm26Installation | null>("get_default_install");
       setInstallation(install);
-      await loadData(install);
+      if (install) {
+        await loadData(install);
+      }
     } catch (err) {
       setError(String(err));
       setInstallation(null);
@@ -56,8 +56,8 @@ export function StadiumsTab() {
   const loadData = async (install: Fm26Installation) => {
     try {
       const [bundleList, mappingList] = await Promise.all([
-        invoke<BundleInfo[]>("list_bundles", { install }),
-        invoke<TeamMapping[]>("read_team_mappings", { install }),
+        invoke<BundleInfo[]>("list_bundles", { installId: install.id }),
+        invoke<TeamMapping[]>("read_team_mappings", { installId: install.id }),
       ]);
       setBundles(bundleList);
       setMappings(mappingList);
@@ -115,6 +115,7 @@ export function StadiumsTab() {
     }
     setMappings(newMappings);
     validateMappings(newMappings, bundles);
+    setInvalidRows(new Set());
   };
 
   const handleAddMapping = () => {
@@ -131,6 +132,7 @@ export function StadiumsTab() {
     const newMappings = mappings.filter((_, i) => i !== index);
     setMappings(newMappings);
     validateMappings(newMappings, bundles);
+    setInvalidRows(new Set());
   };
 
   const handleSave = async () => {
@@ -141,13 +143,18 @@ export function StadiumsTab() {
 
     try {
       await invoke("write_team_mappings", {
-        install: installation,
+        installId: installation.id,
         mappings,
       });
       toast.success("Team mappings saved successfully!");
       setOriginalMappings(mappings);
+      setInvalidRows(new Set());
     } catch (err) {
-      toast.error("Failed to save mappings", { description: String(err) });
+      // Highlight the rows the backend rejected
+      setInvalidRows(
+        new Set(invalidNames(err).flatMap((i) => (i.row === null ? [] : [i.row])))
+      );
+      toast.error("Failed to save mappings", { description: describeError(err) });
     } finally {
       setIsSaving(false);
     }
@@ -156,38 +163,10 @@ export function StadiumsTab() {
   const handleRevert = () => {
     setMappings(originalMappings);
     validateMappings(originalMappings, bundles);
+    setInvalidRows(new Set());
     setError(null);
   };
 
-  const handleInstallCustomStadiums = async () => {
-    if (!installation) return;
-
-    try {
-      const selected = await openDialog({
-        multiple: false,
-        title: "Select Custom Stadiums Zip File",
-        filters: [{ name: "Zip Archives", extensions: ["zip"] }],
-      });
-
-      if (selected && typeof selected === "string") {
-        setIsInstallingStadiums(true);
-        setError(null);
-
-        const filesExtracted = await invoke<number>("install_custom_stadiums_pack", {
-          zipPath: selected,
-          install: installation,
-        });
-
-        toast.success("Custom stadiums installed!", { description: `${filesExtracted} files extracted` });
-        await loadData(installation);
-      }
-    } catch (err) {
-      toast.error("Failed to install stadiums", { description: String(err) });
-    } finally {
-      setIsInstallingStadiums(false);
-    }
-  };
-
   const hasChanges = JSON.stringify(mappings) !== JSON.stringify(originalMappings);
 
   if (!installation) {
@@ -206,6 +185,13 @@ export function StadiumsTab() {
 
   return (
     <div className="space-y-4 pt-4">
+      <StadiumPackDialog
+        review={stadiumPack.review}
+        onLayoutPrefixChange={stadiumPack.setLayoutPrefix}
+        onConfirm={stadiumPack.confirm}
+        onCancel={stadiumPack.cancelReview}
+      />
+
       {error && (
         <Alert variant="destructive">
           <AlertCircle className="h-4 w-4" />
@@ -269,7 +255,10 @@ export function StadiumsTab() {
               </TableHeader>
               <TableBody>
                 {mappings.map((mapping, index) => (
-                  <TableRow key={index}>
+                  <TableRow
+                    key={index}
+                    className={invalidRows.has(index) ? "bg-destructive/10" : undefined}
+                  >
                     <TableCell>
                       <Input
                         type="number"
@@ -352,17 +341,25 @@ export function StadiumsTab() {
             <span>Stadium Bundles</span>
             <div className="flex gap-2">
               <Button
-                onClick={handleInstallCustomStadiums}
-                disabled={isInstallingStadiums}
+                onClick={stadiumPack.pickAndInstall}
+                disabled={stadiumPack.isInstalling}
                 size="sm"
               >
-                {isInstallingStadiums ? (
+                {stadiumPack.isInstalling ? (
                   <RefreshCw className="mr-2 h-4 w-4 animate-spin" />
                 ) : (
                   <FileArchive className="mr-2 h-4 w-4" />
                 )}
-                {isInstallingStadiums ? "Installing..." : "Install from Zip"}
+                {stadiumPack.isInstalling
+                  ? `Installing... ${stadiumPack.percent}%`
+                  : "Install from Zip"}
               </Button>
+              {stadiumPack.isInstalling && (
+                <Button onClick={stadiumPack.cancelInstall} variant="outline" size="sm">
+                  <X className="mr-2 h-4 w-4" />
+                  Cancel
+                </Button>
+              )}
               <Button onClick={handleOpenFolder} variant="outline" size="sm">
                 <FolderOpen className="mr-2 h-4 w-4" />
                 Open Folder
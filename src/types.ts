@@ -1,6 +1,7 @@
 // Types shared between frontend and Tauri backend
 
 export interface Fm26Installation {
+  id: string; // registry id
   root_path: string;
   bep_in_ex_path: string;
   plugins_path: string;
@@ -10,10 +11,89 @@ export interface Fm26Installation {
   log_path: string;
 }
 
+export type GameStore = "steam" | "epic" | "manual";
+
+export type InstallEvidence =
+  | "fm_exe"
+  | "unity_data_folder"
+  | "unity_player_dll"
+  | "game_assembly_dll"
+  | "steam_app_id";
+
+export interface DetectedInstall {
+  path: string;
+  store: GameStore;
+  confidence: number; // 0-100
+  found: InstallEvidence[];
+  missing: InstallEvidence[];
+  data_folder: string | null;
+  steam_app_id: string | null;
+}
+
+export interface RegisteredInstall {
+  id: string;
+  label: string;
+  root_path: string;
+  store: GameStore;
+  added_at: string;
+  last_verified: string | null;
+  valid: boolean;
+  bepinex_version: string | null;
+  pack_sha256: string | null;
+  pack_installed_at: string | null;
+}
+
+export interface InstallRegistry {
+  installs: RegisteredInstall[];
+  default_id: string | null;
+}
+
+export interface GameVersion {
+  steam_build_id: string | null;
+  unity_version: string | null;
+  game_assembly_sha256: string | null;
+  game_assembly_size: number | null;
+}
+
+export interface GameVersionCheck {
+  current: GameVersion;
+  installed_against: GameVersion | null;
+  recorded_at: string | null;
+  game_updated: boolean;
+  warning: string | null;
+}
+
+export type PluginState = "installed" | "missing" | "proton_blocked" | "missing_dependency";
+
+export interface PluginDependency {
+  guid: string;
+  min_version: string | null;
+  soft: boolean;
+  satisfied: boolean;
+}
+
 export interface PluginStatus {
   name: string;
   path: string;
   installed: boolean;
+  state: PluginState;
+  guid: string | null;
+  version: string | null;
+  dependencies: PluginDependency[];
+}
+
+export interface ProtonLaunchOptions {
+  localconfig_path: string;
+  launch_options: string | null;
+  has_winhttp_override: boolean;
+  backup_path: string | null;
+}
+
+export interface ProtonStatus {
+  uses_proton: boolean;
+  prefix_path: string | null;
+  launch_options: ProtonLaunchOptions[];
+  override_configured: boolean;
 }
 
 export interface BundleInfo {
@@ -88,11 +168,19 @@ export interface AdboardsConfig {
   disable_adboards: boolean;
 }
 
+export type BepInExFlavor = "bepinex5" | "bepinex6_mono" | "bepinex6_il2cpp" | "unknown";
+
 export interface BepInExStatus {
   installed: boolean;
   path: string;
   has_plugins: boolean;
   plugin_count: number;
+  core_version: string | null;
+  flavor: BepInExFlavor;
+  doorstop_version: string | null;
+  has_winhttp_dll: boolean;
+  has_doorstop_config: boolean;
+  loader_warnings: string[];
 }
 
 export interface DownloadProgress {
@@ -101,3 +189,247 @@ export interface DownloadProgress {
   speed_bps: number;
   percent: number;
 }
+
+export interface InstallProgress {
+  entries_done: number;
+  entries_total: number;
+  bytes_done: number;
+  bytes_total: number;
+  current_file: string;
+  percent: number;
+}
+
+export type PackSource =
+  | { kind: "bundled" }
+  | { kind: "r2" }
+  | { kind: "url"; url: string }
+  | { kind: "local_file"; path: string };
+
+export interface ManifestFile {
+  path: string;
+  size: number;
+  sha256: string;
+}
+
+export interface InstallManifest {
+  source: PackSource;
+  pack_sha256: string;
+  installed_at: string;
+  files: ManifestFile[];
+  user_data: string[]; // carried over from the replaced install
+}
+
+export interface UninstallReport {
+  removed_files: string[];
+  removed_dirs: string[];
+  failed: string[];
+  used_manifest: boolean;
+  export_path: string | null;
+  left_behind: string[]; // files under BepInEx/ the manifest did not list
+}
+
+export type BackupLocation =
+  | { kind: "game_folder" }
+  | { kind: "app_data" }
+  | { kind: "custom"; path: string };
+
+export interface BackupSettings {
+  retention: number;
+  location: BackupLocation;
+}
+
+export interface BackupInfo {
+  name: string;
+  path: string;
+  created_at: string | null;
+  size_bytes: number;
+  plugins: string[];
+  external: boolean;
+}
+
+export type PackKind = "bepinex" | "custom_stadiums";
+
+export type PlannedAction =
+  | "new"
+  | "overwrite_identical"
+  | "overwrite_different"
+  | "unchanged"
+  | "moved_to_backup"
+  | "merged";
+
+export interface PlannedFile {
+  path: string;
+  action: PlannedAction;
+  size: number;
+}
+
+export interface PlanTotal {
+  action: PlannedAction;
+  files: number;
+  bytes: number;
+}
+
+export interface InstallPlan {
+  target_path: string;
+  files: PlannedFile[];
+  totals: PlanTotal[];
+}
+
+export interface MigrationReport {
+  migrated: string[];
+  not_migrated: string[];
+}
+
+export type PreflightCheck =
+  | "disk_space"
+  | "write_permission"
+  | "read_only_file"
+  | "game_running"
+  | "unsafe_archive"
+  | "leftover_staging";
+
+export interface PreflightIssue {
+  check: PreflightCheck;
+  message: string;
+  path: string | null;
+}
+
+export interface PreflightReport {
+  blockers: PreflightIssue[];
+  warnings: PreflightIssue[];
+  required_bytes: number;
+  available_bytes: number | null;
+}
+
+export type LayoutAnchor =
+  | "bep_in_ex_folder"
+  | "winhttp_dll"
+  | "custom_stadium_folder"
+  | "bundle_files"
+  | "archive_root"
+  | "user_override";
+
+export interface ArchiveLayout {
+  anchor: LayoutAnchor;
+  strip_prefix: string;
+  mapped_entries: number;
+  skipped_entries: string[];
+}
+
+export type RouteDestination =
+  | "custom_stadium"
+  | "stadium_injection"
+  | "audio_inject"
+  | "config";
+
+export interface RoutedDestination {
+  destination: RouteDestination;
+  path: string;
+  files: number;
+  mappings_added: number;
+  mappings_kept: number;
+}
+
+export interface StadiumPackReport {
+  destinations: RoutedDestination[];
+  skipped: string[];
+  identical: string[];
+  kept: string[];
+  renamed: RenamedBundle[];
+}
+
+export interface RenamedBundle {
+  from: string;
+  to: string;
+}
+
+export interface BundleConflict {
+  file: string;
+  existing_size: number;
+  incoming_size: number;
+  rename_to: string;
+}
+
+export type ConflictAction = "overwrite" | "keep" | "rename";
+
+export interface ConflictResolution {
+  file: string;
+  action: ConflictAction;
+}
+
+export type NameField = "folder_name" | "bundle_file" | "team_key";
+
+export type NameProblem =
+  | "empty"
+  | "separator"
+  | "relative_segment"
+  | "absolute"
+  | "reserved"
+  | "control_character"
+  | "mapping_syntax"
+  | "stream_separator"
+  | "trailing_dot_or_space";
+
+export interface InvalidName {
+  field: NameField;
+  row: number | null; // index into the submitted list
+  value: string;
+  problem: NameProblem;
+  message: string;
+}
+
+/** Error of commands taking names: rejected names, or a plain message */
+export type NameError = { invalid: InvalidName[] } | string;
+
+export type DiagnosisCheck =
+  | "loader"
+  | "plugins"
+  | "configs"
+  | "team_mappings"
+  | "default_bundle"
+  | "audio_mappings"
+  | "log"
+  | "staging";
+
+export type DiagnosisSeverity = "ok" | "info" | "warning" | "error";
+
+/** A fix diagnose_install can suggest; install_pack is handled by the install dialog */
+export type FixAction =
+  | { kind: "install_pack" }
+  | { kind: "apply_proton_launch_options" }
+  | { kind: "remove_team_mappings"; team_ids: number[] }
+  | { kind: "disable_replace_all_stadiums" }
+  | { kind: "remove_audio_mappings"; team_keys: string[] }
+  | { kind: "set_aside_config"; file: string };
+
+export interface DiagnosisFinding {
+  check: DiagnosisCheck;
+  severity: DiagnosisSeverity;
+  message: string;
+  details: string[];
+  fix: FixAction | null;
+}
+
+export interface DiagnosisReport {
+  findings: DiagnosisFinding[]; // worst first
+  errors: number;
+  warnings: number;
+  checked_at: string;
+}
+
+export type LaunchMethod = "steam" | "direct";
+
+/** Payload of the game-exited event */
+export interface GameSessionSummary {
+  install_id: string;
+  method: LaunchMethod;
+  started_at: string;
+  ended_at: string;
+  game_detected: boolean; // false when the game never showed up after a Steam launch
+  exit_code: number | null; // only known for direct launches
+  log_updated: boolean; // false when BepInEx did not write LogOutput.log this session
+  plugins_loaded: string[];
+  error_count: number;
+  errors: string[]; // the newest error lines
+  stadium_injections: number;
+}